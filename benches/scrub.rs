@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: benches/scrub.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::hint::black_box;
+use std::io::{self, BufReader};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rustscrub::scrub::{scrub_stream, scrub_text, ChangeDetail, ScrubSettings};
+
+fn corpus() -> String {
+    [include_str!("../src/scrub.rs"), include_str!("../src/filter.rs"), include_str!("../src/lexer.rs")].concat().repeat(20)
+}
+
+fn scrub(c: &mut Criterion) {
+    let input = corpus();
+    let lines = ScrubSettings {
+        change_detail: ChangeDetail::Lines,
+        ..ScrubSettings::default()
+    };
+    let full = ScrubSettings::default();
+
+    let mut group = c.benchmark_group("scrub");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("stream", |b| {
+        b.iter(|| scrub_stream(&mut BufReader::new(input.as_bytes()), Some(&mut io::sink()), black_box(&lines)).unwrap())
+    });
+    group.bench_function("stream_full_detail", |b| {
+        b.iter(|| scrub_stream(&mut BufReader::new(input.as_bytes()), Some(&mut io::sink()), black_box(&full)).unwrap())
+    });
+    group.bench_function("text", |b| b.iter(|| scrub_text(black_box(&input), &lines).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, scrub);
+criterion_main!(benches);
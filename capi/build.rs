@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: capi/build.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("Failed to read cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate C header")
+        .write_to_file(crate_dir.join("include").join("rustscrub.h"));
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
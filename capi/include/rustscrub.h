@@ -0,0 +1,108 @@
+/* SPDX-License-Identifier: MIT */
+/* Generated by cbindgen from capi/src/lib.rs. Do not edit. */
+
+#ifndef RUSTSCRUB_H
+#define RUSTSCRUB_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define RUSTSCRUB_ABI_VERSION 1
+
+#define RUSTSCRUB_OK 0
+
+#define RUSTSCRUB_ERR_NULL_ARGUMENT 1
+
+#define RUSTSCRUB_ERR_INVALID_UTF8 2
+
+#define RUSTSCRUB_ERR_INVALID_OPTIONS 3
+
+#define RUSTSCRUB_ERR_SCRUB_FAILED 4
+
+#define RUSTSCRUB_ERR_PANIC 5
+
+#define RUSTSCRUB_LEVEL_MINIMAL 0
+
+#define RUSTSCRUB_LEVEL_STANDARD 1
+
+#define RUSTSCRUB_LEVEL_AGGRESSIVE 2
+
+#define RUSTSCRUB_CATEGORY_DOC (1 << 0)
+
+#define RUSTSCRUB_CATEGORY_LICENSE (1 << 1)
+
+#define RUSTSCRUB_CATEGORY_TODO (1 << 2)
+
+#define RUSTSCRUB_CATEGORY_CODE (1 << 3)
+
+#define RUSTSCRUB_CATEGORY_DIRECTIVE (1 << 4)
+
+#define RUSTSCRUB_CATEGORY_PROSE (1 << 5)
+
+#define RUSTSCRUB_KEEP_ALL_BLANK_LINES -1
+
+// Scrub options. Initialize with `rustscrub_options_default` before changing fields.
+// `min_length`/`max_length` of 0 mean "no limit", `strip_blank_lines` of
+// `RUSTSCRUB_KEEP_ALL_BLANK_LINES` leaves blank lines untouched.
+typedef struct RustscrubOptions {
+  uint32_t abi_version;
+  size_t header_lines;
+  uint32_t level;
+  int64_t strip_blank_lines;
+  bool keep_links;
+  bool keep_regions;
+  bool keep_test_annotations;
+  bool strip_directives;
+  bool keep_license;
+  size_t min_length;
+  size_t max_length;
+  uint32_t keep_categories;
+} RustscrubOptions;
+
+// Scrub result. All strings are NUL-terminated UTF-8 owned by the library;
+// release them with `rustscrub_output_free`.
+typedef struct RustscrubOutput {
+  char *text;
+  size_t text_len;
+  char *changes_json;
+  size_t removed_comments;
+  char *error;
+} RustscrubOutput;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Fills `options` with the default settings of the current ABI version.
+//
+// # Safety
+// `options` must be null or point to writable memory for a `RustscrubOptions`.
+void rustscrub_options_default(struct RustscrubOptions *options);
+
+// Scrubs `len` bytes of UTF-8 source text. `options` may be null to use the defaults.
+// On return, `output` holds either the result or an error message; it must be
+// released with `rustscrub_output_free` in both cases. Returns `RUSTSCRUB_OK` on success.
+//
+// # Safety
+// `input` must point to `len` readable bytes, `options` must be null or point to an
+// initialized `RustscrubOptions`, and `output` must point to writable memory for a
+// `RustscrubOutput`.
+int32_t rustscrub_scrub_utf8(const char *input,
+                             size_t len,
+                             const struct RustscrubOptions *options,
+                             struct RustscrubOutput *output);
+
+// Releases all strings owned by `output` and resets them to null.
+//
+// # Safety
+// `output` must be null or point to a `RustscrubOutput` filled by `rustscrub_scrub_utf8`.
+void rustscrub_output_free(struct RustscrubOutput *output);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUSTSCRUB_H */
@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: capi/src/lib.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use rustscrub::classify::CommentCategory;
+use rustscrub::inventory::InventoryEntry;
+use rustscrub::options::{Level, ScrubOptions};
+use rustscrub::scrub::scrub_text;
+
+pub const RUSTSCRUB_ABI_VERSION: u32 = 1;
+
+pub const RUSTSCRUB_OK: i32 = 0;
+pub const RUSTSCRUB_ERR_NULL_ARGUMENT: i32 = 1;
+pub const RUSTSCRUB_ERR_INVALID_UTF8: i32 = 2;
+pub const RUSTSCRUB_ERR_INVALID_OPTIONS: i32 = 3;
+pub const RUSTSCRUB_ERR_SCRUB_FAILED: i32 = 4;
+pub const RUSTSCRUB_ERR_PANIC: i32 = 5;
+
+pub const RUSTSCRUB_LEVEL_MINIMAL: u32 = 0;
+pub const RUSTSCRUB_LEVEL_STANDARD: u32 = 1;
+pub const RUSTSCRUB_LEVEL_AGGRESSIVE: u32 = 2;
+
+pub const RUSTSCRUB_CATEGORY_DOC: u32 = 1 << 0;
+pub const RUSTSCRUB_CATEGORY_LICENSE: u32 = 1 << 1;
+pub const RUSTSCRUB_CATEGORY_TODO: u32 = 1 << 2;
+pub const RUSTSCRUB_CATEGORY_CODE: u32 = 1 << 3;
+pub const RUSTSCRUB_CATEGORY_DIRECTIVE: u32 = 1 << 4;
+pub const RUSTSCRUB_CATEGORY_PROSE: u32 = 1 << 5;
+
+pub const RUSTSCRUB_KEEP_ALL_BLANK_LINES: i64 = -1;
+
+/// Scrub options. Initialize with `rustscrub_options_default` before changing fields.
+/// `min_length`/`max_length` of 0 mean "no limit", `strip_blank_lines` of
+/// `RUSTSCRUB_KEEP_ALL_BLANK_LINES` leaves blank lines untouched.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RustscrubOptions {
+    pub abi_version: u32,
+    pub header_lines: usize,
+    pub level: u32,
+    pub strip_blank_lines: i64,
+    pub keep_links: bool,
+    pub keep_regions: bool,
+    pub keep_test_annotations: bool,
+    pub strip_directives: bool,
+    pub keep_license: bool,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub keep_categories: u32,
+}
+
+/// Scrub result. All strings are NUL-terminated UTF-8 owned by the library;
+/// release them with `rustscrub_output_free`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RustscrubOutput {
+    pub text: *mut c_char,
+    pub text_len: usize,
+    pub changes_json: *mut c_char,
+    pub removed_comments: usize,
+    pub error: *mut c_char,
+}
+
+const CATEGORY_BITS: [(u32, CommentCategory); 6] = [
+    (RUSTSCRUB_CATEGORY_DOC, CommentCategory::Doc),
+    (RUSTSCRUB_CATEGORY_LICENSE, CommentCategory::License),
+    (RUSTSCRUB_CATEGORY_TODO, CommentCategory::Todo),
+    (RUSTSCRUB_CATEGORY_CODE, CommentCategory::Code),
+    (RUSTSCRUB_CATEGORY_DIRECTIVE, CommentCategory::Directive),
+    (RUSTSCRUB_CATEGORY_PROSE, CommentCategory::Prose),
+];
+
+impl Default for RustscrubOptions {
+    fn default() -> Self {
+        RustscrubOptions {
+            abi_version: RUSTSCRUB_ABI_VERSION,
+            header_lines: 0,
+            level: RUSTSCRUB_LEVEL_STANDARD,
+            strip_blank_lines: RUSTSCRUB_KEEP_ALL_BLANK_LINES,
+            keep_links: false,
+            keep_regions: false,
+            keep_test_annotations: false,
+            strip_directives: false,
+            keep_license: false,
+            min_length: 0,
+            max_length: 0,
+            keep_categories: 0,
+        }
+    }
+}
+
+impl RustscrubOptions {
+    fn to_options(self) -> Result<ScrubOptions, String> {
+        if self.abi_version != RUSTSCRUB_ABI_VERSION {
+            return Err(format!("Unsupported options ABI version {}", self.abi_version));
+        }
+        let level = match self.level {
+            RUSTSCRUB_LEVEL_MINIMAL => Level::Minimal,
+            RUSTSCRUB_LEVEL_STANDARD => Level::Standard,
+            RUSTSCRUB_LEVEL_AGGRESSIVE => Level::Aggressive,
+            other => return Err(format!("Unknown level {}", other)),
+        };
+        Ok(ScrubOptions {
+            header_lines: self.header_lines,
+            level,
+            strip_blank_lines: usize::try_from(self.strip_blank_lines).ok(),
+            keep_links: self.keep_links,
+            keep_regions: self.keep_regions,
+            keep_test_annotations: self.keep_test_annotations,
+            strip_directives: self.strip_directives,
+            keep_license: self.keep_license,
+            min_length: (self.min_length > 0).then_some(self.min_length),
+            max_length: (self.max_length > 0).then_some(self.max_length),
+            keep_categories: CATEGORY_BITS
+                .iter()
+                .filter(|(bit, _)| self.keep_categories & bit != 0)
+                .map(|(_, category)| *category)
+                .collect(),
+            ..ScrubOptions::default()
+        })
+    }
+}
+
+fn into_c_string(text: String) -> *mut c_char {
+    let bytes: Vec<u8> = text.into_bytes().into_iter().filter(|&b| b != 0).collect();
+    CString::new(bytes).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn scrub_to_output(input: &[u8], options: RustscrubOptions, output: &mut RustscrubOutput) -> i32 {
+    let text = match std::str::from_utf8(input) {
+        Ok(text) => text,
+        Err(e) => {
+            output.error = into_c_string(format!("Failed to decode input: {}", e));
+            return RUSTSCRUB_ERR_INVALID_UTF8;
+        }
+    };
+    let settings = match options.to_options() {
+        Ok(options) => options.settings(),
+        Err(e) => {
+            output.error = into_c_string(e);
+            return RUSTSCRUB_ERR_INVALID_OPTIONS;
+        }
+    };
+    let (scrubbed, report) = match scrub_text(text, &settings) {
+        Ok(result) => result,
+        Err(e) => {
+            output.error = into_c_string(e);
+            return RUSTSCRUB_ERR_SCRUB_FAILED;
+        }
+    };
+    let entries: Vec<InventoryEntry> = InventoryEntry::entries(&report.changes);
+    let scrubbed = scrubbed.replace('\0', "");
+    output.text_len = scrubbed.len();
+    output.text = into_c_string(scrubbed);
+    output.changes_json = into_c_string(serde_json::to_string(&entries).unwrap_or_default());
+    output.removed_comments = entries.len();
+    RUSTSCRUB_OK
+}
+
+/// Fills `options` with the default settings of the current ABI version.
+///
+/// # Safety
+/// `options` must be null or point to writable memory for a `RustscrubOptions`.
+#[no_mangle]
+pub unsafe extern "C" fn rustscrub_options_default(options: *mut RustscrubOptions) {
+    if !options.is_null() {
+        options.write(RustscrubOptions::default());
+    }
+}
+
+/// Scrubs `len` bytes of UTF-8 source text. `options` may be null to use the defaults.
+/// On return, `output` holds either the result or an error message; it must be
+/// released with `rustscrub_output_free` in both cases. Returns `RUSTSCRUB_OK` on success.
+///
+/// # Safety
+/// `input` must point to `len` readable bytes, `options` must be null or point to an
+/// initialized `RustscrubOptions`, and `output` must point to writable memory for a
+/// `RustscrubOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn rustscrub_scrub_utf8(
+    input: *const c_char,
+    len: usize,
+    options: *const RustscrubOptions,
+    output: *mut RustscrubOutput,
+) -> i32 {
+    if output.is_null() {
+        return RUSTSCRUB_ERR_NULL_ARGUMENT;
+    }
+    output.write(RustscrubOutput {
+        text: ptr::null_mut(),
+        text_len: 0,
+        changes_json: ptr::null_mut(),
+        removed_comments: 0,
+        error: ptr::null_mut(),
+    });
+    let output = &mut *output;
+    if input.is_null() && len > 0 {
+        output.error = into_c_string("Input is null".to_string());
+        return RUSTSCRUB_ERR_NULL_ARGUMENT;
+    }
+    let bytes = if len == 0 { &[][..] } else { slice::from_raw_parts(input.cast::<u8>(), len) };
+    let options = if options.is_null() { RustscrubOptions::default() } else { *options };
+    panic::catch_unwind(AssertUnwindSafe(|| scrub_to_output(bytes, options, output))).unwrap_or_else(|_| {
+        output.error = into_c_string("Scrubbing panicked".to_string());
+        RUSTSCRUB_ERR_PANIC
+    })
+}
+
+/// Releases all strings owned by `output` and resets them to null.
+///
+/// # Safety
+/// `output` must be null or point to a `RustscrubOutput` filled by `rustscrub_scrub_utf8`.
+#[no_mangle]
+pub unsafe extern "C" fn rustscrub_output_free(output: *mut RustscrubOutput) {
+    if output.is_null() {
+        return;
+    }
+    let output = &mut *output;
+    for field in [&mut output.text, &mut output.changes_json, &mut output.error] {
+        if !field.is_null() {
+            drop(CString::from_raw(*field));
+            *field = ptr::null_mut();
+        }
+    }
+    output.text_len = 0;
+    output.removed_comments = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::mem::MaybeUninit;
+
+    fn scrub(input: &str, options: Option<&RustscrubOptions>) -> (i32, RustscrubOutput) {
+        let mut output = MaybeUninit::<RustscrubOutput>::uninit();
+        let options = options.map_or(ptr::null(), |options| options as *const _);
+        let status = unsafe { rustscrub_scrub_utf8(input.as_ptr().cast(), input.len(), options, output.as_mut_ptr()) };
+        (status, unsafe { output.assume_init() })
+    }
+
+    #[test]
+    fn test_scrub_utf8_with_defaults() {
+        let (status, mut output) = scrub("fn f() {} // gone\n", None);
+        assert_eq!(status, RUSTSCRUB_OK);
+        assert_eq!(unsafe { CStr::from_ptr(output.text) }.to_str().unwrap(), "fn f() {} \n");
+        assert_eq!(output.removed_comments, 1);
+        assert!(output.error.is_null());
+        unsafe { rustscrub_output_free(&mut output) };
+        assert!(output.text.is_null() && output.changes_json.is_null());
+    }
+
+    #[test]
+    fn test_scrub_utf8_rejects_unknown_level() {
+        let mut options = RustscrubOptions::default();
+        unsafe { rustscrub_options_default(&mut options) };
+        options.level = 7;
+        let (status, mut output) = scrub("fn f() {}\n", Some(&options));
+        assert_eq!(status, RUSTSCRUB_ERR_INVALID_OPTIONS);
+        assert!(output.text.is_null());
+        assert!(!output.error.is_null());
+        unsafe { rustscrub_output_free(&mut output) };
+    }
+}
@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/adapters.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use crate::scrub::{ScrubReport, ScrubSettings, Scrubber};
+
+fn to_io_error(message: String) -> io::Error {
+    io::Error::other(message)
+}
+
+pub struct ScrubReader<R: Read> {
+    inner: BufReader<R>,
+    scrubber: Option<Scrubber>,
+    report: ScrubReport,
+    line: Vec<u8>,
+    output: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> ScrubReader<R> {
+    pub fn new(inner: R, settings: ScrubSettings) -> Self {
+        ScrubReader {
+            inner: BufReader::new(inner),
+            scrubber: Some(Scrubber::new(settings)),
+            report: ScrubReport::default(),
+            line: Vec::new(),
+            output: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn report(&self) -> &ScrubReport {
+        match &self.scrubber {
+            Some(scrubber) => scrubber.report(),
+            None => &self.report,
+        }
+    }
+
+    pub fn into_report(self) -> ScrubReport {
+        match self.scrubber {
+            Some(scrubber) => scrubber.report().clone(),
+            None => self.report,
+        }
+    }
+
+    fn fill_output(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.position = 0;
+        while self.output.is_empty() {
+            let Some(scrubber) = self.scrubber.as_mut() else {
+                return Ok(());
+            };
+            self.line.clear();
+            if self.inner.read_until(b'\n', &mut self.line)? == 0 {
+                let scrubber = self.scrubber.take().expect("scrubber is active until end of input");
+                self.report = scrubber.finish(&mut self.output).map_err(to_io_error)?;
+                return Ok(());
+            }
+            let line = std::str::from_utf8(&self.line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Failed to decode input line: {}", e)))?;
+            scrubber.push_line(line, &mut self.output).map_err(to_io_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ScrubReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.output.len() {
+            self.fill_output()?;
+        }
+        let available = &self.output[self.position..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+pub struct ScrubWriter<W: Write> {
+    inner: Option<W>,
+    scrubber: Option<Scrubber>,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> ScrubWriter<W> {
+    pub fn new(inner: W, settings: ScrubSettings) -> Self {
+        ScrubWriter {
+            inner: Some(inner),
+            scrubber: Some(Scrubber::new(settings)),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().expect("inner writer is present until finish")
+    }
+
+    fn push_pending(&mut self, start: usize, end: usize) -> io::Result<()> {
+        let (Some(scrubber), Some(inner)) = (self.scrubber.as_mut(), self.inner.as_mut()) else {
+            return Err(io::Error::other("ScrubWriter is already finished"));
+        };
+        let line = std::str::from_utf8(&self.pending[start..end])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Failed to decode input line: {}", e)))?;
+        scrubber.push_line(line, inner).map_err(to_io_error)
+    }
+
+    fn finish_scrub(&mut self) -> io::Result<ScrubReport> {
+        if !self.pending.is_empty() {
+            self.push_pending(0, self.pending.len())?;
+            self.pending.clear();
+        }
+        let (Some(scrubber), Some(inner)) = (self.scrubber.take(), self.inner.as_mut()) else {
+            return Err(io::Error::other("ScrubWriter is already finished"));
+        };
+        let report = scrubber.finish(inner).map_err(to_io_error)?;
+        inner.flush()?;
+        Ok(report)
+    }
+
+    pub fn finish(mut self) -> io::Result<(W, ScrubReport)> {
+        let report = self.finish_scrub()?;
+        let inner = self.inner.take().expect("inner writer is present until finish");
+        Ok((inner, report))
+    }
+}
+
+impl<W: Write> Write for ScrubWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut search_from = self.pending.len();
+        self.pending.extend_from_slice(buf);
+        let mut start = 0;
+        while let Some(offset) = self.pending[search_from..].iter().position(|&b| b == b'\n') {
+            let end = search_from + offset + 1;
+            self.push_pending(start, end)?;
+            start = end;
+            search_from = end;
+        }
+        self.pending.drain(..start);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write> Drop for ScrubWriter<W> {
+    fn drop(&mut self) {
+        if self.scrubber.is_some() {
+            let _ = self.finish_scrub();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrub::scrub_text;
+
+    #[test]
+    fn test_scrub_reader_matches_scrub_text() {
+        let input = "/// doc\nfn f() { /* a\n b */ g(); }\n\n// trailing";
+        let settings = ScrubSettings::default();
+        let mut reader = ScrubReader::new(input.as_bytes(), settings.clone());
+        let mut output = String::new();
+        let mut chunk = [0u8; 3];
+        loop {
+            let read = reader.read(&mut chunk).unwrap();
+            if read == 0 {
+                break;
+            }
+            output.push_str(std::str::from_utf8(&chunk[..read]).unwrap());
+        }
+        let (expected, expected_report) = scrub_text(input, &settings).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(reader.into_report().changes.len(), expected_report.changes.len());
+    }
+
+    #[test]
+    fn test_scrub_writer_handles_split_writes() {
+        let input = "fn f() { /* a\n b */ g(); } // x\nlet s = \"// ü\";\n// tail";
+        let settings = ScrubSettings::default();
+        let mut writer = ScrubWriter::new(Vec::new(), settings.clone());
+        for chunk in input.as_bytes().chunks(2) {
+            writer.write_all(chunk).unwrap();
+        }
+        let (output, report) = writer.finish().unwrap();
+        let (expected, expected_report) = scrub_text(input, &settings).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+        assert_eq!(report.changes.len(), expected_report.changes.len());
+    }
+}
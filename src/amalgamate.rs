@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/amalgamate.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Default)]
+struct ModuleNode {
+    content: Option<String>,
+    children: BTreeMap<String, ModuleNode>,
+}
+
+struct ModuleDecl {
+    attributes: Vec<String>,
+    visibility: String,
+}
+
+fn module_ident(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+fn common_parent(paths: &[PathBuf]) -> PathBuf {
+    let mut common = match paths.first().and_then(|path| path.parent()) {
+        Some(parent) => parent.to_path_buf(),
+        None => return PathBuf::new(),
+    };
+    for path in &paths[1..] {
+        while !path.starts_with(&common) {
+            if !common.pop() {
+                return PathBuf::new();
+            }
+        }
+    }
+    common
+}
+
+fn module_path(relative: &Path) -> Vec<String> {
+    let mut segments: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    let file_name = segments.pop().unwrap_or_default();
+    let stem = Path::new(&file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let is_root = segments.is_empty() && (stem == "lib" || stem == "main");
+    if stem != "mod" && !is_root {
+        segments.push(stem);
+    }
+    segments.iter().map(|segment| module_ident(segment)).collect()
+}
+
+fn parse_mod_decl(line: &str) -> Option<(String, String)> {
+    let trimmed = line.trim();
+    let rest = trimmed.strip_suffix(';')?.trim_end();
+    let (visibility, rest) = match rest.find("mod ") {
+        Some(index) => (rest[..index].trim(), rest[index + 4..].trim()),
+        None => return None,
+    };
+    let visibility_ok = visibility.is_empty() || visibility == "pub" || visibility.starts_with("pub(");
+    let name_ok = !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    (visibility_ok && name_ok).then(|| (visibility.to_string(), rest.to_string()))
+}
+
+fn take_mod_decls(content: &str, children: &BTreeMap<String, ModuleNode>) -> (String, BTreeMap<String, ModuleDecl>) {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut decls = BTreeMap::new();
+    for line in content.split_inclusive('\n') {
+        match parse_mod_decl(line) {
+            Some((visibility, name)) if children.contains_key(&name) => {
+                let mut attributes = Vec::new();
+                while kept.last().is_some_and(|previous| previous.trim_start().starts_with("#[")) {
+                    attributes.insert(0, kept.pop().unwrap_or_default().trim().to_string());
+                }
+                decls.insert(name, ModuleDecl { attributes, visibility });
+            }
+            _ => kept.push(line),
+        }
+    }
+    (kept.concat(), decls)
+}
+
+fn render(node: &ModuleNode, output: &mut String) {
+    let (content, decls) = take_mod_decls(node.content.as_deref().unwrap_or_default(), &node.children);
+    output.push_str(&content);
+    if !content.is_empty() && !content.ends_with('\n') {
+        output.push('\n');
+    }
+    for (name, child) in &node.children {
+        if !output.is_empty() && !output.ends_with("\n\n") {
+            output.push('\n');
+        }
+        let decl = decls.get(name);
+        for attribute in decl.map(|decl| decl.attributes.as_slice()).unwrap_or_default() {
+            output.push_str(attribute);
+            output.push('\n');
+        }
+        match decl.map(|decl| decl.visibility.as_str()) {
+            Some("") => output.push_str(&format!("mod {} {{\n", name)),
+            Some(visibility) => output.push_str(&format!("{} mod {} {{\n", visibility, name)),
+            None => output.push_str(&format!("pub mod {} {{\n", name)),
+        }
+        render(child, output);
+        output.push_str("}\n");
+    }
+}
+
+pub fn amalgamate(files: &[(PathBuf, String)]) -> String {
+    let paths: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+    let base = common_parent(&paths);
+    let mut root = ModuleNode::default();
+    for (path, content) in files {
+        let relative = path.strip_prefix(&base).unwrap_or(path);
+        let mut node = &mut root;
+        for segment in module_path(relative) {
+            node = node.children.entry(segment).or_default();
+        }
+        let existing = node.content.get_or_insert_with(String::new);
+        existing.push_str(content);
+    }
+    let mut output = String::new();
+    render(&root, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_paths() {
+        assert_eq!(module_path(Path::new("lib.rs")), Vec::<String>::new());
+        assert_eq!(module_path(Path::new("api/mod.rs")), vec!["api"]);
+        assert_eq!(module_path(Path::new("api/http-client.rs")), vec!["api", "http_client"]);
+    }
+
+    #[test]
+    fn test_amalgamate_replaces_mod_declarations() {
+        let files = vec![
+            (PathBuf::from("src/lib.rs"), "pub mod api;\n#[cfg(test)]\nmod tests;\nfn root() {}\n".to_string()),
+            (PathBuf::from("src/api.rs"), "pub fn get() {}\n".to_string()),
+            (PathBuf::from("src/tests.rs"), "fn t() {}".to_string()),
+        ];
+        assert_eq!(
+            amalgamate(&files),
+            "fn root() {}\n\npub mod api {\npub fn get() {}\n}\n\n#[cfg(test)]\nmod tests {\nfn t() {}\n}\n"
+        );
+    }
+}
@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/archive.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+#[cfg(feature = "archives")]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+
+#[cfg(feature = "archives")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+#[cfg(feature = "archives")]
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use rustscrub::scrub::{ScrubReport, ScrubSettings};
+use rustscrub::spill::MemoryBudget;
+#[cfg(feature = "archives")]
+use rustscrub::{scrub::scrub_stream, spill::SpillBuffer};
+
+use crate::editorconfig::FinalNewline;
+#[cfg(feature = "archives")]
+use crate::editorconfig::{EditorConfig, EditorConfigWriter};
+#[cfg(feature = "archives")]
+use crate::walk::is_rust_source;
+
+type EntryReports = Vec<(String, ScrubReport)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+pub fn archive_kind(path: &str) -> Option<ArchiveKind> {
+    let lower = path.to_ascii_lowercase();
+    if lower.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if lower.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "archives")]
+fn entry_label(archive: &str, entry: &str) -> String {
+    format!("{}/{}", archive, entry)
+}
+
+#[cfg(feature = "archives")]
+fn scrub_entry<'a, R: Read>(
+    entry: R,
+    settings: &ScrubSettings,
+    final_newline: Option<FinalNewline>,
+    budget: &'a MemoryBudget,
+) -> Result<(SpillBuffer<'a>, ScrubReport), String> {
+    let mut writer = EditorConfigWriter::new(SpillBuffer::new(budget), EditorConfig::default().with_final_newline(final_newline));
+    let report = scrub_stream(&mut BufReader::new(entry), Some(&mut writer), settings)?;
+    let scrubbed = writer.finish().map_err(|e| format!("Failed to buffer archive entry: {}", e))?;
+    Ok((scrubbed, report))
+}
+
+#[cfg(feature = "archives")]
+fn scrub_zip<R: Read + Seek, W: Write + Seek>(
+    reader: R,
+    writer: Option<W>,
+    label: &str,
+    settings: &ScrubSettings,
+    final_newline: Option<FinalNewline>,
+    budget: &MemoryBudget,
+) -> Result<(EntryReports, Option<W>), String> {
+    let zip_error = |e: zip::result::ZipError| format!("Failed to process zip archive '{}': {}", label, e);
+    let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+    let mut writer = writer.map(ZipWriter::new);
+    let mut reports = Vec::new();
+    for index in 0..archive.len() {
+        let entry = archive.by_index_raw(index).map_err(zip_error)?;
+        if entry.is_dir() || !is_rust_source(Path::new(entry.name())) {
+            if let Some(writer) = writer.as_mut() {
+                writer.raw_copy_file(entry).map_err(zip_error)?;
+            }
+            continue;
+        }
+        drop(entry);
+
+        let mut entry = archive.by_index(index).map_err(zip_error)?;
+        let name = entry.name().to_string();
+        let compression = match entry.compression() {
+            CompressionMethod::Stored => CompressionMethod::Stored,
+            _ => CompressionMethod::Deflated,
+        };
+        let mut options = SimpleFileOptions::default().compression_method(compression);
+        if let Some(mode) = entry.unix_mode() {
+            options = options.unix_permissions(mode);
+        }
+        if let Some(modified) = entry.last_modified() {
+            options = options.last_modified_time(modified);
+        }
+        let (mut scrubbed, report) = scrub_entry(&mut entry, settings, final_newline, budget)?;
+        if let Some(writer) = writer.as_mut() {
+            writer.start_file(name.as_str(), options).map_err(zip_error)?;
+            scrubbed
+                .copy_to(writer)
+                .map_err(|e| format!("Failed to write archive entry '{}': {}", name, e))?;
+        }
+        reports.push((entry_label(label, &name), report));
+    }
+    let inner = writer.map(|writer| writer.finish()).transpose().map_err(zip_error)?;
+    Ok((reports, inner))
+}
+
+#[cfg(feature = "archives")]
+fn scrub_tar<R: Read, W: Write>(
+    reader: R,
+    writer: Option<W>,
+    label: &str,
+    settings: &ScrubSettings,
+    final_newline: Option<FinalNewline>,
+    budget: &MemoryBudget,
+) -> Result<(EntryReports, Option<W>), String> {
+    let tar_error = |e: std::io::Error| format!("Failed to process tar archive '{}': {}", label, e);
+    let mut archive = tar::Archive::new(reader);
+    let mut builder = writer.map(tar::Builder::new);
+    let mut reports = Vec::new();
+    for entry in archive.entries().map_err(tar_error)? {
+        let mut entry = entry.map_err(tar_error)?;
+        let path = entry.path().map_err(tar_error)?.into_owned();
+        let mut header = entry.header().clone();
+        let entry_type = header.entry_type();
+        if entry_type.is_file() && is_rust_source(&path) {
+            let (mut scrubbed, report) = scrub_entry(&mut entry, settings, final_newline, budget)?;
+            if let Some(builder) = builder.as_mut() {
+                header.set_size(scrubbed.len() as u64);
+                builder.append_data(&mut header, &path, scrubbed.reader().map_err(tar_error)?).map_err(tar_error)?;
+            }
+            reports.push((entry_label(label, &path.to_string_lossy()), report));
+        } else if let Some(builder) = builder.as_mut() {
+            if entry_type.is_symlink() || entry_type.is_hard_link() {
+                let target = entry.link_name().map_err(tar_error)?.unwrap_or_default().into_owned();
+                builder.append_link(&mut header, &path, target).map_err(tar_error)?;
+            } else {
+                builder.append_data(&mut header, &path, &mut entry).map_err(tar_error)?;
+            }
+        }
+    }
+    let inner = builder.map(|builder| builder.into_inner()).transpose().map_err(tar_error)?;
+    Ok((reports, inner))
+}
+
+#[cfg(feature = "archives")]
+pub fn scrub_archive(
+    input: &str,
+    output: Option<&str>,
+    settings: &ScrubSettings,
+    final_newline: Option<FinalNewline>,
+    budget: &MemoryBudget,
+) -> Result<EntryReports, String> {
+    let kind = archive_kind(input).ok_or_else(|| format!("Input '{}' is not a supported archive.", input))?;
+    let reader = BufReader::new(
+        File::open(input).map_err(|e| format!("Failed to open input file '{}': {}", input, e))?,
+    );
+    let writer = output
+        .map(|path| {
+            File::create(path)
+                .map(BufWriter::new)
+                .map_err(|e| format!("Failed to create output file '{}': {}", path, e))
+        })
+        .transpose()?;
+
+    let (reports, writer) = match kind {
+        ArchiveKind::Zip => scrub_zip(reader, writer, input, settings, final_newline, budget)?,
+        ArchiveKind::Tar => scrub_tar(reader, writer, input, settings, final_newline, budget)?,
+        ArchiveKind::TarGz => {
+            let encoder = writer.map(|writer| GzEncoder::new(writer, Compression::default()));
+            let (reports, encoder) = scrub_tar(GzDecoder::new(reader), encoder, input, settings, final_newline, budget)?;
+            let writer = encoder
+                .map(|encoder| encoder.finish())
+                .transpose()
+                .map_err(|e| format!("Failed to compress archive '{}': {}", input, e))?;
+            (reports, writer)
+        }
+    };
+    if let Some(mut writer) = writer {
+        writer.flush().map_err(|e| format!("Failed to flush output: {}", e))?;
+    }
+    Ok(reports)
+}
+
+pub fn check_available(input: &str) -> Result<(), String> {
+    if cfg!(feature = "archives") {
+        Ok(())
+    } else {
+        Err(format!("Archive input '{}' requires building rustscrub with the 'archives' feature.", input))
+    }
+}
+
+#[cfg(not(feature = "archives"))]
+pub fn scrub_archive(
+    input: &str,
+    _output: Option<&str>,
+    _settings: &ScrubSettings,
+    _final_newline: Option<FinalNewline>,
+    _budget: &MemoryBudget,
+) -> Result<EntryReports, String> {
+    check_available(input).map(|_| Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "archives")]
+    use std::io::Cursor;
+
+    #[cfg(feature = "archives")]
+    const SOURCE: &str = "// comment\nfn main() {}\n";
+    #[cfg(feature = "archives")]
+    const README: &str = "# keep // this\n";
+
+    #[test]
+    fn test_archive_kind() {
+        assert_eq!(archive_kind("drop.ZIP"), Some(ArchiveKind::Zip));
+        assert_eq!(archive_kind("drop.tar"), Some(ArchiveKind::Tar));
+        assert_eq!(archive_kind("drop.tar.gz"), Some(ArchiveKind::TarGz));
+        assert_eq!(archive_kind("drop.tgz"), Some(ArchiveKind::TarGz));
+        assert_eq!(archive_kind("main.rs"), None);
+    }
+
+    #[cfg(feature = "archives")]
+    #[test]
+    fn test_scrub_zip_keeps_structure() {
+        let mut input = ZipWriter::new(Cursor::new(Vec::new()));
+        input.add_directory("src/", SimpleFileOptions::default()).unwrap();
+        input.start_file("src/main.rs", SimpleFileOptions::default()).unwrap();
+        input.write_all(SOURCE.as_bytes()).unwrap();
+        input.start_file("README.md", SimpleFileOptions::default()).unwrap();
+        input.write_all(README.as_bytes()).unwrap();
+        let input = input.finish().unwrap();
+
+        let (reports, output) =
+            scrub_zip(input, Some(Cursor::new(Vec::new())), "drop.zip", &ScrubSettings::default(), None, &MemoryBudget::default()).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].0, "drop.zip/src/main.rs");
+
+        let mut output = ZipArchive::new(output.unwrap()).unwrap();
+        assert_eq!(output.len(), 3);
+        let mut text = String::new();
+        output.by_name("src/main.rs").unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "fn main() {}\n");
+        text.clear();
+        output.by_name("README.md").unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, README);
+    }
+
+    #[cfg(feature = "archives")]
+    #[test]
+    fn test_scrub_tar_keeps_structure() {
+        let mut input = tar::Builder::new(Vec::new());
+        for (path, content) in [("src/main.rs", SOURCE), ("README.md", README)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            input.append_data(&mut header, path, content.as_bytes()).unwrap();
+        }
+        let input = input.into_inner().unwrap();
+
+        let (reports, output) =
+            scrub_tar(input.as_slice(), Some(Vec::new()), "drop.tar", &ScrubSettings::default(), None, &MemoryBudget::default()).unwrap();
+        assert_eq!(reports.len(), 1);
+
+        let mut output = tar::Archive::new(Cursor::new(output.unwrap()));
+        let entries: Vec<(String, String)> = output
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let mut text = String::new();
+                entry.read_to_string(&mut text).unwrap();
+                (entry.path().unwrap().to_string_lossy().into_owned(), text)
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("src/main.rs".to_string(), "fn main() {}\n".to_string()),
+                ("README.md".to_string(), README.to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "archives")]
+    #[test]
+    fn test_archive_entries_follow_final_newline() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("src/main.rs", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"fn main() {}\n// gone\n\n\n").unwrap();
+        let zip = zip.finish().unwrap();
+        let never = Some(FinalNewline::Never);
+        let (_, output) = scrub_zip(zip, Some(Cursor::new(Vec::new())), "drop.zip", &ScrubSettings::default(), never, &MemoryBudget::default()).unwrap();
+        let mut text = String::new();
+        ZipArchive::new(output.unwrap()).unwrap().by_name("src/main.rs").unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "fn main() {}");
+
+        let mut tar = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(12);
+        header.set_mode(0o644);
+        tar.append_data(&mut header, "src/main.rs", &b"fn main() {}"[..]).unwrap();
+        let tar = tar.into_inner().unwrap();
+        let always = Some(FinalNewline::Always);
+        let (_, output) = scrub_tar(tar.as_slice(), Some(Vec::new()), "drop.tar", &ScrubSettings::default(), always, &MemoryBudget::default()).unwrap();
+        let mut output = tar::Archive::new(Cursor::new(output.unwrap()));
+        let mut entry = output.entries().unwrap().next().unwrap().unwrap();
+        text.clear();
+        entry.read_to_string(&mut text).unwrap();
+        assert_eq!((entry.header().size().unwrap(), text.as_str()), (13, "fn main() {}\n"));
+    }
+
+    #[cfg(feature = "archives")]
+    #[test]
+    fn test_scrub_tar_spills_entries_over_memory_budget() {
+        let mut input = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(SOURCE.len() as u64);
+        header.set_mode(0o644);
+        input.append_data(&mut header, "src/main.rs", SOURCE.as_bytes()).unwrap();
+        let input = input.into_inner().unwrap();
+
+        let budget = MemoryBudget::new(Some(4));
+        let (_, output) = scrub_tar(input.as_slice(), Some(Vec::new()), "drop.tar", &ScrubSettings::default(), None, &budget).unwrap();
+        assert!(budget.exceeded());
+
+        let mut output = tar::Archive::new(Cursor::new(output.unwrap()));
+        let mut entry = output.entries().unwrap().next().unwrap().unwrap();
+        let mut text = String::new();
+        entry.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "fn main() {}\n");
+    }
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/async_io.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::scrub::{ScrubReport, ScrubSettings, Scrubber};
+
+pub async fn scrub_async_reader_to_writer<R, W>(
+    reader: R,
+    writer: &mut W,
+    settings: &ScrubSettings,
+) -> Result<ScrubReport, String>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut scrubber = Scrubber::new(settings.clone());
+    let mut line_buffer = String::new();
+    let mut output = Vec::new();
+
+    loop {
+        line_buffer.clear();
+        let read = reader
+            .read_line(&mut line_buffer)
+            .await
+            .map_err(|e| format!("Failed to read line for processing: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        scrubber.push_line(&line_buffer, &mut output)?;
+        if !output.is_empty() {
+            writer.write_all(&output)
+                .await
+                .map_err(|e| format!("Failed to write processed line: {}", e))?;
+            output.clear();
+        }
+    }
+
+    let report = scrubber.finish(&mut output)?;
+    writer.write_all(&output)
+        .await
+        .map_err(|e| format!("Failed to write processed line: {}", e))?;
+    writer.flush().await.map_err(|e| format!("Failed to flush output: {}", e))?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_async_matches_sync_scrub() {
+        let input = "//! crate doc\nfn f() { /* inline */ }\n// trailing\n";
+        let settings = ScrubSettings::default();
+        let mut output = Vec::new();
+        let report = scrub_async_reader_to_writer(input.as_bytes(), &mut output, &settings).await.unwrap();
+        let (expected, expected_report) = crate::scrub::scrub_text(input, &settings).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+        assert_eq!(report.changes.len(), expected_report.changes.len());
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/banned.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::borrow::Cow;
+
+pub const PROFANITY: [&str; 24] = [
+    "arse*", "ass", "asshole*", "bastard*", "bitch*", "bloody hell", "bollocks", "bullshit*", "crap*", "damn*",
+    "dick*", "dumbass*", "fuck*", "goddamn*", "idiot*", "moron*", "piss*", "retard*", "screw this", "shit*",
+    "stupid*", "sucks", "wanker*", "wtf",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pattern {
+    phrase: String,
+    words: Vec<String>,
+    prefix: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BannedMatch {
+    pub start: usize,
+    pub end: usize,
+    pub phrase: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BannedWords {
+    patterns: Vec<Pattern>,
+}
+
+fn words(text: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (start, c.is_alphanumeric() || c == '\'') {
+            (None, true) => start = Some(index),
+            (Some(begin), false) => {
+                words.push((begin, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+impl Pattern {
+    fn matches(&self, text: &str, candidates: &[(usize, usize)]) -> bool {
+        let last = self.words.len() - 1;
+        self.words.iter().zip(candidates).enumerate().all(|(index, (word, &(start, end)))| {
+            let candidate = text[start..end].to_lowercase();
+            if index == last && self.prefix {
+                candidate.starts_with(word.as_str())
+            } else {
+                candidate == *word
+            }
+        })
+    }
+}
+
+impl BannedWords {
+    pub fn add(&mut self, phrase: &str) {
+        let phrase = phrase.trim();
+        let prefix = phrase.ends_with('*');
+        let text = phrase.trim_end_matches('*');
+        let words: Vec<String> = words(text).iter().map(|&(start, end)| text[start..end].to_lowercase()).collect();
+        if !words.is_empty() {
+            self.patterns.push(Pattern {
+                phrase: phrase.to_string(),
+                words,
+                prefix,
+            });
+        }
+    }
+
+    pub fn add_profanity(&mut self) {
+        for phrase in PROFANITY {
+            self.add(phrase);
+        }
+    }
+
+    pub fn add_wordlist(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') {
+                self.add(line);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn find(&self, text: &str) -> Vec<BannedMatch> {
+        let words = words(text);
+        let mut matches = Vec::new();
+        let mut index = 0;
+        while index < words.len() {
+            let found = self.patterns.iter().find(|pattern| {
+                words.len() - index >= pattern.words.len() && pattern.matches(text, &words[index..])
+            });
+            match found {
+                Some(pattern) => {
+                    matches.push(BannedMatch {
+                        start: words[index].0,
+                        end: words[index + pattern.words.len() - 1].1,
+                        phrase: pattern.phrase.clone(),
+                    });
+                    index += pattern.words.len();
+                }
+                None => index += 1,
+            }
+        }
+        matches
+    }
+
+    pub fn redact<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        let matches = self.find(text);
+        if matches.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        let mut redacted = String::with_capacity(text.len());
+        let mut position = 0;
+        for found in matches {
+            redacted.push_str(&text[position..found.start]);
+            redacted.extend(
+                text[found.start..found.end]
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { '*' } else { c }),
+            );
+            position = found.end;
+        }
+        redacted.push_str(&text[position..]);
+        Cow::Owned(redacted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_and_redacts_banned_phrases() {
+        let mut banned = BannedWords::default();
+        banned.add_profanity();
+        banned.add_wordlist("# codenames\nProject Falcon\n\nacme*\n");
+        let text = "// this is a shitty hack for project\n// falcon, see AcmeCorp and the assessment";
+        let found: Vec<&str> = banned.find(text).iter().map(|m| &text[m.start..m.end]).collect();
+        assert_eq!(found, vec!["shitty", "project\n// falcon", "AcmeCorp"]);
+        assert_eq!(
+            banned.redact(text),
+            "// this is a ****** hack for *******\n// ******, see ******** and the assessment"
+        );
+        assert!(matches!(banned.redact("// all good"), Cow::Borrowed(_)));
+    }
+}
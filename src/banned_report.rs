@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/banned_report.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+
+use rustscrub::banned::BannedWords;
+use rustscrub::filter::CommentFilter;
+use rustscrub::scrub::{scrub_text, ChangeInfo, ScrubSettings};
+
+use crate::config::{BannedConfig, Config};
+use crate::status::Failure;
+use crate::walk::{collect_source_files, WalkArgs};
+
+#[derive(clap::Args, Debug)]
+pub struct BannedArgs {
+    #[clap(value_parser, required = true)]
+    paths: Vec<String>,
+
+    #[clap(long, value_name = "FILE")]
+    config: Option<String>,
+
+    #[clap(long = "wordlist", value_name = "FILE")]
+    wordlists: Vec<String>,
+
+    #[clap(long = "phrase", value_name = "TEXT")]
+    phrases: Vec<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    no_profanity: bool,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub line: usize,
+    pub column: usize,
+    pub phrase: String,
+    pub text: String,
+}
+
+pub fn load_banned(config: &BannedConfig, wordlists: &[String], phrases: &[String]) -> Result<BannedWords, String> {
+    let mut banned = BannedWords::default();
+    if config.profanity {
+        banned.add_profanity();
+    }
+    for path in config.wordlists.iter().chain(wordlists) {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read word list '{}': {}", path, e))?;
+        banned.add_wordlist(&text);
+    }
+    for phrase in config.phrases.iter().chain(phrases) {
+        banned.add(phrase);
+    }
+    if banned.is_empty() {
+        return Err("No banned phrases configured. Pass --wordlist or --phrase, or add a [banned] section to rustscrub.toml.".to_string());
+    }
+    Ok(banned)
+}
+
+fn locate(change: &ChangeInfo, offset: usize) -> (usize, usize) {
+    let prefix = &change.text[..offset];
+    let newlines = prefix.matches('\n').count();
+    let column = match prefix.rsplit_once('\n') {
+        Some((_, rest)) => rest.chars().count() + 1,
+        None => change.start_column + prefix.chars().count(),
+    };
+    (change.start_line + newlines, column)
+}
+
+pub fn scan_source(content: &str, banned: &BannedWords) -> Result<Vec<Finding>, String> {
+    let settings = ScrubSettings {
+        filter: CommentFilter {
+            keep_directives: false,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    };
+    let (_, report) = scrub_text(content, &settings)?;
+    let mut findings = Vec::new();
+    for change in &report.changes {
+        for found in banned.find(&change.text) {
+            let (line, column) = locate(change, found.start);
+            findings.push(Finding {
+                line,
+                column,
+                phrase: found.phrase,
+                text: change.text[found.start..found.end].split_whitespace().collect::<Vec<_>>().join(" "),
+            });
+        }
+    }
+    Ok(findings)
+}
+
+pub fn run(args: &BannedArgs) -> Result<(), Failure> {
+    let mut config = Config::load(args.config.as_deref())?.banned;
+    config.profanity &= !args.no_profanity;
+    let banned = load_banned(&config, &args.wordlists, &args.phrases)?;
+
+    let mut total = 0;
+    let mut files = 0;
+    let paths = collect_source_files(&args.paths, &args.walk)?;
+    for path in &paths {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read input file '{}': {}", path.display(), e))?;
+        let findings = scan_source(&content, &banned)?;
+        for finding in &findings {
+            println!(
+                "{}:{}:{}: banned phrase '{}' in comment: {}",
+                path.display(),
+                finding.line,
+                finding.column,
+                finding.phrase,
+                finding.text
+            );
+        }
+        total += findings.len();
+        files += usize::from(!findings.is_empty());
+    }
+    eprintln!("RustScrub Banned: {} banned phrases in {} of {} files", total, files, paths.len());
+    if total > 0 {
+        Err(Failure::changes(format!("Found {} banned phrases in comments", total)))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_reports_comment_positions_only() {
+        let mut banned = BannedWords::default();
+        banned.add_profanity();
+        banned.add("Project Falcon");
+        let source = "let crap = 1; // this crap is temporary\n/* ported from\n   project falcon */\nlet s = \"damn\";\n";
+        let findings = scan_source(source, &banned).unwrap();
+        assert_eq!(
+            findings,
+            vec![
+                Finding { line: 1, column: 23, phrase: "crap*".to_string(), text: "crap".to_string() },
+                Finding { line: 3, column: 4, phrase: "Project Falcon".to_string(), text: "project falcon".to_string() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/baseline.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use rustscrub::fingerprint::{normalize, Fingerprinter};
+
+const BASELINE_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Finding {
+    pub path: String,
+    pub rule: String,
+    pub text: String,
+    #[serde(default)]
+    pub fingerprint: String,
+}
+
+impl Finding {
+    pub fn new(path: &str, rule: &str, text: &str, fingerprint: String) -> Self {
+        Finding {
+            path: path.to_string(),
+            rule: rule.to_string(),
+            text: normalize(text),
+            fingerprint,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    findings: Vec<Finding>,
+}
+
+#[derive(Debug)]
+pub struct Baseline {
+    path: String,
+    known: Option<HashMap<Finding, usize>>,
+    recorded: Vec<Finding>,
+}
+
+impl Baseline {
+    pub fn open(path: &str, update: bool) -> Result<Self, String> {
+        let known = if update || !Path::new(path).exists() {
+            None
+        } else {
+            let text = fs::read_to_string(path).map_err(|e| format!("Failed to read baseline '{}': {}", path, e))?;
+            let file: BaselineFile = serde_json::from_str(&text).map_err(|e| format!("Failed to parse baseline '{}': {}", path, e))?;
+            if !(1..=BASELINE_VERSION).contains(&file.version) {
+                return Err(format!("Baseline '{}' has unsupported version {}.", path, file.version));
+            }
+            let mut fingerprinters: HashMap<(String, String), Fingerprinter> = HashMap::new();
+            let mut known = HashMap::new();
+            for mut finding in file.findings {
+                if finding.fingerprint.is_empty() {
+                    let fingerprinter = fingerprinters.entry((finding.path.clone(), finding.rule.clone())).or_default();
+                    finding.fingerprint = fingerprinter.next(&finding.text);
+                }
+                *known.entry(finding).or_insert(0) += 1;
+            }
+            Some(known)
+        };
+        Ok(Baseline {
+            path: path.to_string(),
+            known,
+            recorded: Vec::new(),
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.known.is_none()
+    }
+
+    pub fn is_new(&mut self, finding: Finding) -> bool {
+        let new = match self.known.as_mut().map(|known| known.get_mut(&finding)) {
+            None => false,
+            Some(Some(count)) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            Some(_) => true,
+        };
+        self.recorded.push(finding);
+        new
+    }
+
+    pub fn finish(mut self) -> Result<(), String> {
+        match self.known {
+            None => {
+                self.recorded.sort();
+                let file = BaselineFile {
+                    version: BASELINE_VERSION,
+                    findings: self.recorded,
+                };
+                let json = serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize baseline: {}", e))?;
+                fs::write(&self.path, json + "\n").map_err(|e| format!("Failed to write baseline '{}': {}", self.path, e))?;
+                eprintln!("RustScrub: Recorded {} findings in baseline '{}'", file.findings.len(), self.path);
+            }
+            Some(known) => {
+                let fixed: usize = known.values().sum();
+                if fixed > 0 {
+                    eprintln!(
+                        "RustScrub: {} findings from baseline '{}' are gone; rerun with --update-baseline to tighten it",
+                        fixed, self.path
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(path: &str, text: &str, occurrence: usize) -> Finding {
+        Finding::new(path, "comment", text, rustscrub::fingerprint::fingerprint(text, occurrence))
+    }
+
+    #[test]
+    fn test_only_findings_beyond_the_baseline_are_new() {
+        let path = std::env::temp_dir().join(format!("rustscrub-baseline-{}.json", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let _ = fs::remove_file(&path);
+
+        let mut recording = Baseline::open(&path, false).unwrap();
+        assert!(recording.is_recording());
+        assert!(!recording.is_new(finding("a.rs", "// one", 0)));
+        assert!(!recording.is_new(finding("a.rs", "// twice", 0)));
+        assert!(!recording.is_new(finding("a.rs", "// twice", 1)));
+        recording.finish().unwrap();
+
+        let mut baseline = Baseline::open(&path, false).unwrap();
+        assert!(!baseline.is_recording());
+        assert!(!baseline.is_new(finding("a.rs", "//   twice", 0)));
+        assert!(!baseline.is_new(finding("a.rs", "// twice", 1)));
+        assert!(baseline.is_new(finding("a.rs", "// twice", 2)));
+        assert!(baseline.is_new(finding("b.rs", "// one", 0)));
+        baseline.finish().unwrap();
+
+        assert!(Baseline::open(&path, true).unwrap().is_recording());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_version_one_baselines_get_fingerprints() {
+        let path = std::env::temp_dir().join(format!("rustscrub-baseline-v1-{}.json", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let findings = r#"[{"path":"a.rs","rule":"comment","text":"// twice"},{"path":"a.rs","rule":"comment","text":"// twice"}]"#;
+        fs::write(&path, format!(r#"{{"version":1,"findings":{}}}"#, findings)).unwrap();
+        let mut baseline = Baseline::open(&path, false).unwrap();
+        assert!(!baseline.is_new(finding("a.rs", "// twice", 1)));
+        assert!(!baseline.is_new(finding("a.rs", "// twice", 0)));
+        assert!(baseline.is_new(finding("a.rs", "// twice", 2)));
+        fs::remove_file(&path).unwrap();
+    }
+}
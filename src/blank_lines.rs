@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/blank_lines.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlankLineLimit(pub usize);
+
+impl FromStr for BlankLineLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = s.strip_prefix("keep-").unwrap_or(s);
+        count
+            .parse::<usize>()
+            .map(BlankLineLimit)
+            .map_err(|_| format!("Invalid blank line limit '{}'. Expected 'keep-N'.", s))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BlankLineFilter {
+    limit: usize,
+    pending_line: String,
+    consecutive_blanks: usize,
+}
+
+impl BlankLineFilter {
+    pub fn new(limit: BlankLineLimit) -> Self {
+        BlankLineFilter {
+            limit: limit.0,
+            ..BlankLineFilter::default()
+        }
+    }
+
+    pub fn push(&mut self, segment: &str) -> String {
+        let mut emitted = String::with_capacity(segment.len());
+        for piece in segment.split_inclusive('\n') {
+            self.pending_line.push_str(piece);
+            if !piece.ends_with('\n') {
+                continue;
+            }
+            if self.pending_line.trim().is_empty() {
+                self.consecutive_blanks += 1;
+                if self.consecutive_blanks <= self.limit {
+                    emitted.push_str(&self.pending_line);
+                }
+            } else {
+                self.consecutive_blanks = 0;
+                emitted.push_str(&self.pending_line);
+            }
+            self.pending_line.clear();
+        }
+        emitted
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.pending_line.is_empty() && self.consecutive_blanks == 0
+    }
+
+    pub fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.pending_line);
+        if rest.trim().is_empty() {
+            String::new()
+        } else {
+            rest
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrub::{scrub_text, ScrubSettings};
+
+    #[test]
+    fn test_blank_line_limit_caps_runs_and_keeps_header() {
+        assert_eq!("keep-2".parse::<BlankLineLimit>(), Ok(BlankLineLimit(2)));
+        assert_eq!("0".parse::<BlankLineLimit>(), Ok(BlankLineLimit(0)));
+        assert!("keep-x".parse::<BlankLineLimit>().is_err());
+
+        let mut filter = BlankLineFilter::new(BlankLineLimit(1));
+        assert_eq!(filter.push("a\n\n  \n\t\nb\n"), "a\n\nb\n");
+        assert_eq!(filter.push("\n\n"), "\n");
+        assert_eq!(filter.finish(), "");
+
+        let settings = ScrubSettings {
+            header_lines: 2,
+            blank_line_limit: Some(BlankLineLimit(0)),
+            ..ScrubSettings::default()
+        };
+        let input = "// header\n\nfn a() {}\n// gone\n\n\nfn b() {}\n   \n";
+        let (output, _) = scrub_text(input, &settings).unwrap();
+        assert_eq!(output, "// header\n\nfn a() {}\nfn b() {}\n");
+    }
+}
@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/check.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs::File;
+use std::io::BufReader;
+
+use rustscrub::fingerprint::fingerprints;
+use rustscrub::options::ScrubArgs;
+use rustscrub::scrub::{scrub_stream, ScrubReport};
+
+use crate::baseline::{Baseline, Finding};
+use crate::config::Config;
+use crate::doc_coverage;
+use crate::policy;
+use crate::status::Failure;
+use crate::suppressions::Suppressions;
+use crate::walk::{collect_source_files, WalkArgs};
+
+#[derive(clap::Args, Debug)]
+pub struct CheckArgs {
+    #[clap(value_parser, required = true)]
+    paths: Vec<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["max_comments", "max_comment_ratio"])]
+    policy: bool,
+
+    #[clap(long, value_name = "FILE")]
+    config: Option<String>,
+
+    #[clap(long, value_name = "N")]
+    max_comments: Option<usize>,
+
+    #[clap(long, value_name = "PERCENT")]
+    max_comment_ratio: Option<f64>,
+
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["max_comments", "max_comment_ratio"])]
+    baseline: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, requires = "baseline")]
+    update_baseline: bool,
+
+    #[clap(long, value_name = "FILE")]
+    suppressions: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with = "update_baseline")]
+    update_suppressions: bool,
+
+    #[clap(long, value_name = "PERCENT", conflicts_with_all = ["policy", "max_comments", "max_comment_ratio", "baseline", "update_suppressions"])]
+    min_doc_coverage: Option<f64>,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+
+    #[clap(flatten)]
+    scrub: ScrubArgs,
+}
+
+impl CheckArgs {
+    pub fn check_features(&self) -> Result<(), String> {
+        if self.min_doc_coverage.is_some() {
+            doc_coverage::check_available()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckThresholds {
+    pub max_comments: Option<usize>,
+    pub max_comment_ratio: Option<f64>,
+}
+
+const COMMENT_RULE: &str = "comment";
+
+fn comment_bytes(report: &ScrubReport) -> usize {
+    report.changes.iter().map(|change| change.text_len).sum()
+}
+
+fn ratio_percent(comment_bytes: usize, total_bytes: usize) -> f64 {
+    if total_bytes == 0 {
+        0.0
+    } else {
+        comment_bytes as f64 * 100.0 / total_bytes as f64
+    }
+}
+
+pub fn evaluate(reports: &[(String, ScrubReport)], thresholds: CheckThresholds) -> Result<(), Failure> {
+    let mut total_comments = 0;
+    let mut total_comment_bytes = 0;
+    let mut total_bytes = 0;
+    for (path, report) in reports {
+        let bytes = comment_bytes(report);
+        if !report.changes.is_empty() {
+            eprintln!(
+                "RustScrub: {}: {} comments ({:.1}% of bytes)",
+                path,
+                report.changes.len(),
+                ratio_percent(bytes, report.bytes.before)
+            );
+        }
+        total_comments += report.changes.len();
+        total_comment_bytes += bytes;
+        total_bytes += report.bytes.before;
+    }
+    let ratio = ratio_percent(total_comment_bytes, total_bytes);
+    eprintln!(
+        "RustScrub Check: {} comments in {} files ({:.1}% of bytes)",
+        total_comments,
+        reports.len(),
+        ratio
+    );
+
+    let mut failures = Vec::new();
+    if let Some(max_comments) = thresholds.max_comments {
+        if total_comments > max_comments {
+            failures.push(format!("{} comments exceed the budget of {}", total_comments, max_comments));
+        }
+    }
+    if let Some(max_ratio) = thresholds.max_comment_ratio {
+        if ratio > max_ratio {
+            failures.push(format!("comment ratio {:.1}% exceeds the budget of {:.1}%", ratio, max_ratio));
+        }
+    }
+    if thresholds.max_comments.is_none() && thresholds.max_comment_ratio.is_none() && total_comments > 0 {
+        failures.push(format!("{} comments found", total_comments));
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Failure::changes(format!("Check failed: {}", failures.join("; "))))
+    }
+}
+
+pub fn suppress_comments(reports: &mut [(String, ScrubReport)], suppressions: &mut Suppressions) {
+    for (path, report) in reports {
+        let fingerprints = fingerprints(report.changes.iter().map(|change| change.text.as_str()));
+        let mut fingerprints = fingerprints.iter();
+        report.changes.retain(|change| {
+            let fingerprint = fingerprints.next().map(String::as_str).unwrap_or_default();
+            !suppressions.is_suppressed(path, COMMENT_RULE, &change.text, fingerprint)
+        });
+    }
+}
+
+pub fn evaluate_baseline(reports: &[(String, ScrubReport)], baseline: &mut Baseline, suppressions: &mut Suppressions) -> Result<(), Failure> {
+    let mut new_comments = 0;
+    for (path, report) in reports {
+        let fingerprints = fingerprints(report.changes.iter().map(|change| change.text.as_str()));
+        for (change, fingerprint) in report.changes.iter().zip(fingerprints) {
+            if suppressions.is_suppressed(path, COMMENT_RULE, &change.text, &fingerprint) {
+                continue;
+            }
+            if baseline.is_new(Finding::new(path, COMMENT_RULE, &change.text, fingerprint.clone())) {
+                let summary = change.text.lines().next().unwrap_or_default().trim();
+                eprintln!("{}:{}: new comment [{}]: {}", path, change.start_line, fingerprint, summary);
+                new_comments += 1;
+            }
+        }
+    }
+    if !baseline.is_recording() {
+        eprintln!("RustScrub Check: {} new comments in {} files", new_comments, reports.len());
+    }
+    if new_comments > 0 {
+        Err(Failure::changes(format!("Check failed: {} comments are not in the baseline", new_comments)))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn run(args: &CheckArgs) -> Result<(), Failure> {
+    let paths: Vec<String> = collect_source_files(&args.paths, &args.walk)?
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    let mut baseline = args.baseline.as_deref().map(|path| Baseline::open(path, args.update_baseline)).transpose()?;
+    let mut suppressions = Suppressions::open(args.suppressions.as_deref(), args.update_suppressions)?;
+    if args.policy {
+        let config = Config::load(args.config.as_deref())?;
+        let result = policy::run(&paths, &config.policy, baseline.as_mut(), &mut suppressions);
+        if let Some(baseline) = baseline {
+            baseline.finish()?;
+        }
+        suppressions.finish()?;
+        return result;
+    }
+
+    let settings = args.scrub.settings();
+    if let Some(min_coverage) = args.min_doc_coverage {
+        return doc_coverage::run(&paths, &settings, min_coverage);
+    }
+    let mut reports = Vec::new();
+    for path in paths {
+        let input_file = File::open(&path)
+            .map_err(|e| format!("Failed to open input file '{}': {}", path, e))?;
+        let report = scrub_stream(&mut BufReader::new(input_file), None, &settings)?;
+        reports.push((path, report));
+    }
+    if let Some(mut baseline) = baseline {
+        let result = evaluate_baseline(&reports, &mut baseline, &mut suppressions);
+        baseline.finish()?;
+        suppressions.finish()?;
+        return result;
+    }
+    suppress_comments(&mut reports, &mut suppressions);
+    suppressions.finish()?;
+    evaluate(
+        &reports,
+        CheckThresholds {
+            max_comments: args.max_comments,
+            max_comment_ratio: args.max_comment_ratio,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    fn report(text: &str) -> (String, ScrubReport) {
+        ("f.rs".to_string(), scrub_text(text, &ScrubSettings::default()).unwrap().1)
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let reports = vec![report("fn f() {} // one\n// two\n")];
+        assert!(evaluate(&reports, CheckThresholds::default()).is_err());
+        let budget = CheckThresholds { max_comments: Some(2), max_comment_ratio: None };
+        assert!(evaluate(&reports, budget).is_ok());
+        let ratio = CheckThresholds { max_comments: Some(2), max_comment_ratio: Some(10.0) };
+        assert!(evaluate(&reports, ratio).is_err());
+        assert!(evaluate(&[report("fn f() {}\n")], CheckThresholds::default()).is_ok());
+    }
+
+    #[test]
+    fn test_ratio_survives_dropped_comment_text() {
+        let mut reports = vec![report("fn f() {} // a fairly long trailing comment\n")];
+        for change in &mut reports[0].1.changes {
+            change.text = String::new();
+        }
+        let ratio = CheckThresholds { max_comments: Some(1), max_comment_ratio: Some(10.0) };
+        assert!(evaluate(&reports, ratio).is_err());
+    }
+}
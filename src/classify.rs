@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/classify.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::filter::{is_directive, is_license, is_test_annotation, Comment};
+use crate::scrub::{ChangeInfo, VerboseCommentType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentCategory {
+    Doc,
+    License,
+    Todo,
+    Code,
+    Directive,
+    Prose,
+}
+
+const TODO_MARKERS: [&str; 5] = ["TODO", "FIXME", "XXX", "HACK", "BUG"];
+
+const CODE_PREFIXES: [&str; 20] = [
+    "let ", "fn ", "pub ", "use ", "mod ", "impl ", "struct ", "enum ", "trait ", "if ", "else", "for ",
+    "while ", "loop ", "match ", "return", "println!", "assert", "#[", "self.",
+];
+
+pub fn is_todo(text: &str) -> bool {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| TODO_MARKERS.contains(&word))
+}
+
+pub fn looks_like_code(text: &str) -> bool {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['*', '/']).trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return false;
+    }
+    let code_lines = lines
+        .iter()
+        .filter(|line| {
+            line.ends_with(';')
+                || line.ends_with('{')
+                || *line == &"}"
+                || line.ends_with("},")
+                || line.contains("=>")
+                || CODE_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+        })
+        .count();
+    code_lines * 2 > lines.len()
+}
+
+pub fn classify(comment: &Comment) -> CommentCategory {
+    let body = comment.body();
+    if comment.doc_style().is_some() {
+        CommentCategory::Doc
+    } else if is_license(body) {
+        CommentCategory::License
+    } else if is_directive(comment) || is_test_annotation(comment) {
+        CommentCategory::Directive
+    } else if is_todo(body) {
+        CommentCategory::Todo
+    } else if looks_like_code(body) {
+        CommentCategory::Code
+    } else {
+        CommentCategory::Prose
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, JsonSchema)]
+pub struct CategoryCounts {
+    pub doc: usize,
+    pub license: usize,
+    pub todo: usize,
+    pub code: usize,
+    pub directive: usize,
+    pub prose: usize,
+}
+
+impl CategoryCounts {
+    pub fn record(&mut self, category: CommentCategory) {
+        match category {
+            CommentCategory::Doc => self.doc += 1,
+            CommentCategory::License => self.license += 1,
+            CommentCategory::Todo => self.todo += 1,
+            CommentCategory::Code => self.code += 1,
+            CommentCategory::Directive => self.directive += 1,
+            CommentCategory::Prose => self.prose += 1,
+        }
+    }
+
+    pub fn add(&mut self, other: &CategoryCounts) {
+        self.doc += other.doc;
+        self.license += other.license;
+        self.todo += other.todo;
+        self.code += other.code;
+        self.directive += other.directive;
+        self.prose += other.prose;
+    }
+
+    pub fn total(&self) -> usize {
+        self.doc + self.license + self.todo + self.code + self.directive + self.prose
+    }
+}
+
+pub fn count_categories(changes: &[ChangeInfo]) -> CategoryCounts {
+    let mut counts = CategoryCounts::default();
+    for change in changes {
+        counts.record(classify(&change.as_comment()));
+    }
+    counts
+}
+
+pub fn comment_blocks<'a>(changes: impl IntoIterator<Item = &'a ChangeInfo>) -> Vec<(usize, String)> {
+    let mut blocks: Vec<(usize, String)> = Vec::new();
+    let mut previous: Option<&ChangeInfo> = None;
+    for change in changes {
+        let continues = previous.is_some_and(|previous| {
+            previous.comment_type == VerboseCommentType::Line
+                && change.comment_type == VerboseCommentType::Line
+                && change.start_line == previous.end_line + 1
+        });
+        let body = change.as_comment().body().to_string();
+        match blocks.last_mut() {
+            Some((_, text)) if continues => {
+                text.push(' ');
+                text.push_str(&body);
+            }
+            _ => blocks.push((change.start_line, body)),
+        }
+        previous = Some(change);
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> CommentCategory {
+        classify(&Comment {
+            text,
+            comment_type: VerboseCommentType::Line,
+            full_line: true,
+            continues: None,
+        })
+    }
+
+    #[test]
+    fn test_classify_categories() {
+        assert_eq!(line("/// Returns the sum."), CommentCategory::Doc);
+        assert_eq!(line("// SPDX-License-Identifier: MIT"), CommentCategory::License);
+        assert_eq!(line("// cspell:ignore foo"), CommentCategory::Directive);
+        assert_eq!(line("// TODO: handle errors"), CommentCategory::Todo);
+        assert_eq!(line("// let x = compute(a, b);"), CommentCategory::Code);
+        assert_eq!(line("// Compute the running total."), CommentCategory::Prose);
+    }
+
+    #[test]
+    fn test_todo_requires_whole_word() {
+        assert!(is_todo("FIXME later"));
+        assert!(!is_todo("TODOS are tracked elsewhere"));
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/classify_report.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::classify::{count_categories, CategoryCounts};
+use rustscrub::filter::CommentFilter;
+use rustscrub::schema;
+use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+use crate::duplicates::{DuplicateComment, DuplicateFinder};
+use crate::language::{self, detect_foreign, parse_language, snippet, ForeignComment, Lang};
+use crate::stale::{code_identifiers, detect_stale, StaleReference};
+use crate::walk::{collect_source_files, WalkArgs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClassifyFormat {
+    Table,
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ClassifyArgs {
+    #[clap(value_parser, required = true)]
+    paths: Vec<String>,
+
+    #[clap(long, value_enum, default_value_t = ClassifyFormat::Table)]
+    format: ClassifyFormat,
+
+    #[clap(long, value_name = "LANG", num_args = 0..=1, require_equals = true, default_missing_value = "eng")]
+    detect_language: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    detect_stale: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    detect_redundant: bool,
+
+    #[clap(long, value_name = "MIN_CHARS", num_args = 0..=1, require_equals = true, default_missing_value = "40")]
+    detect_duplicates: Option<usize>,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+}
+
+impl ClassifyArgs {
+    pub fn check_features(&self) -> Result<(), String> {
+        if self.detect_language.is_some() {
+            language::check_available()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct FileClassification {
+    path: String,
+    counts: CategoryCounts,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    foreign_comments: Vec<ForeignComment>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stale_references: Vec<StaleReference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    redundant_comments: Vec<RedundantComment>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ClassificationReport {
+    files: Vec<FileClassification>,
+    total: CategoryCounts,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    duplicates: Vec<DuplicateComment>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct RedundantComment {
+    line: usize,
+    snippet: String,
+}
+
+fn find_redundant(source: &str) -> Result<Vec<RedundantComment>, String> {
+    let settings = ScrubSettings {
+        filter: CommentFilter {
+            remove_redundant: true,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    };
+    let (_, report) = scrub_text(source, &settings)?;
+    Ok(report
+        .changes
+        .iter()
+        .map(|change| RedundantComment {
+            line: change.start_line,
+            snippet: snippet(change.as_comment().body()),
+        })
+        .collect())
+}
+
+fn classify_file(
+    path: String,
+    args: &ClassifyArgs,
+    expected_language: Option<Lang>,
+    duplicates: Option<&mut DuplicateFinder>,
+) -> Result<FileClassification, String> {
+    let source = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read input file '{}': {}", path, e))?;
+    let settings = ScrubSettings {
+        filter: CommentFilter {
+            keep_directives: false,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    };
+    let (code, report) = scrub_text(&source, &settings)?;
+    let foreign_comments = expected_language
+        .map(|language| detect_foreign(&report.changes, language))
+        .unwrap_or_default();
+    if let Some(duplicates) = duplicates {
+        duplicates.add(&path, &report.changes);
+    }
+    let stale_references = if args.detect_stale {
+        detect_stale(&report.changes, &code_identifiers(&code))
+    } else {
+        Vec::new()
+    };
+    let redundant_comments = if args.detect_redundant {
+        find_redundant(&source)?
+    } else {
+        Vec::new()
+    };
+    Ok(FileClassification {
+        path,
+        counts: count_categories(&report.changes),
+        foreign_comments,
+        stale_references,
+        redundant_comments,
+    })
+}
+
+fn print_table(report: &ClassificationReport) {
+    let width = report
+        .files
+        .iter()
+        .map(|f| f.path.len())
+        .max()
+        .unwrap_or(0)
+        .max("Total".len());
+    println!(
+        "{:<width$}  {:>5}  {:>7}  {:>5}  {:>5}  {:>9}  {:>6}  {:>6}",
+        "File", "Doc", "License", "TODO", "Code", "Directive", "Prose", "Total",
+        width = width
+    );
+    let row = |name: &str, c: &CategoryCounts| {
+        println!(
+            "{:<width$}  {:>5}  {:>7}  {:>5}  {:>5}  {:>9}  {:>6}  {:>6}",
+            name, c.doc, c.license, c.todo, c.code, c.directive, c.prose, c.total(),
+            width = width
+        );
+    };
+    for file in &report.files {
+        row(&file.path, &file.counts);
+    }
+    row("Total", &report.total);
+
+    for duplicate in &report.duplicates {
+        println!("Duplicate comment ({} places): {}", duplicate.locations.len(), duplicate.snippet);
+        for location in &duplicate.locations {
+            println!("    {}:{}", location.path, location.line);
+        }
+    }
+
+    for file in &report.files {
+        for comment in &file.foreign_comments {
+            println!("{}:{}: {} ({}): {}", file.path, comment.line, comment.language, comment.code, comment.snippet);
+        }
+        for reference in &file.stale_references {
+            println!("{}:{}: stale reference to `{}`", file.path, reference.line, reference.name);
+        }
+        for comment in &file.redundant_comments {
+            println!("{}:{}: redundant comment: {}", file.path, comment.line, comment.snippet);
+        }
+    }
+}
+
+pub fn run(args: &ClassifyArgs) -> Result<(), String> {
+    let expected_language = args.detect_language.as_deref().map(parse_language).transpose()?;
+    let paths = collect_source_files(&args.paths, &args.walk)?;
+    let mut report = ClassificationReport {
+        files: Vec::new(),
+        total: CategoryCounts::default(),
+        duplicates: Vec::new(),
+    };
+    let mut duplicates = args.detect_duplicates.map(DuplicateFinder::new);
+    for path in paths {
+        let file = classify_file(
+            path.to_string_lossy().into_owned(),
+            args,
+            expected_language,
+            duplicates.as_mut(),
+        )?;
+        report.total.add(&file.counts);
+        report.files.push(file);
+    }
+    if let Some(duplicates) = duplicates {
+        report.duplicates = duplicates.finish();
+    }
+
+    match args.format {
+        ClassifyFormat::Table => print_table(&report),
+        ClassifyFormat::Json => {
+            let json = schema::to_json("classify", &report)
+                .map_err(|e| format!("Failed to serialize classification report: {}", e))?;
+            println!("{}", json);
+        }
+    }
+    if let Some(language) = expected_language {
+        let files = report.files.iter().filter(|f| !f.foreign_comments.is_empty()).count();
+        eprintln!("RustScrub: {} files contain comments not written in {}", files, language.eng_name());
+    }
+    if args.detect_stale {
+        let references: usize = report.files.iter().map(|f| f.stale_references.len()).sum();
+        eprintln!("RustScrub: {} comment references to identifiers not found in their file", references);
+    }
+    if args.detect_redundant {
+        let comments: usize = report.files.iter().map(|f| f.redundant_comments.len()).sum();
+        eprintln!("RustScrub: {} comments restate the code next to them", comments);
+    }
+    if args.detect_duplicates.is_some() {
+        let places: usize = report.duplicates.iter().map(|d| d.locations.len()).sum();
+        eprintln!("RustScrub: {} comments are duplicated across {} places", report.duplicates.len(), places);
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/cleanup.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+struct Pending {
+    path: PathBuf,
+    restore_to: Option<PathBuf>,
+}
+
+static PENDING: Mutex<Vec<Pending>> = Mutex::new(Vec::new());
+
+fn pending() -> MutexGuard<'static, Vec<Pending>> {
+    PENDING.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn remove_on_abort(path: &Path) {
+    pending().push(Pending { path: path.to_path_buf(), restore_to: None });
+}
+
+pub fn restore_on_abort(backup: &Path, target: &Path) {
+    pending().push(Pending { path: backup.to_path_buf(), restore_to: Some(target.to_path_buf()) });
+}
+
+pub fn forget(path: &Path) {
+    pending().retain(|entry| entry.path != path);
+}
+
+fn apply(entries: Vec<Pending>) {
+    for entry in entries.into_iter().rev() {
+        let _ = match entry.restore_to {
+            Some(target) => fs::rename(&entry.path, target),
+            None => fs::remove_file(&entry.path),
+        };
+    }
+}
+
+pub fn abort() {
+    let entries = std::mem::take(&mut *pending());
+    apply(entries);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abort_removes_temporary_files_and_restores_backups() {
+        let dir = std::env::temp_dir().join(format!("rustscrub-cleanup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (staged, backup, target, kept) = (dir.join(".a.rs.tmp"), dir.join(".b.rs.bak"), dir.join("b.rs"), dir.join("c.rs"));
+        fs::write(&staged, "staged").unwrap();
+        fs::write(&backup, "original").unwrap();
+        fs::write(&target, "replaced").unwrap();
+        fs::write(&kept, "kept").unwrap();
+
+        remove_on_abort(&kept);
+        forget(&kept);
+        assert!(pending().iter().all(|entry| entry.path != kept));
+        apply(vec![
+            Pending { path: staged.clone(), restore_to: None },
+            Pending { path: backup.clone(), restore_to: Some(target.clone()) },
+        ]);
+        let restored = fs::read_to_string(&target).unwrap();
+        let leftovers = (staged.exists(), backup.exists(), kept.exists());
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(restored, "original");
+        assert_eq!(leftovers, (false, false, true));
+    }
+}
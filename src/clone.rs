@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/clone.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rustscrub::scrub::{scrub_stream, ScrubReport, ScrubSettings};
+
+use crate::editorconfig::{EditorConfig, EditorConfigWriter, FinalNewline};
+use crate::walk::is_rust_source;
+
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn temp_dir(prefix: &str) -> Result<TempDir, String> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    let path = env::temp_dir().join(format!("{}-{}-{}", prefix, process::id(), nanos));
+    fs::create_dir_all(&path)
+        .map_err(|e| format!("Failed to create temporary directory '{}': {}", path.display(), e))?;
+    Ok(TempDir(path))
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "git") {
+        Ok(())
+    } else {
+        Err("Git integration requires building rustscrub with the 'git' feature.".to_string())
+    }
+}
+
+pub fn git(args: &[&str], dir: &Path) -> Result<Vec<u8>, String> {
+    check_available()?;
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to run git {}: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn export_dir(
+    dir: &Path,
+    root: &Path,
+    output_dir: Option<&Path>,
+    settings: &ScrubSettings,
+    editorconfig: bool,
+    final_newline: Option<FinalNewline>,
+    reports: &mut Vec<(String, ScrubReport)>,
+) -> Result<(), String> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory '{}': {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+    for path in entries {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if relative == Path::new(".git") {
+            continue;
+        }
+        let file_type = fs::symlink_metadata(&path)
+            .map_err(|e| format!("Failed to read metadata for '{}': {}", path.display(), e))?
+            .file_type();
+        let target = output_dir.map(|output_dir| output_dir.join(relative));
+        if file_type.is_dir() {
+            if let Some(target) = &target {
+                fs::create_dir_all(target)
+                    .map_err(|e| format!("Failed to create directory '{}': {}", target.display(), e))?;
+            }
+            export_dir(&path, root, output_dir, settings, editorconfig, final_newline, reports)?;
+        } else if file_type.is_file() && is_rust_source(&path) {
+            let input_file = File::open(&path)
+                .map_err(|e| format!("Failed to open input file '{}': {}", path.display(), e))?;
+            let mut reader = BufReader::new(input_file);
+            let report = match &target {
+                Some(target) => {
+                    let config = EditorConfig::for_output(target, editorconfig, final_newline)?;
+                    let output_file = File::create(target)
+                        .map_err(|e| format!("Failed to create output file '{}': {}", target.display(), e))?;
+                    let mut writer = EditorConfigWriter::new(BufWriter::new(output_file), config);
+                    let report = scrub_stream(&mut reader, Some(&mut writer), settings)?;
+                    writer
+                        .finish()
+                        .map_err(|e| format!("Failed to flush output file '{}': {}", target.display(), e))?;
+                    report
+                }
+                None => scrub_stream(&mut reader, None, settings)?,
+            };
+            reports.push((relative.to_string_lossy().into_owned(), report));
+        } else if let (true, Some(target)) = (file_type.is_file(), &target) {
+            fs::copy(&path, target)
+                .map_err(|e| format!("Failed to copy '{}' to '{}': {}", path.display(), target.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_source(url: &str, git_ref: Option<&str>) -> Result<(), String> {
+    if url.starts_with('-') {
+        return Err(format!("Invalid --clone URL '{}': it must not start with '-'.", url));
+    }
+    match git_ref {
+        Some(git_ref) if git_ref.starts_with('-') => {
+            Err(format!("Invalid --ref '{}': it must not start with '-'.", git_ref))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn export_revision(
+    url: &str,
+    git_ref: Option<&str>,
+    output_dir: Option<&Path>,
+    settings: &ScrubSettings,
+    editorconfig: bool,
+    final_newline: Option<FinalNewline>,
+) -> Result<Vec<(String, ScrubReport)>, String> {
+    validate_source(url, git_ref)?;
+    let checkout = temp_dir("rustscrub-clone")?;
+    git(&["init", "--quiet"], &checkout.0)?;
+    git(&["fetch", "--quiet", "--depth", "1", "--", url, git_ref.unwrap_or("HEAD")], &checkout.0)?;
+    git(&["checkout", "--quiet", "FETCH_HEAD"], &checkout.0)?;
+
+    if let Some(output_dir) = output_dir {
+        fs::create_dir_all(output_dir)
+            .map_err(|e| format!("Failed to create directory '{}': {}", output_dir.display(), e))?;
+    }
+    let mut reports = Vec::new();
+    export_dir(&checkout.0, &checkout.0, output_dir, settings, editorconfig, final_newline, &mut reports)?;
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_dir_scrubs_sources_and_copies_the_rest() {
+        let source = temp_dir("rustscrub-export-src").unwrap();
+        let output = temp_dir("rustscrub-export-out").unwrap();
+        fs::create_dir_all(source.0.join("src")).unwrap();
+        fs::create_dir_all(source.0.join(".git")).unwrap();
+        fs::write(source.0.join("src/lib.rs"), "// comment\npub fn f() {}\n").unwrap();
+        fs::write(source.0.join("README.md"), "// kept\n").unwrap();
+        fs::write(source.0.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let mut reports = Vec::new();
+        export_dir(&source.0, &source.0, Some(&output.0), &ScrubSettings::default(), true, None, &mut reports).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(Path::new(&reports[0].0), Path::new("src/lib.rs"));
+        assert_eq!(fs::read_to_string(output.0.join("src/lib.rs")).unwrap(), "pub fn f() {}\n");
+        assert_eq!(fs::read_to_string(output.0.join("README.md")).unwrap(), "// kept\n");
+        assert!(!output.0.join(".git").exists());
+    }
+
+    #[test]
+    fn test_option_like_url_and_ref_are_rejected() {
+        assert!(validate_source("https://example.com/repo.git", Some("main")).is_ok());
+        assert!(validate_source("--upload-pack=touch /tmp/x", None).is_err());
+        assert!(validate_source("https://example.com/repo.git", Some("--upload-pack=x")).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/comment_age.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::policy::{blame_days, today};
+use crate::stats::all_comments;
+
+const AGE_BUCKETS: [(&str, i64); 6] = [
+    ("< 1 month", 30),
+    ("1-6 months", 182),
+    ("6-12 months", 365),
+    ("1-2 years", 730),
+    ("2-5 years", 1826),
+    ("> 5 years", i64::MAX),
+];
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AgedComment {
+    pub path: String,
+    pub line: usize,
+    pub age_days: i64,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AgeBucket {
+    pub label: &'static str,
+    pub comments: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CommentAges {
+    pub comments: usize,
+    pub unblamed: usize,
+    pub median_days: Option<i64>,
+    pub older_than_years: u64,
+    pub older_than: usize,
+    pub buckets: Vec<AgeBucket>,
+    pub oldest: Vec<AgedComment>,
+}
+
+impl CommentAges {
+    pub fn new(mut aged: Vec<AgedComment>, unblamed: usize, older_than_years: u64, oldest: usize) -> Self {
+        aged.sort_by(|a, b| b.age_days.cmp(&a.age_days).then_with(|| a.path.cmp(&b.path)).then(a.line.cmp(&b.line)));
+        let limit = (older_than_years as f64 * DAYS_PER_YEAR) as i64;
+        let mut buckets: Vec<AgeBucket> = AGE_BUCKETS.iter().map(|(label, _)| AgeBucket { label, comments: 0 }).collect();
+        for comment in &aged {
+            let index = AGE_BUCKETS.iter().position(|(_, below)| comment.age_days < *below).unwrap_or(AGE_BUCKETS.len() - 1);
+            buckets[index].comments += 1;
+        }
+        CommentAges {
+            comments: aged.len() + unblamed,
+            unblamed,
+            median_days: aged.get(aged.len() / 2).map(|comment| comment.age_days),
+            older_than_years,
+            older_than: aged.iter().filter(|comment| comment.age_days > limit).count(),
+            buckets,
+            oldest: aged.into_iter().take(oldest).collect(),
+        }
+    }
+
+    pub fn print(&self) {
+        println!("{:<14}  {:>10}", "Comments", self.comments);
+        println!("{:<14}  {:>10}", "Not in git", self.unblamed);
+        let median = self.median_days.map_or("-".to_string(), |days| format!("{} days", days));
+        println!("{:<14}  {:>10}", "Median age", median);
+        println!("{:<14}  {:>10}", format!("Older than {}y", self.older_than_years), self.older_than);
+        println!();
+        println!("{:<14}  {:>10}", "Age", "Comments");
+        for bucket in &self.buckets {
+            println!("{:<14}  {:>10}", bucket.label, bucket.comments);
+        }
+        if !self.oldest.is_empty() {
+            println!();
+            println!("Oldest comments:");
+            for comment in &self.oldest {
+                println!("{:>7} days  {}:{}  {}", comment.age_days, comment.path, comment.line, comment.text);
+            }
+        }
+    }
+}
+
+pub fn collect(paths: &[impl AsRef<Path>], older_than_years: u64, oldest: usize) -> Result<CommentAges, String> {
+    let today = today();
+    let mut aged = Vec::new();
+    let mut unblamed = 0;
+    for path in paths {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| format!("Failed to read input file '{}': {}", path.display(), e))?;
+        let comments = all_comments(&source)?;
+        let blame = if comments.is_empty() { None } else { blame_days(path) };
+        for change in comments {
+            let touched = blame
+                .as_ref()
+                .and_then(|days| (change.start_line..=change.end_line).filter_map(|line| days.get(&line).copied()).max());
+            match touched {
+                Some(day) => aged.push(AgedComment {
+                    path: path.display().to_string(),
+                    line: change.start_line,
+                    age_days: (today - day).max(0),
+                    text: change.text.lines().next().unwrap_or_default().trim().to_string(),
+                }),
+                None => unblamed += 1,
+            }
+        }
+    }
+    Ok(CommentAges::new(aged, unblamed, older_than_years, oldest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(line: usize, age_days: i64) -> AgedComment {
+        AgedComment {
+            path: "a.rs".to_string(),
+            line,
+            age_days,
+            text: format!("// {}", line),
+        }
+    }
+
+    #[test]
+    fn test_ages_are_bucketed_and_ranked() {
+        let aged = vec![comment(1, 10), comment(2, 400), comment(3, 3000), comment(4, 800), comment(5, 100)];
+        let ages = CommentAges::new(aged, 2, 2, 2);
+        assert_eq!(ages.comments, 7);
+        assert_eq!(ages.median_days, Some(400));
+        assert_eq!(ages.older_than, 2);
+        let counts: Vec<usize> = ages.buckets.iter().map(|bucket| bucket.comments).collect();
+        assert_eq!(counts, vec![1, 1, 0, 1, 1, 1]);
+        let oldest: Vec<usize> = ages.oldest.iter().map(|comment| comment.line).collect();
+        assert_eq!(oldest, vec![3, 4]);
+        assert_eq!(CommentAges::new(Vec::new(), 0, 2, 10).median_days, None);
+    }
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/comment_owners.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::classify::{classify, CommentCategory};
+
+use crate::policy::{blame_lines, embedded_date, today};
+use crate::stats::all_comments;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OwnedTodo {
+    pub path: String,
+    pub line: usize,
+    pub age_days: i64,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CommentOwner {
+    pub author: String,
+    pub comments: usize,
+    pub comment_lines: usize,
+    pub todos: usize,
+    pub oldest_todo: Option<OwnedTodo>,
+}
+
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct CommentOwners {
+    pub owners: Vec<CommentOwner>,
+    pub unblamed: usize,
+}
+
+impl CommentOwners {
+    fn owner(&mut self, author: &str) -> &mut CommentOwner {
+        let index = match self.owners.iter().position(|owner| owner.author == author) {
+            Some(index) => index,
+            None => {
+                self.owners.push(CommentOwner {
+                    author: author.to_string(),
+                    comments: 0,
+                    comment_lines: 0,
+                    todos: 0,
+                    oldest_todo: None,
+                });
+                self.owners.len() - 1
+            }
+        };
+        &mut self.owners[index]
+    }
+
+    pub fn add(&mut self, author: &str, lines: usize, todo: Option<OwnedTodo>) {
+        let owner = self.owner(author);
+        owner.comments += 1;
+        owner.comment_lines += lines;
+        if let Some(todo) = todo {
+            owner.todos += 1;
+            if owner.oldest_todo.as_ref().is_none_or(|oldest| todo.age_days > oldest.age_days) {
+                owner.oldest_todo = Some(todo);
+            }
+        }
+    }
+
+    pub fn sort(&mut self) {
+        self.owners
+            .sort_by(|a, b| b.comments.cmp(&a.comments).then(b.todos.cmp(&a.todos)).then_with(|| a.author.cmp(&b.author)));
+    }
+
+    pub fn print(&self) {
+        println!("{:<24}  {:>8}  {:>8}  {:>6}  {:>10}  Oldest TODO", "Author", "Comments", "Lines", "TODOs", "TODO age");
+        for owner in &self.owners {
+            let (age, location) = match &owner.oldest_todo {
+                Some(todo) => (format!("{} days", todo.age_days), format!("{}:{}  {}", todo.path, todo.line, todo.text)),
+                None => ("-".to_string(), String::new()),
+            };
+            let row = format!(
+                "{:<24}  {:>8}  {:>8}  {:>6}  {:>10}  {}",
+                owner.author, owner.comments, owner.comment_lines, owner.todos, age, location
+            );
+            println!("{}", row.trim_end());
+        }
+        if self.unblamed > 0 {
+            println!("{} comments are not in git.", self.unblamed);
+        }
+    }
+}
+
+pub fn collect(paths: &[impl AsRef<Path>]) -> Result<CommentOwners, String> {
+    let today = today();
+    let mut owners = CommentOwners::default();
+    for path in paths {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| format!("Failed to read input file '{}': {}", path.display(), e))?;
+        let comments = all_comments(&source)?;
+        let blame = if comments.is_empty() { None } else { blame_lines(path) };
+        for change in comments {
+            let mut lines: HashMap<&str, (usize, i64)> = HashMap::new();
+            for line in change.start_line..=change.end_line {
+                if let Some(blame) = blame.as_ref().and_then(|blame| blame.get(&line)) {
+                    let entry = lines.entry(blame.author.as_str()).or_insert((0, blame.day));
+                    entry.0 += 1;
+                    entry.1 = entry.1.max(blame.day);
+                }
+            }
+            let Some((author, (count, day))) = lines.into_iter().max_by(|a, b| a.1 .0.cmp(&b.1 .0).then_with(|| b.0.cmp(a.0))) else {
+                owners.unblamed += 1;
+                continue;
+            };
+            let todo = (classify(&change.as_comment()) == CommentCategory::Todo).then(|| OwnedTodo {
+                path: path.display().to_string(),
+                line: change.start_line,
+                age_days: (today - embedded_date(&change.text).unwrap_or(day)).max(0),
+                text: change.text.lines().next().unwrap_or_default().trim().to_string(),
+            });
+            owners.add(author, count, todo);
+        }
+    }
+    owners.sort();
+    Ok(owners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(line: usize, age_days: i64) -> Option<OwnedTodo> {
+        Some(OwnedTodo {
+            path: "a.rs".to_string(),
+            line,
+            age_days,
+            text: "// TODO".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_owners_rank_by_comments_and_keep_oldest_todo() {
+        let mut owners = CommentOwners::default();
+        owners.add("Grace", 1, todo(1, 30));
+        owners.add("Ada", 2, None);
+        owners.add("Grace", 3, todo(9, 400));
+        owners.add("Grace", 1, todo(5, 100));
+        owners.sort();
+        let authors: Vec<&str> = owners.owners.iter().map(|owner| owner.author.as_str()).collect();
+        assert_eq!(authors, vec!["Grace", "Ada"]);
+        assert_eq!((owners.owners[0].comments, owners.owners[0].comment_lines, owners.owners[0].todos), (3, 5, 3));
+        assert_eq!(owners.owners[0].oldest_todo.as_ref().map(|todo| todo.line), Some(9));
+        assert!(owners.owners[1].oldest_todo.is_none());
+    }
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/compress.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Compress {
+    Gzip,
+    Zstd,
+}
+
+impl Compress {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".gz") {
+            Some(Compress::Gzip)
+        } else if lower.ends_with(".zst") {
+            Some(Compress::Zstd)
+        } else {
+            None
+        }
+    }
+
+    pub fn check_available(self) -> Result<(), String> {
+        match self {
+            Compress::Gzip if !cfg!(feature = "gzip") => Err("gzip output requires building rustscrub with the 'gzip' feature.".to_string()),
+            Compress::Zstd if !cfg!(feature = "zstd") => Err("zstd output requires building rustscrub with the 'zstd' feature.".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub enum CompressedWriter<W: Write> {
+    Plain(W),
+    #[cfg(feature = "gzip")]
+    Gzip(GzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    pub fn new(inner: W, compress: Option<Compress>) -> Result<Self, String> {
+        match compress {
+            None => Ok(CompressedWriter::Plain(inner)),
+            #[cfg(feature = "gzip")]
+            Some(Compress::Gzip) => Ok(CompressedWriter::Gzip(GzEncoder::new(inner, flate2::Compression::default()))),
+            #[cfg(not(feature = "gzip"))]
+            Some(Compress::Gzip) => Compress::Gzip.check_available().map(|_| CompressedWriter::Plain(inner)),
+            #[cfg(feature = "zstd")]
+            Some(Compress::Zstd) => zstd::Encoder::new(inner, 0)
+                .map(CompressedWriter::Zstd)
+                .map_err(|e| format!("Failed to start zstd stream: {}", e)),
+            #[cfg(not(feature = "zstd"))]
+            Some(Compress::Zstd) => Compress::Zstd.check_available().map(|_| CompressedWriter::Plain(inner)),
+        }
+    }
+
+    #[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(clippy::infallible_destructuring_match))]
+    pub fn finish(self) -> io::Result<W> {
+        let mut inner = match self {
+            CompressedWriter::Plain(inner) => inner,
+            #[cfg(feature = "gzip")]
+            CompressedWriter::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "zstd")]
+            CompressedWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CompressedWriter::Plain(inner) => inner.write(buf),
+            #[cfg(feature = "gzip")]
+            CompressedWriter::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "zstd")]
+            CompressedWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CompressedWriter::Plain(inner) => inner.flush(),
+            #[cfg(feature = "gzip")]
+            CompressedWriter::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "zstd")]
+            CompressedWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+pub fn create_file(path: &Path, compress: Option<Compress>) -> Result<CompressedWriter<BufWriter<File>>, String> {
+    let output_file = File::create(path)
+        .map_err(|e| format!("Failed to create output file '{}': {}", path.display(), e))?;
+    let compress = compress.or_else(|| Compress::from_path(&path.to_string_lossy()));
+    CompressedWriter::new(BufWriter::new(output_file), compress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(Compress::from_path("pack.txt.gz"), Some(Compress::Gzip));
+        assert_eq!(Compress::from_path("lib.rs.ZST"), Some(Compress::Zstd));
+        assert_eq!(Compress::from_path("lib.rs"), None);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_round_trip() {
+        use std::io::Read;
+
+        let mut writer = CompressedWriter::new(Vec::new(), Some(Compress::Gzip)).unwrap();
+        writer.write_all(b"fn main() {}\n").unwrap();
+        let compressed = writer.finish().unwrap();
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice()).read_to_string(&mut text).unwrap();
+        assert_eq!(text, "fn main() {}\n");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_round_trip() {
+        let mut writer = CompressedWriter::new(Vec::new(), Some(Compress::Zstd)).unwrap();
+        writer.write_all(b"fn main() {}\n").unwrap();
+        let compressed = writer.finish().unwrap();
+        assert_eq!(zstd::decode_all(compressed.as_slice()).unwrap(), b"fn main() {}\n");
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/config.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use rustscrub::schema;
+
+use crate::status::Failure;
+
+pub const DEFAULT_CONFIG_FILE: &str = "rustscrub.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Off,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TodoAgeRule {
+    pub days: u64,
+    #[serde(default = "default_rule_severity")]
+    pub severity: Severity,
+}
+
+fn default_rule_severity() -> Severity {
+    Severity::Warning
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suppression {
+    pub rule: Option<String>,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub fingerprints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PolicyConfig {
+    pub doc_required_on_pub: Severity,
+    pub no_commented_out_code: Severity,
+    pub todo_max_age: Option<TodoAgeRule>,
+    pub suppress: Vec<Suppression>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BannedConfig {
+    pub profanity: bool,
+    pub phrases: Vec<String>,
+    pub wordlists: Vec<String>,
+}
+
+impl Default for BannedConfig {
+    fn default() -> Self {
+        BannedConfig {
+            profanity: true,
+            phrases: Vec::new(),
+            wordlists: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    pub keep: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl RulesConfig {
+    pub fn is_empty(&self) -> bool {
+        self.keep.is_empty() && self.remove.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub policy: PolicyConfig,
+    pub banned: BannedConfig,
+    pub rules: RulesConfig,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Failed to parse config: {}", e))
+    }
+
+    pub fn load(path: Option<&str>) -> Result<Self, Failure> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(DEFAULT_CONFIG_FILE).is_file() => DEFAULT_CONFIG_FILE,
+            None => return Ok(Config::default()),
+        };
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file '{}': {}", path, e))?;
+        schema::record_config(&text);
+        Config::parse(&text).map_err(|e| Failure::usage(format!("{} ({})", e, path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::Status;
+
+    #[test]
+    fn test_parse_policy_section() {
+        let config = Config::parse(
+            r#"
+            [policy]
+            doc-required-on-pub = "error"
+            todo-max-age = { days = 180 }
+
+            [[policy.suppress]]
+            rule = "doc-required-on-pub"
+            paths = ["src/generated/**"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.policy.doc_required_on_pub, Severity::Error);
+        assert_eq!(config.policy.no_commented_out_code, Severity::Off);
+        assert_eq!(config.policy.todo_max_age.unwrap().severity, Severity::Warning);
+        assert_eq!(config.policy.suppress[0].paths, vec!["src/generated/**"]);
+        assert!(Config::parse("[policy]\nunknown-rule = \"error\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_banned_section() {
+        assert!(Config::default().banned.profanity);
+        let config = Config::parse("[banned]\nprofanity = false\nphrases = [\"Project Falcon\"]\n").unwrap();
+        assert!(!config.banned.profanity);
+        assert_eq!(config.banned.phrases, vec!["Project Falcon"]);
+    }
+
+    #[test]
+    fn test_parse_rules_section() {
+        assert!(Config::default().rules.is_empty());
+        let config = Config::parse("[rules]\nkeep = ['comment.text.contains(\"SAFETY\")']\n").unwrap();
+        assert_eq!(config.rules.keep, vec![r#"comment.text.contains("SAFETY")"#]);
+        assert!(config.rules.remove.is_empty());
+        assert!(Config::parse("[rules]\nkeep = 'comment.lines > 5'\n").is_err());
+    }
+
+    #[test]
+    fn test_malformed_config_file_is_a_usage_error() {
+        let path = std::env::temp_dir().join(format!("rustscrub-config-{}.toml", std::process::id()));
+        fs::write(&path, "[policy\n").unwrap();
+        let result = Config::load(path.to_str());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap_err().status, Status::Usage);
+        assert_eq!(Config::load(Some("/nonexistent/rustscrub.toml")).unwrap_err().status, Status::Io);
+    }
+}
@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/diff_comments.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashSet;
+use std::fs;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::classify::comment_blocks;
+use rustscrub::filter::CommentFilter;
+use rustscrub::schema;
+use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+use crate::classify_report::ClassifyFormat;
+use crate::status::Status;
+
+const MIN_SIMILARITY: f64 = 0.5;
+
+#[derive(clap::Args, Debug)]
+pub struct DiffCommentsArgs {
+    #[clap(value_parser)]
+    old: String,
+
+    #[clap(value_parser)]
+    new: String,
+
+    #[clap(long, value_enum, default_value_t = ClassifyFormat::Table)]
+    format: ClassifyFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct CommentText {
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ModifiedComment {
+    pub old: CommentText,
+    pub new: CommentText,
+}
+
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct CommentDiff {
+    pub added: Vec<CommentText>,
+    pub removed: Vec<CommentText>,
+    pub modified: Vec<ModifiedComment>,
+    pub unchanged: usize,
+}
+
+fn extract(source: &str) -> Result<Vec<CommentText>, String> {
+    let settings = ScrubSettings {
+        filter: CommentFilter {
+            keep_directives: false,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    };
+    let (_, report) = scrub_text(source, &settings)?;
+    Ok(comment_blocks(&report.changes)
+        .into_iter()
+        .map(|(line, text)| CommentText {
+            line,
+            text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        })
+        .filter(|comment| !comment.text.is_empty())
+        .collect())
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    let words = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+pub fn diff_comments(old: &str, new: &str) -> Result<CommentDiff, String> {
+    let mut removed = extract(old)?;
+    let mut added = extract(new)?;
+    let mut diff = CommentDiff::default();
+
+    removed.retain(|comment| match added.iter().position(|candidate| candidate.text == comment.text) {
+        Some(index) => {
+            added.remove(index);
+            diff.unchanged += 1;
+            false
+        }
+        None => true,
+    });
+
+    for comment in removed {
+        let best = added
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| (index, similarity(&comment.text, &candidate.text)))
+            .filter(|&(_, score)| score >= MIN_SIMILARITY)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((index, _)) => diff.modified.push(ModifiedComment {
+                old: comment,
+                new: added.remove(index),
+            }),
+            None => diff.removed.push(comment),
+        }
+    }
+    diff.added = added;
+    Ok(diff)
+}
+
+pub fn run(args: &DiffCommentsArgs) -> Result<Status, String> {
+    let read = |path: &str| {
+        fs::read_to_string(path).map_err(|e| format!("Failed to read input file '{}': {}", path, e))
+    };
+    let diff = diff_comments(&read(&args.old)?, &read(&args.new)?)?;
+
+    match args.format {
+        ClassifyFormat::Table => {
+            for comment in &diff.removed {
+                println!("removed  {}:{}: {}", args.old, comment.line, comment.text);
+            }
+            for comment in &diff.added {
+                println!("added    {}:{}: {}", args.new, comment.line, comment.text);
+            }
+            for comment in &diff.modified {
+                println!("modified {}:{} -> {}:{}", args.old, comment.old.line, args.new, comment.new.line);
+                println!("    - {}", comment.old.text);
+                println!("    + {}", comment.new.text);
+            }
+        }
+        ClassifyFormat::Json => {
+            let json = schema::to_json("comment-diff", &diff)
+                .map_err(|e| format!("Failed to serialize comment diff: {}", e))?;
+            println!("{}", json);
+        }
+    }
+    eprintln!(
+        "RustScrub: {} comments added, {} removed, {} modified, {} unchanged",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.modified.len(),
+        diff.unchanged
+    );
+    if diff.added.is_empty() && diff.removed.is_empty() && diff.modified.is_empty() {
+        Ok(Status::Clean)
+    } else {
+        Ok(Status::Changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_comments_classifies_changes() {
+        let old = "// Keeps the cache warm.\nfn a() {}\n// The timeout is in seconds because the server rounds.\nfn b() {}\n// Safe to call twice.\nfn c() {}\n";
+        let new = "fn b() {}\n// Keeps the cache warm.\nfn a() {}\n// Safe to call twice from any thread.\nfn c() {}\n/// Builds the client.\nfn d() {}\n";
+        let diff = diff_comments(old, new).unwrap();
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.removed, vec![CommentText { line: 3, text: "The timeout is in seconds because the server rounds.".to_string() }]);
+        assert_eq!(diff.added, vec![CommentText { line: 6, text: "Builds the client.".to_string() }]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!((diff.modified[0].old.line, diff.modified[0].new.line), (5, 4));
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/doc_coverage.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+
+use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+use crate::status::Failure;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicItem {
+    pub name: String,
+    pub line: usize,
+    pub documented: bool,
+}
+
+#[cfg(feature = "items")]
+mod items {
+    use syn::spanned::Spanned;
+    use syn::{Attribute, ImplItem, Item, Visibility};
+
+    use super::PublicItem;
+
+    fn push(found: &mut Vec<PublicItem>, prefix: &str, name: String, attrs: &[Attribute], node: &impl Spanned) {
+        found.push(PublicItem {
+            name: format!("{}{}", prefix, name),
+            line: node.span().start().line,
+            documented: attrs.iter().any(|attr| attr.path().is_ident("doc")),
+        });
+    }
+
+    pub fn collect(items: &[Item], prefix: &str, found: &mut Vec<PublicItem>) {
+        for item in items {
+            let (vis, name, attrs) = match item {
+                Item::Const(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Enum(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Fn(item) => (&item.vis, item.sig.ident.to_string(), &item.attrs),
+                Item::Static(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Struct(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Trait(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Type(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Union(item) => (&item.vis, item.ident.to_string(), &item.attrs),
+                Item::Mod(module) => {
+                    if let Some((_, content)) = &module.content {
+                        collect(content, &format!("{}{}::", prefix, module.ident), found);
+                    }
+                    (&module.vis, module.ident.to_string(), &module.attrs)
+                }
+                Item::Impl(item) => {
+                    if let syn::Type::Path(self_ty) = &*item.self_ty {
+                        if let Some(segment) = self_ty.path.segments.last() {
+                            let prefix = format!("{}{}::", prefix, segment.ident);
+                            for impl_item in &item.items {
+                                match impl_item {
+                                    ImplItem::Fn(function) if matches!(function.vis, Visibility::Public(_)) => {
+                                        push(found, &prefix, function.sig.ident.to_string(), &function.attrs, function)
+                                    }
+                                    ImplItem::Const(constant) if matches!(constant.vis, Visibility::Public(_)) => {
+                                        push(found, &prefix, constant.ident.to_string(), &constant.attrs, constant)
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            if matches!(vis, Visibility::Public(_)) {
+                push(found, prefix, name, attrs, item);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "items")]
+pub fn public_items(source: &str, path: &str) -> Result<Vec<PublicItem>, String> {
+    let parsed = syn::parse_file(source).map_err(|e| format!("Failed to parse '{}' for doc coverage: {}", path, e))?;
+    let mut found = Vec::new();
+    items::collect(&parsed.items, "", &mut found);
+    Ok(found)
+}
+
+#[cfg(not(feature = "items"))]
+pub fn public_items(_source: &str, _path: &str) -> Result<Vec<PublicItem>, String> {
+    check_available().map(|_| Vec::new())
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "items") {
+        Ok(())
+    } else {
+        Err("--min-doc-coverage requires building rustscrub with the 'items' feature.".to_string())
+    }
+}
+
+fn percent(documented: usize, public: usize) -> f64 {
+    if public == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / public as f64
+    }
+}
+
+pub fn run(paths: &[String], settings: &ScrubSettings, min_coverage: f64) -> Result<(), Failure> {
+    let mut public = 0;
+    let mut before = 0;
+    let mut after = 0;
+    for path in paths {
+        let source = fs::read_to_string(path).map_err(|e| format!("Failed to read input file '{}': {}", path, e))?;
+        let (scrubbed, _) = scrub_text(&source, settings)?;
+        let original = public_items(&source, path)?;
+        let kept = public_items(&scrubbed, path)?;
+        for (item, kept) in original.iter().zip(&kept) {
+            if item.documented && !kept.documented {
+                eprintln!("{}:{}: doc comment of public item `{}` would be removed", path, item.line, item.name);
+            }
+        }
+        public += original.len();
+        before += original.iter().filter(|item| item.documented).count();
+        after += kept.iter().filter(|item| item.documented).count();
+    }
+    let coverage = percent(after, public);
+    eprintln!(
+        "RustScrub Doc Coverage: {:.1}% before and {:.1}% after scrubbing ({} public items in {} files)",
+        percent(before, public),
+        coverage,
+        public,
+        paths.len()
+    );
+    if coverage < min_coverage {
+        Err(Failure::changes(format!(
+            "Check failed: doc coverage after scrubbing is {:.1}%, below the minimum of {:.1}%",
+            coverage, min_coverage
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "items"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_items_and_their_docs() {
+        let source = "/// Documented.\n#[inline]\npub fn a() {}\nfn private() {}\npub(crate) fn c() {}\npub struct S;\nimpl S {\n    /// Method.\n    pub fn m(&self) {}\n    fn hidden(&self) {}\n}\npub mod inner {\n    pub const X: u8 = 1;\n}\n";
+        let items = public_items(source, "lib.rs").unwrap();
+        let summary: Vec<(&str, usize, bool)> = items.iter().map(|item| (item.name.as_str(), item.line, item.documented)).collect();
+        assert_eq!(
+            summary,
+            vec![("a", 1, true), ("S", 6, false), ("S::m", 8, true), ("inner::X", 13, false), ("inner", 12, false)]
+        );
+        let (scrubbed, _) = scrub_text(source, &ScrubSettings::default()).unwrap();
+        assert!(public_items(&scrubbed, "lib.rs").unwrap().iter().all(|item| !item.documented));
+        assert_eq!(percent(2, 5), 40.0);
+        assert_eq!(percent(0, 0), 100.0);
+    }
+}
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/doctests.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use rustscrub::filter::DocStyle;
+use rustscrub::scrub::{ChangeInfo, VerboseCommentType};
+
+const RUSTDOC_ATTRIBUTES: [&str; 8] = [
+    "rust",
+    "no_run",
+    "should_panic",
+    "ignore",
+    "compile_fail",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Doctest {
+    pub path: String,
+    pub line: usize,
+    pub code: String,
+    pub no_run: bool,
+    pub should_panic: bool,
+}
+
+fn doc_lines(change: &ChangeInfo) -> Vec<(usize, String)> {
+    let comment = change.as_comment();
+    let body = match comment.comment_type {
+        VerboseCommentType::Line => &change.text[3..],
+        VerboseCommentType::Block => {
+            let inner = &change.text[3..];
+            inner.strip_suffix("*/").unwrap_or(inner)
+        }
+    };
+    body.split('\n')
+        .enumerate()
+        .map(|(offset, line)| {
+            let line = line.trim_end_matches('\r');
+            let line = match comment.comment_type {
+                VerboseCommentType::Block if offset > 0 => {
+                    let trimmed = line.trim_start();
+                    trimmed.strip_prefix('*').unwrap_or(line)
+                }
+                _ => line,
+            };
+            (change.start_line + offset, line.strip_prefix(' ').unwrap_or(line).to_string())
+        })
+        .collect()
+}
+
+fn runnable_fence(info: &str) -> Option<(bool, bool)> {
+    let tokens: Vec<&str> = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .collect();
+    let is_rust = tokens.iter().all(|token| RUSTDOC_ATTRIBUTES.contains(token));
+    if !is_rust || tokens.contains(&"ignore") || tokens.contains(&"compile_fail") {
+        return None;
+    }
+    Some((tokens.contains(&"no_run"), tokens.contains(&"should_panic")))
+}
+
+fn unhide(line: &str) -> &str {
+    if line == "#" {
+        ""
+    } else {
+        line.strip_prefix("# ").unwrap_or(line)
+    }
+}
+
+fn fence_width(line: &str) -> usize {
+    line.trim_start().chars().take_while(|&c| c == '`').count()
+}
+
+fn collect_blocks(path: &str, lines: &[(usize, String)], doctests: &mut Vec<Doctest>) {
+    let mut index = 0;
+    while index < lines.len() {
+        let (start, opening) = &lines[index];
+        let width = fence_width(opening);
+        index += 1;
+        if width < 3 {
+            continue;
+        }
+        let kind = runnable_fence(&opening.trim_start()[width..]);
+        let mut code = String::new();
+        while index < lines.len() && fence_width(&lines[index].1) < width {
+            code.push_str(unhide(&lines[index].1));
+            code.push('\n');
+            index += 1;
+        }
+        index += 1;
+        if let Some((no_run, should_panic)) = kind {
+            doctests.push(Doctest {
+                path: path.to_string(),
+                line: *start,
+                code,
+                no_run,
+                should_panic,
+            });
+        }
+    }
+}
+
+pub fn extract(path: &str, changes: &[ChangeInfo]) -> Vec<Doctest> {
+    let mut doctests = Vec::new();
+    let mut lines = Vec::new();
+    let mut previous: Option<(DocStyle, usize)> = None;
+    for change in changes {
+        let Some(style) = change.as_comment().doc_style() else {
+            continue;
+        };
+        let continues = previous
+            .is_some_and(|(previous_style, end_line)| previous_style == style && change.start_line == end_line + 1);
+        if !continues {
+            collect_blocks(path, &lines, &mut doctests);
+            lines.clear();
+        }
+        lines.extend(doc_lines(change));
+        previous = Some((style, change.end_line));
+    }
+    collect_blocks(path, &lines, &mut doctests);
+    doctests
+}
+
+fn test_name(doctest: &Doctest) -> String {
+    let path: String = doctest
+        .path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("doctest_{}_{}", path.trim_matches('_'), doctest.line)
+}
+
+fn indent(code: &str, depth: usize) -> String {
+    code.lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                "\n".to_string()
+            } else {
+                format!("{}{}\n", "    ".repeat(depth), line)
+            }
+        })
+        .collect()
+}
+
+pub fn render(doctests: &[Doctest]) -> String {
+    let mut out = String::from("// Doctests extracted by rustscrub from removed doc comments.\n");
+    for doctest in doctests {
+        out.push_str(&format!("\n// {}:{}\n#[test]\n", doctest.path, doctest.line));
+        if doctest.no_run {
+            out.push_str("#[ignore = \"no_run doctest\"]\n");
+        }
+        if doctest.should_panic {
+            out.push_str("#[should_panic]\n");
+        }
+        out.push_str(&format!("#[allow(unused)]\nfn {}() {{\n", test_name(doctest)));
+        let returns_result = doctest
+            .code
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .is_some_and(|line| line.trim_start().starts_with("Ok::<"));
+        let main_signature = doctest.code.lines().find(|line| line.contains("fn main("));
+        if let Some(signature) = main_signature {
+            out.push_str(&indent(&doctest.code, 1));
+            if signature.contains("->") {
+                out.push_str("    main().unwrap();\n");
+            } else {
+                out.push_str("    main();\n");
+            }
+        } else if returns_result {
+            out.push_str("    (|| {\n");
+            out.push_str(&indent(&doctest.code, 2));
+            out.push_str("    })()\n    .unwrap();\n");
+        } else {
+            out.push_str(&indent(&doctest.code, 1));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    fn doctests(source: &str) -> Vec<Doctest> {
+        let (_, report) = scrub_text(source, &ScrubSettings::default()).unwrap();
+        extract("src/lib.rs", &report.changes)
+    }
+
+    #[test]
+    fn test_extracts_runnable_blocks() {
+        let source = "/// Adds one.\n///\n/// ```\n/// # use demo::add_one;\n/// assert_eq!(add_one(1), 2);\n/// ```\n///\n/// ```text\n/// not code\n/// ```\npub fn add_one(x: i32) -> i32 { x + 1 }\n\n/**\n * ```no_run\n * demo::serve();\n * ```\n */\npub fn serve() {}\n";
+        let found = doctests(source);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].line, 3);
+        assert_eq!(found[0].code, "use demo::add_one;\nassert_eq!(add_one(1), 2);\n");
+        assert!(!found[0].no_run);
+        assert_eq!(found[1].code, "demo::serve();\n");
+        assert!(found[1].no_run);
+    }
+
+    #[test]
+    fn test_render_wraps_result_blocks() {
+        let source = "//! ```\n//! let n: i32 = \"4\".parse()?;\n//! Ok::<(), std::num::ParseIntError>(())\n//! ```\n";
+        let rendered = render(&doctests(source));
+        assert!(rendered.contains("fn doctest_src_lib_rs_1() {\n    (|| {\n        let n: i32 = \"4\".parse()?;\n"));
+        assert!(rendered.contains("    })()\n    .unwrap();\n}\n"));
+    }
+
+    #[test]
+    fn test_render_unwraps_result_returning_main() {
+        let source = "//! ```\n//! fn main() -> Result<(), std::num::ParseIntError> {\n//!     let _n: i32 = \"4\".parse()?;\n//!     Ok(())\n//! }\n//! ```\n//!\n//! ```\n//! fn main() {}\n//! ```\n";
+        let rendered = render(&doctests(source));
+        assert!(rendered.contains("    }\n    main().unwrap();\n}\n"));
+        assert!(rendered.contains("    fn main() {}\n    main();\n}\n"));
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/duplicates.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::classify::{classify, comment_blocks, CommentCategory};
+use rustscrub::scrub::ChangeInfo;
+
+use crate::language::snippet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Location {
+    pub path: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DuplicateComment {
+    pub snippet: String,
+    pub locations: Vec<Location>,
+}
+
+pub struct DuplicateFinder {
+    min_chars: usize,
+    index: HashMap<String, usize>,
+    comments: Vec<DuplicateComment>,
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_boilerplate(change: &ChangeInfo) -> bool {
+    matches!(
+        classify(&change.as_comment()),
+        CommentCategory::License | CommentCategory::Directive
+    )
+}
+
+impl DuplicateFinder {
+    pub fn new(min_chars: usize) -> Self {
+        DuplicateFinder {
+            min_chars,
+            index: HashMap::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, path: &str, changes: &[ChangeInfo]) {
+        for (line, text) in comment_blocks(changes.iter().filter(|change| !is_boilerplate(change))) {
+            let key = normalize(&text);
+            if key.chars().count() <= self.min_chars {
+                continue;
+            }
+            let location = Location {
+                path: path.to_string(),
+                line,
+            };
+            match self.index.get(&key) {
+                Some(&position) => self.comments[position].locations.push(location),
+                None => {
+                    self.index.insert(key, self.comments.len());
+                    self.comments.push(DuplicateComment {
+                        snippet: snippet(&text),
+                        locations: vec![location],
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> Vec<DuplicateComment> {
+        self.comments
+            .into_iter()
+            .filter(|comment| comment.locations.len() > 1)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustscrub::filter::CommentFilter;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    fn changes(source: &str) -> Vec<ChangeInfo> {
+        let settings = ScrubSettings {
+            filter: CommentFilter {
+                keep_directives: false,
+                ..CommentFilter::default()
+            },
+            ..ScrubSettings::default()
+        };
+        scrub_text(source, &settings).unwrap().1.changes
+    }
+
+    #[test]
+    fn test_reports_comments_repeated_across_files() {
+        let mut finder = DuplicateFinder::new(20);
+        finder.add(
+            "a.rs",
+            &changes("// SPDX-License-Identifier: MIT\n// Retry the request up to three times\n// before giving up.\nfn a() {}\n// short note\n"),
+        );
+        finder.add(
+            "b.rs",
+            &changes("// SPDX-License-Identifier: MIT\nfn b() {}\n//   retry the request up to three times\n// before  giving up.\nfn c() {}\n// short note\n"),
+        );
+        let duplicates = finder.finish();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].snippet, "Retry the request up to three times before giving up.");
+        assert_eq!(
+            duplicates[0].locations,
+            vec![
+                Location { path: "a.rs".to_string(), line: 2 },
+                Location { path: "b.rs".to_string(), line: 3 },
+            ]
+        );
+    }
+}
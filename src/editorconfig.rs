@@ -0,0 +1,287 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/editorconfig.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{self, Path};
+
+use crate::gitattributes::LineEndings;
+use crate::glob::Glob;
+
+const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl EndOfLine {
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            EndOfLine::Lf => b"\n",
+            EndOfLine::Crlf => b"\r\n",
+            EndOfLine::Cr => b"\r",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FinalNewline {
+    Always,
+    Never,
+    Preserve,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorConfig {
+    pub end_of_line: Option<EndOfLine>,
+    pub insert_final_newline: Option<bool>,
+    pub trim_trailing_whitespace: Option<bool>,
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+impl EditorConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == EditorConfig::default()
+    }
+
+    fn apply_section(&mut self, content: &str, relative: &str) -> bool {
+        let mut is_root = false;
+        let mut matches = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                matches = Some(Glob::new(section).is_match(relative));
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().to_ascii_lowercase();
+            match (matches, key.as_str()) {
+                (None, "root") => is_root = value == "true",
+                (Some(true), "end_of_line") => {
+                    self.end_of_line = match value.as_str() {
+                        "lf" => Some(EndOfLine::Lf),
+                        "crlf" => Some(EndOfLine::Crlf),
+                        "cr" => Some(EndOfLine::Cr),
+                        _ => None,
+                    }
+                }
+                (Some(true), "insert_final_newline") => self.insert_final_newline = parse_bool(&value),
+                (Some(true), "trim_trailing_whitespace") => self.trim_trailing_whitespace = parse_bool(&value),
+                _ => {}
+            }
+        }
+        is_root
+    }
+
+    pub fn for_output(path: &Path, editorconfig: bool, final_newline: Option<FinalNewline>) -> Result<EditorConfig, String> {
+        let mut config = if editorconfig { EditorConfig::for_path(path)? } else { EditorConfig::default() };
+        LineEndings::for_path(path)?.apply(&mut config);
+        Ok(config.with_final_newline(final_newline))
+    }
+
+    pub fn with_final_newline(mut self, final_newline: Option<FinalNewline>) -> EditorConfig {
+        match final_newline {
+            Some(FinalNewline::Always) => self.insert_final_newline = Some(true),
+            Some(FinalNewline::Never) => self.insert_final_newline = Some(false),
+            Some(FinalNewline::Preserve) => self.insert_final_newline = None,
+            None => {}
+        }
+        self
+    }
+
+    pub fn for_path(path: &Path) -> Result<EditorConfig, String> {
+        let absolute = path::absolute(path)
+            .map_err(|e| format!("Failed to resolve path '{}': {}", path.display(), e))?;
+        let mut files = Vec::new();
+        for dir in absolute.ancestors().skip(1) {
+            let config_path = dir.join(EDITORCONFIG_FILE);
+            let content = match fs::read_to_string(&config_path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(format!("Failed to read '{}': {}", config_path.display(), e)),
+            };
+            let relative = absolute.strip_prefix(dir).unwrap_or(&absolute).to_string_lossy().replace('\\', "/");
+            let is_root = EditorConfig::default().apply_section(&content, &relative);
+            files.push((content, relative));
+            if is_root {
+                break;
+            }
+        }
+        let mut config = EditorConfig::default();
+        for (content, relative) in files.iter().rev() {
+            config.apply_section(content, relative);
+        }
+        Ok(config)
+    }
+}
+
+pub struct EditorConfigWriter<W: Write> {
+    inner: W,
+    config: EditorConfig,
+    line: Vec<u8>,
+    held_newlines: Vec<u8>,
+}
+
+impl<W: Write> EditorConfigWriter<W> {
+    pub fn new(inner: W, config: EditorConfig) -> Self {
+        EditorConfigWriter {
+            inner,
+            config,
+            line: Vec::new(),
+            held_newlines: Vec::new(),
+        }
+    }
+
+    fn trimmed_line(&self) -> &[u8] {
+        let line = self.line.strip_suffix(b"\r").unwrap_or(&self.line);
+        if self.config.trim_trailing_whitespace == Some(true) {
+            line.trim_ascii_end()
+        } else {
+            line
+        }
+    }
+
+    fn write_held_newlines(&mut self) -> io::Result<()> {
+        self.inner.write_all(&self.held_newlines)?;
+        self.held_newlines.clear();
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        let original = if self.line.ends_with(b"\r") { EndOfLine::Crlf } else { EndOfLine::Lf };
+        let line = self.trimmed_line().to_vec();
+        if !line.is_empty() || self.config.insert_final_newline != Some(false) {
+            self.write_held_newlines()?;
+            self.inner.write_all(&line)?;
+        }
+        self.line.clear();
+        self.held_newlines.extend_from_slice(self.config.end_of_line.unwrap_or(original).as_bytes());
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.line.is_empty() {
+            if self.config.insert_final_newline != Some(false) {
+                self.write_held_newlines()?;
+            }
+        } else {
+            self.write_held_newlines()?;
+            let line = self.trimmed_line().to_vec();
+            self.inner.write_all(&line)?;
+            if self.config.insert_final_newline == Some(true) {
+                self.inner.write_all(self.config.end_of_line.unwrap_or(EndOfLine::Lf).as_bytes())?;
+            }
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EditorConfigWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.config.is_empty() {
+            return self.inner.write(buf);
+        }
+        for piece in buf.split_inclusive(|&byte| byte == b'\n') {
+            match piece.strip_suffix(b"\n") {
+                Some(content) => {
+                    self.line.extend_from_slice(content);
+                    self.end_line()?;
+                }
+                None => self.line.extend_from_slice(piece),
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(config: EditorConfig, input: &str) -> String {
+        let mut writer = EditorConfigWriter::new(Vec::new(), config);
+        for chunk in input.as_bytes().chunks(3) {
+            writer.write_all(chunk).unwrap();
+        }
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_writer_applies_settings() {
+        let crlf = EditorConfig {
+            end_of_line: Some(EndOfLine::Crlf),
+            insert_final_newline: Some(true),
+            trim_trailing_whitespace: Some(true),
+        };
+        assert_eq!(rewrite(crlf, "fn f() {} \nfn g() {}"), "fn f() {}\r\nfn g() {}\r\n");
+        let no_final = EditorConfig { insert_final_newline: Some(false), ..EditorConfig::default() };
+        assert_eq!(rewrite(no_final, "a  \r\nb\n"), "a  \r\nb");
+        assert_eq!(rewrite(EditorConfig::default(), "a  \nb"), "a  \nb");
+    }
+
+    #[test]
+    fn test_final_newline_overrides_editorconfig() {
+        let always = EditorConfig::default().with_final_newline(Some(FinalNewline::Always));
+        assert_eq!(rewrite(always, "a\nb"), "a\nb\n");
+        assert_eq!(rewrite(always, "a\nb\n"), "a\nb\n");
+        assert_eq!(rewrite(always, "a\n\n\n"), "a\n\n\n");
+        let never = EditorConfig { insert_final_newline: Some(true), ..EditorConfig::default() }.with_final_newline(Some(FinalNewline::Never));
+        assert_eq!(rewrite(never, "a\nb\n"), "a\nb");
+        assert_eq!(rewrite(never, "a\n\n\n"), "a");
+        assert_eq!(rewrite(never, "a\r\n\r\n\nb\n\n"), "a\r\n\r\n\nb");
+        assert_eq!(rewrite(never, "\n\n"), "");
+        let preserve = never.with_final_newline(Some(FinalNewline::Preserve));
+        assert_eq!(rewrite(preserve, "a\nb"), "a\nb");
+        assert_eq!(rewrite(preserve, "a\nb\n"), "a\nb\n");
+        assert_eq!(rewrite(preserve, "a\n\n\n"), "a\n\n\n");
+    }
+
+    #[test]
+    fn test_for_path_merges_nested_files() {
+        let root = std::env::temp_dir().join(format!("rustscrub-editorconfig-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join(EDITORCONFIG_FILE),
+            "root = true\n\n[*]\nend_of_line = crlf\ninsert_final_newline = true\n\n[*.md]\ntrim_trailing_whitespace = false\n",
+        )
+        .unwrap();
+        fs::write(root.join("src").join(EDITORCONFIG_FILE), "[*.rs]\ntrim_trailing_whitespace = true\nend_of_line = lf\n").unwrap();
+
+        let config = EditorConfig::for_path(&root.join("src/lib.rs")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            config,
+            EditorConfig {
+                end_of_line: Some(EndOfLine::Lf),
+                insert_final_newline: Some(true),
+                trim_trailing_whitespace: Some(true),
+            }
+        );
+    }
+}
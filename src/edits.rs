@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/edits.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use std::ops::Range;
+
+use crate::blank_lines::BlankLineLimit;
+use crate::classify::{classify, CommentCategory};
+use crate::scrub::{ScrubSettings, Scrubber, VerboseCommentType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletionKind {
+    Comment {
+        comment_type: VerboseCommentType,
+        category: CommentCategory,
+    },
+    Whitespace,
+    Code,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deletion {
+    pub range: Range<usize>,
+    pub kind: DeletionKind,
+}
+
+fn byte_offset(line: &str, column: usize) -> usize {
+    line.char_indices().nth(column).map_or(line.len(), |(offset, _)| offset)
+}
+
+fn layout_deletion(text: &str, range: Range<usize>) -> Deletion {
+    let kind = if text[range.clone()].trim().is_empty() { DeletionKind::Whitespace } else { DeletionKind::Code };
+    Deletion { range, kind }
+}
+
+fn drop_blank_lines(kept: &[(usize, char)], limit: BlankLineLimit) -> Vec<Range<usize>> {
+    let mut dropped: Vec<Range<usize>> = Vec::new();
+    let mut drop_line = |line: &[(usize, char)]| {
+        for &(offset, current) in line {
+            let end = offset + current.len_utf8();
+            match dropped.last_mut() {
+                Some(range) if range.end == offset => range.end = end,
+                _ => dropped.push(offset..end),
+            }
+        }
+    };
+    let is_blank = |line: &[(usize, char)]| line.iter().all(|(_, current)| current.is_whitespace());
+    let mut consecutive_blanks = 0;
+    let lines = kept.split_inclusive(|&(_, current)| current == '\n');
+    for line in lines {
+        let terminated = line.last().is_some_and(|&(_, current)| current == '\n');
+        if !terminated {
+            if is_blank(line) {
+                drop_line(line);
+            }
+        } else if is_blank(line) {
+            consecutive_blanks += 1;
+            if consecutive_blanks > limit.0 {
+                drop_line(line);
+            }
+        } else {
+            consecutive_blanks = 0;
+        }
+    }
+    dropped
+}
+
+pub fn scrub_edits(text: &str, settings: &ScrubSettings) -> Result<Vec<Deletion>, String> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let starts: Vec<usize> = lines
+        .iter()
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some(start)
+        })
+        .collect();
+
+    let mut scrubber = Scrubber::new(ScrubSettings {
+        blank_line_limit: None,
+        ..settings.clone()
+    });
+    let mut scrubbed = Vec::with_capacity(lines.len());
+    for line in &lines {
+        scrubbed.extend(scrubber.scrub_line(line));
+    }
+    scrubbed.extend(scrubber.finish_lines());
+
+    let position = |line: usize, column: usize| starts[line - 1] + byte_offset(lines[line - 1], column);
+    let mut deleted = vec![false; text.len()];
+    let mut deletions = Vec::new();
+    for change in scrubbed.iter().flat_map(|line| &line.changes) {
+        let range = position(change.start_line, change.start_column - 1)..position(change.end_line, change.end_column);
+        deleted[range.clone()].fill(true);
+        deletions.push(Deletion {
+            range,
+            kind: DeletionKind::Comment {
+                comment_type: change.comment_type,
+                category: classify(&change.as_comment()),
+            },
+        });
+    }
+
+    let mut kept = Vec::with_capacity(text.len());
+    for (index, (line, output)) in lines.iter().zip(&scrubbed).enumerate() {
+        let start = starts[index];
+        let mut expected = output.text.chars().peekable();
+        let mut run: Option<usize> = None;
+        for (offset, current) in line.char_indices() {
+            let absolute = start + offset;
+            if !deleted[absolute] && expected.peek() != Some(&current) {
+                run.get_or_insert(absolute);
+                continue;
+            }
+            if let Some(run_start) = run.take() {
+                deletions.push(layout_deletion(text, run_start..absolute));
+            }
+            if !deleted[absolute] {
+                expected.next();
+                kept.push((absolute, current));
+            }
+        }
+        if let Some(run_start) = run {
+            deletions.push(layout_deletion(text, run_start..start + line.len()));
+        }
+        if expected.next().is_some() {
+            return Err(format!(
+                "Scrubbed line {} is not a deletion from its input; edit lists need settings that only remove text.",
+                index + 1
+            ));
+        }
+    }
+    if let Some(limit) = settings.blank_line_limit {
+        for range in drop_blank_lines(&kept, limit) {
+            deletions.push(Deletion { range, kind: DeletionKind::Whitespace });
+        }
+    }
+
+    deletions.sort_by_key(|deletion| deletion.range.start);
+    let mut merged: Vec<Deletion> = Vec::with_capacity(deletions.len());
+    for deletion in deletions {
+        match merged.last_mut() {
+            Some(last)
+                if last.range.end == deletion.range.start
+                    && last.kind == deletion.kind
+                    && !matches!(deletion.kind, DeletionKind::Comment { .. }) =>
+            {
+                last.range.end = deletion.range.end;
+            }
+            _ => merged.push(deletion),
+        }
+    }
+    Ok(merged)
+}
+
+pub fn apply_deletions(text: &str, deletions: &[Deletion]) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for deletion in deletions {
+        output.push_str(&text[cursor..deletion.range.start]);
+        cursor = deletion.range.end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::CommentFilter;
+    use crate::scrub::scrub_text;
+
+    #[test]
+    fn test_deletions_reproduce_scrubbed_output() {
+        let input = "/// doc\nfn f() {} // note é\n    // gone\nlet s = \"// kept\"; /* a\n b */ x\n\n\n\n// TODO: last";
+        let settings = ScrubSettings {
+            blank_line_limit: Some(BlankLineLimit(1)),
+            ..ScrubSettings::default()
+        };
+        let deletions = scrub_edits(input, &settings).unwrap();
+        assert_eq!(apply_deletions(input, &deletions), scrub_text(input, &settings).unwrap().0);
+
+        let comments: Vec<(&str, DeletionKind)> = deletions
+            .iter()
+            .filter(|deletion| matches!(deletion.kind, DeletionKind::Comment { .. }))
+            .map(|deletion| (&input[deletion.range.clone()], deletion.kind))
+            .collect();
+        assert_eq!(comments.len(), 5);
+        assert_eq!(comments[0].0, "/// doc");
+        assert_eq!(comments[1].0, "// note é");
+        assert_eq!(comments[3].0, "/* a\n b */");
+        assert_eq!(
+            comments[4].1,
+            DeletionKind::Comment { comment_type: VerboseCommentType::Line, category: CommentCategory::Todo }
+        );
+        assert!(deletions.iter().all(|deletion| deletion.kind != DeletionKind::Code));
+    }
+
+    #[test]
+    fn test_deletions_match_scrub_over_sources() {
+        let variants = [
+            ScrubSettings::default(),
+            ScrubSettings { blank_line_limit: Some(BlankLineLimit(0)), strip_tests: true, ..ScrubSettings::default() },
+            ScrubSettings {
+                filter: CommentFilter { keep_doc: true, keep_safety: true, min_length: Some(20), ..CommentFilter::default() },
+                ..ScrubSettings::default()
+            },
+        ];
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for entry in std::fs::read_dir(src).unwrap() {
+            let source = std::fs::read_to_string(entry.unwrap().path()).unwrap_or_default();
+            for settings in &variants {
+                let deletions = scrub_edits(&source, settings).unwrap();
+                assert_eq!(apply_deletions(&source, &deletions), scrub_text(&source, settings).unwrap().0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rewriting_settings_are_rejected() {
+        let settings = ScrubSettings {
+            filter: CommentFilter { mark_removals: true, ..CommentFilter::default() },
+            ..ScrubSettings::default()
+        };
+        assert!(scrub_edits("fn f() {} // note\n", &settings).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/events.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::inventory::InventoryEntry;
+use rustscrub::schema::SCHEMA_VERSION;
+use rustscrub::scrub::ScrubReport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventFormat {
+    Ndjson,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event<'a> {
+    FileStart {
+        path: &'a str,
+    },
+    CommentRemoved {
+        path: &'a str,
+        #[serde(flatten)]
+        comment: InventoryEntry,
+    },
+    FileDone {
+        path: &'a str,
+        comments_removed: usize,
+        bytes_before: usize,
+        bytes_after: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        elapsed_ms: Option<f64>,
+    },
+    FileError {
+        path: &'a str,
+        message: &'a str,
+    },
+    Warning {
+        message: &'a str,
+    },
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct EventLine<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+}
+
+pub struct EventStream {
+    writer: RefCell<Box<dyn Write>>,
+}
+
+impl EventStream {
+    pub fn new(path: Option<&str>) -> Result<Self, String> {
+        let writer: Box<dyn Write> = match path {
+            Some(path) => {
+                let file = File::create(path).map_err(|e| format!("Failed to create event file '{}': {}", path, e))?;
+                Box::new(BufWriter::new(file))
+            }
+            None => Box::new(io::stderr()),
+        };
+        Ok(EventStream { writer: RefCell::new(writer) })
+    }
+
+    pub fn emit(&self, event: &Event) -> Result<(), String> {
+        let line = EventLine {
+            schema_version: SCHEMA_VERSION,
+            event,
+        };
+        let mut writer = self.writer.borrow_mut();
+        serde_json::to_writer(&mut *writer, &line)
+            .map_err(|e| e.to_string())
+            .and_then(|_| writeln!(writer).and_then(|_| writer.flush()).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to write event: {}", e))
+    }
+
+    pub fn file_report(&self, path: &str, report: &ScrubReport, elapsed: Option<Duration>) -> Result<(), String> {
+        for comment in InventoryEntry::entries(&report.changes) {
+            self.emit(&Event::CommentRemoved { path, comment })?;
+        }
+        self.emit(&Event::FileDone {
+            path,
+            comments_removed: report.changes.len(),
+            bytes_before: report.bytes.before,
+            bytes_after: report.bytes.after,
+            elapsed_ms: elapsed.map(|elapsed| elapsed.as_secs_f64() * 1000.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_report::{generate, SchemaKind};
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+    use serde_json::Value;
+
+    #[test]
+    fn test_events_are_one_json_object_per_line() {
+        let (_, report) = scrub_text("fn f() {} // note\n", &ScrubSettings::default()).unwrap();
+        let buffer = std::rc::Rc::new(RefCell::new(Vec::new()));
+        struct Shared(std::rc::Rc<RefCell<Vec<u8>>>);
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let events = EventStream {
+            writer: RefCell::new(Box::new(Shared(buffer.clone()))),
+        };
+        events.emit(&Event::FileStart { path: "a.rs" }).unwrap();
+        events.file_report("a.rs", &report, None).unwrap();
+        events.emit(&Event::Warning { message: "careful" }).unwrap();
+
+        let output = String::from_utf8(buffer.borrow().clone()).unwrap();
+        let lines: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let kinds: Vec<&str> = lines.iter().map(|line| line["event"].as_str().unwrap()).collect();
+        assert_eq!(kinds, vec!["file-start", "comment-removed", "file-done", "warning"]);
+        assert!(lines.iter().all(|line| line["schema_version"] == SCHEMA_VERSION));
+        assert_eq!(lines[1]["text"], "// note");
+        assert_eq!(lines[1]["start_line"], 1);
+        assert_eq!(lines[2]["comments_removed"], 1);
+        assert!(lines[2].get("elapsed_ms").is_none());
+
+        let schema: Value = serde_json::from_str(&generate(SchemaKind::Events).unwrap()).unwrap();
+        for line in &lines {
+            let variants = schema["oneOf"].as_array().unwrap();
+            let variant = variants.iter().find(|variant| variant["properties"]["event"]["const"] == line["event"]).unwrap();
+            for key in line.as_object().unwrap().keys() {
+                let declared = variant["properties"].get(key).or(schema["properties"].get(key));
+                assert!(declared.is_some(), "undeclared {}", key);
+            }
+        }
+    }
+}
@@ -0,0 +1,614 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/filter.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::banned::BannedWords;
+use crate::classify::{classify, CommentCategory};
+use crate::plugin::{CommentPlugin, Decision, PluginComment};
+use crate::redundant::restates_code;
+use crate::scrub::{ChangeInfo, VerboseCommentType};
+use crate::transform::Transform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocStyle {
+    Outer,
+    Inner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepReason {
+    Doc,
+    Safety,
+    Link,
+    Region,
+    TestAnnotation,
+    InTest,
+    InMacro,
+    OutsideLines,
+    Pragma,
+    Plugin,
+    Directive,
+    License,
+    Length,
+    Category,
+    NotRedundant,
+    Transform,
+}
+
+impl KeepReason {
+    pub fn is_verbatim(self) -> bool {
+        matches!(self, KeepReason::InTest | KeepReason::InMacro | KeepReason::OutsideLines | KeepReason::Pragma)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct LineRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl LineRange {
+    pub fn contains(&self, line: usize) -> bool {
+        line >= self.start && self.end.is_none_or(|end| line <= end)
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid line range '{}'. Expected START:END, e.g. '10:25' or '40:'.", s);
+        let (start, end) = s.split_once(':').ok_or_else(invalid)?;
+        let start = start.trim().parse::<usize>().ok().filter(|&start| start > 0).ok_or_else(invalid)?;
+        let end = match end.trim() {
+            "" => None,
+            end => Some(end.parse::<usize>().ok().filter(|&end| end >= start).ok_or_else(invalid)?),
+        };
+        Ok(LineRange { start, end })
+    }
+}
+
+impl TryFrom<String> for LineRange {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Comment<'a> {
+    pub text: &'a str,
+    pub comment_type: VerboseCommentType,
+    pub full_line: bool,
+    pub continues: Option<KeepReason>,
+}
+
+impl<'a> Comment<'a> {
+    pub fn doc_style(&self) -> Option<DocStyle> {
+        let text = self.text;
+        match self.comment_type {
+            VerboseCommentType::Line => {
+                if text.starts_with("///") && !text.starts_with("////") {
+                    Some(DocStyle::Outer)
+                } else if text.starts_with("//!") {
+                    Some(DocStyle::Inner)
+                } else {
+                    None
+                }
+            }
+            VerboseCommentType::Block => {
+                if text.starts_with("/**") && !text.starts_with("/***") && text != "/**/" {
+                    Some(DocStyle::Outer)
+                } else if text.starts_with("/*!") {
+                    Some(DocStyle::Inner)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn body(&self) -> &'a str {
+        let body = match self.comment_type {
+            VerboseCommentType::Line => self.text.strip_prefix("//").unwrap_or(self.text),
+            VerboseCommentType::Block => {
+                let inner = self.text.strip_prefix("/*").unwrap_or(self.text);
+                inner.strip_suffix("*/").unwrap_or(inner)
+            }
+        };
+        match self.doc_style() {
+            Some(_) => &body[1..],
+            None => body,
+        }
+        .trim()
+    }
+
+    pub fn is_safety(&self) -> bool {
+        self.body()
+            .trim_start_matches(['*', ' '])
+            .get(..7)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("safety:"))
+    }
+}
+
+fn contains_url(text: &str) -> bool {
+    ["http://", "https://", "ftp://", "www."]
+        .iter()
+        .any(|scheme| text.contains(scheme))
+}
+
+fn contains_rfc_reference(text: &str) -> bool {
+    let upper = text.to_ascii_uppercase();
+    upper.match_indices("RFC").any(|(index, _)| {
+        let rest = upper[index + 3..].trim_start_matches(['-', ' ', '_']);
+        rest.starts_with(|c: char| c.is_ascii_digit())
+    })
+}
+
+fn contains_issue_reference(text: &str) -> bool {
+    text.match_indices('#').any(|(index, _)| {
+        let preceded_ok = text[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace() || "([,:/".contains(c) || c.is_alphanumeric());
+        let digits = text[index + 1..].chars().take_while(|c| c.is_ascii_digit()).count();
+        let followed_ok = text[index + 1 + digits..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+        preceded_ok && digits > 0 && followed_ok
+    })
+}
+
+pub fn contains_link(text: &str) -> bool {
+    contains_url(text) || contains_rfc_reference(text) || contains_issue_reference(text)
+}
+
+pub fn is_region_marker(text: &str) -> bool {
+    let text = text.trim();
+    let lower = text.to_ascii_lowercase();
+    let keyword = lower.trim_start_matches('#');
+    let starts_with_word = |word: &str| {
+        keyword.strip_prefix(word).is_some_and(|rest| {
+            rest.is_empty() || rest.starts_with([':', ' ', '\t', '-'])
+        })
+    };
+    starts_with_word("region")
+        || starts_with_word("endregion")
+        || text.starts_with("MARK:")
+        || text.contains("{{{")
+        || text.contains("}}}")
+}
+
+const COMPILETEST_DIRECTIVES: [&str; 22] = [
+    "check-pass",
+    "build-pass",
+    "run-pass",
+    "check-fail",
+    "build-fail",
+    "run-fail",
+    "compile-flags",
+    "edition",
+    "aux-build",
+    "aux-crate",
+    "revisions",
+    "error-pattern",
+    "run-rustfix",
+    "rustfix-only-machine-applicable",
+    "known-bug",
+    "failure-status",
+    "exec-env",
+    "rustc-env",
+    "dont-check-compiler-stderr",
+    "normalize-stderr-test",
+    "normalize-stdout-test",
+    "should-fail",
+];
+
+pub fn is_test_annotation(comment: &Comment) -> bool {
+    if comment.comment_type != VerboseCommentType::Line {
+        return false;
+    }
+    if comment.text.starts_with("//~") || comment.text.starts_with("//@") {
+        return true;
+    }
+    let directive = comment
+        .body()
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+    COMPILETEST_DIRECTIVES.contains(&directive)
+        || ["ignore-", "only-", "needs-"]
+            .iter()
+            .any(|prefix| directive.len() > prefix.len() && directive.starts_with(prefix))
+}
+
+const DIRECTIVE_PREFIXES: [&str; 30] = [
+    "cspell:",
+    "cspell-",
+    "spell-checker:",
+    "spellchecker:",
+    "codespell:",
+    "typos:",
+    "gcov_excl",
+    "lcov_excl",
+    "grcov-excl",
+    "grcov_excl",
+    "tarpaulin::skip",
+    "pragma: no cover",
+    "istanbul ignore",
+    "c8 ignore",
+    "eslint-",
+    "@ts-",
+    "prettier-ignore",
+    "biome-ignore",
+    "deno-lint-ignore",
+    "clang-format ",
+    "nolint",
+    "noqa",
+    "nosec",
+    "nosemgrep",
+    "jscpd:",
+    "#pragma",
+    "+build",
+    "rustfmt:",
+    "lint:ignore ",
+    "lint:file-ignore ",
+];
+
+pub fn is_directive(comment: &Comment) -> bool {
+    let body = comment.body().trim_start_matches(['*', ' ']);
+    let lower = body.to_ascii_lowercase();
+    DIRECTIVE_PREFIXES.iter().any(|prefix| lower.starts_with(prefix))
+        || body.strip_prefix("go:").is_some_and(|name| name.starts_with(|c: char| c.is_ascii_lowercase()))
+        || body.contains("NOLINT")
+        || body.contains("GCOV_EXCL_LINE")
+        || body.contains("LCOV_EXCL_LINE")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pragma {
+    Off,
+    On,
+    IgnoreFile,
+    KeepNext,
+    KeepLine,
+}
+
+pub fn pragma(comment: &Comment) -> Option<Pragma> {
+    if comment.comment_type != VerboseCommentType::Line || comment.doc_style().is_some() {
+        return None;
+    }
+    match comment.body() {
+        "rustscrub:off" => Some(Pragma::Off),
+        "rustscrub:on" => Some(Pragma::On),
+        "rustscrub:ignore-file" => Some(Pragma::IgnoreFile),
+        "rustscrub:keep-next" => Some(Pragma::KeepNext),
+        "rustscrub:keep-line" => Some(Pragma::KeepLine),
+        _ => None,
+    }
+}
+
+pub fn is_pub_item(code: &str) -> bool {
+    let code = code.trim_start();
+    code.starts_with("pub ") || code.starts_with("pub(")
+}
+
+pub fn uses_unsafe(code: &str) -> bool {
+    let code = code.split("//").next().unwrap_or_default();
+    code.split(|c: char| !c.is_alphanumeric() && c != '_').any(|word| word == "unsafe")
+}
+
+pub fn is_license(text: &str) -> bool {
+    let lower = text.to_ascii_lowercase();
+    text.contains("SPDX-")
+        || text.contains('©')
+        || lower.contains("copyright")
+        || lower.contains("all rights reserved")
+        || lower.contains("licensed under")
+        || lower.contains("(c) 1")
+        || lower.contains("(c) 2")
+}
+
+#[derive(Debug, Clone)]
+pub struct CommentFilter {
+    pub keep_doc: bool,
+    pub keep_outer_doc: bool,
+    pub keep_inner_doc: bool,
+    pub keep_safety: bool,
+    pub keep_links: bool,
+    pub keep_regions: bool,
+    pub keep_test_annotations: bool,
+    pub keep_in_tests: bool,
+    pub keep_in_macros: bool,
+    pub keep_above_unsafe: bool,
+    pub keep_above_pub: bool,
+    pub keep_blocks_over: Option<usize>,
+    pub keep_directives: bool,
+    pub keep_license: bool,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub keep_categories: Vec<CommentCategory>,
+    pub remove_redundant: bool,
+    pub redact: Option<BannedWords>,
+    pub transform: Option<Transform>,
+    pub rewrap: Option<usize>,
+    pub mark_removals: bool,
+    pub line_ranges: Vec<LineRange>,
+    pub plugins: Vec<Arc<dyn CommentPlugin>>,
+}
+
+impl Default for CommentFilter {
+    fn default() -> Self {
+        CommentFilter {
+            keep_doc: false,
+            keep_outer_doc: false,
+            keep_inner_doc: false,
+            keep_safety: false,
+            keep_links: false,
+            keep_regions: false,
+            keep_test_annotations: false,
+            keep_in_tests: false,
+            keep_in_macros: false,
+            keep_above_unsafe: false,
+            keep_above_pub: false,
+            keep_blocks_over: None,
+            keep_directives: true,
+            keep_license: false,
+            min_length: None,
+            max_length: None,
+            keep_categories: Vec::new(),
+            remove_redundant: false,
+            redact: None,
+            transform: None,
+            rewrap: None,
+            mark_removals: false,
+            line_ranges: Vec::new(),
+            plugins: Vec::new(),
+        }
+    }
+}
+
+impl CommentFilter {
+    pub fn plugin_decision(&self, comment: &Comment, start_line: usize, end_line: usize, start_column: usize) -> Decision {
+        if self.plugins.is_empty() {
+            return Decision::Default;
+        }
+        let comment = PluginComment::new(comment, start_line, end_line, start_column);
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.decide(&comment))
+            .find(|decision| *decision != Decision::Default)
+            .unwrap_or(Decision::Default)
+    }
+
+    pub fn keep_reason(&self, comment: &Comment) -> Option<KeepReason> {
+        if self.transform.is_some() {
+            return Some(KeepReason::Transform);
+        }
+        let keeps_doc = match comment.doc_style() {
+            Some(DocStyle::Outer) => self.keep_doc || self.keep_outer_doc,
+            Some(DocStyle::Inner) => self.keep_doc || self.keep_inner_doc,
+            None => false,
+        };
+        if keeps_doc {
+            return Some(KeepReason::Doc);
+        }
+        if self.remove_redundant && (comment.comment_type == VerboseCommentType::Block || comment.doc_style().is_some()) {
+            return Some(KeepReason::NotRedundant);
+        }
+        if self.keep_safety && (comment.is_safety() || comment.continues == Some(KeepReason::Safety)) {
+            return Some(KeepReason::Safety);
+        }
+        if self.keep_links && contains_link(comment.body()) {
+            return Some(KeepReason::Link);
+        }
+        if self.keep_regions && is_region_marker(comment.body()) {
+            return Some(KeepReason::Region);
+        }
+        if self.keep_test_annotations && is_test_annotation(comment) {
+            return Some(KeepReason::TestAnnotation);
+        }
+        if self.keep_directives && is_directive(comment) {
+            return Some(KeepReason::Directive);
+        }
+        if self.keep_license && (is_license(comment.body()) || comment.continues == Some(KeepReason::License)) {
+            return Some(KeepReason::License);
+        }
+        if !self.keep_categories.is_empty() && self.keep_categories.contains(&classify(comment)) {
+            return Some(KeepReason::Category);
+        }
+        let deferred_to_block = comment.full_line && comment.comment_type == VerboseCommentType::Line;
+        if !deferred_to_block && !self.length_in_range(comment.body().chars().count()) {
+            return Some(KeepReason::Length);
+        }
+        None
+    }
+
+    pub fn kept_text<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        match &self.redact {
+            Some(banned) => banned.redact(text),
+            None => Cow::Borrowed(text),
+        }
+    }
+
+    fn length_in_range(&self, length: usize) -> bool {
+        self.min_length.is_none_or(|min| length >= min) && self.max_length.is_none_or(|max| length <= max)
+    }
+
+    pub fn needs_blocks(&self) -> bool {
+        self.min_length.is_some()
+            || self.max_length.is_some()
+            || self.remove_redundant
+            || self.rewrap.is_some()
+            || self.keep_above_unsafe
+            || self.keep_above_pub
+            || self.keep_blocks_over.is_some()
+    }
+
+    pub fn in_lines(&self, line: usize) -> bool {
+        self.line_ranges.is_empty() || self.line_ranges.iter().any(|range| range.contains(line))
+    }
+
+    pub fn keeps_span(&self, lines: usize) -> bool {
+        self.keep_blocks_over.is_some_and(|min_lines| lines >= min_lines)
+    }
+
+    pub fn keeps_block(&self, line_comments: &[&str], next_line: &str) -> bool {
+        if self.keeps_span(line_comments.len()) {
+            return true;
+        }
+        if (self.keep_above_unsafe && uses_unsafe(next_line)) || (self.keep_above_pub && is_pub_item(next_line)) {
+            return true;
+        }
+        let bodies: Vec<&str> = line_comments
+            .iter()
+            .map(|text| {
+                let comment = Comment {
+                    text,
+                    comment_type: VerboseCommentType::Line,
+                    full_line: true,
+                    continues: None,
+                };
+                comment.body()
+            })
+            .collect();
+        if self.remove_redundant {
+            return !restates_code(&bodies.join(" "), next_line);
+        }
+        let length = bodies.iter().map(|body| body.chars().count()).sum::<usize>() + bodies.len().saturating_sub(1);
+        !self.length_in_range(length)
+    }
+
+    pub fn keeps_trailing(&self, comments: &[ChangeInfo], code: &str) -> bool {
+        if !self.remove_redundant || comments.is_empty() {
+            return false;
+        }
+        let bodies: Vec<&str> = comments.iter().map(|change| change.as_comment().body()).collect();
+        !restates_code(&bodies.join(" "), code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_link_detects_urls_rfcs_and_issues() {
+        assert!(contains_link("see https://example.com/x"));
+        assert!(contains_link("as described in RFC-9110"));
+        assert!(contains_link("as described in RFC 9110"));
+        assert!(contains_link("workaround for #1234."));
+        assert!(contains_link("fixed upstream in rust-lang/rust#98765"));
+    }
+
+    #[test]
+    fn test_contains_link_ignores_plain_prose() {
+        assert!(!contains_link("increment the counter"));
+        assert!(!contains_link("use #[derive(Debug)] here"));
+        assert!(!contains_link("color #fff or item #3a"));
+    }
+
+    #[test]
+    fn test_region_markers() {
+        assert!(is_region_marker("region: Parsing"));
+        assert!(is_region_marker("#region helpers"));
+        assert!(is_region_marker("endregion"));
+        assert!(is_region_marker("MARK: - Lifecycle"));
+        assert!(is_region_marker("helpers {{{"));
+        assert!(!is_region_marker("regional settings are loaded here"));
+    }
+
+    #[test]
+    fn test_compiletest_annotations() {
+        let annotation = |text| Comment {
+            text,
+            comment_type: VerboseCommentType::Line,
+            full_line: true,
+            continues: None,
+        };
+        assert!(is_test_annotation(&annotation("//~ ERROR mismatched types")));
+        assert!(is_test_annotation(&annotation("//~^ WARN unused variable")));
+        assert!(is_test_annotation(&annotation("// check-pass")));
+        assert!(is_test_annotation(&annotation("// compile-flags: -Zunstable-options")));
+        assert!(is_test_annotation(&annotation("//@ edition:2021")));
+        assert!(is_test_annotation(&annotation("// ignore-windows")));
+        assert!(!is_test_annotation(&annotation("// check the pass manager")));
+    }
+
+    #[test]
+    fn test_tool_directives() {
+        let directive = |text| Comment {
+            text,
+            comment_type: VerboseCommentType::Line,
+            full_line: true,
+            continues: None,
+        };
+        assert!(is_directive(&directive("// cSpell:ignore frobnicate")));
+        assert!(is_directive(&directive("// GCOV_EXCL_START")));
+        assert!(is_directive(&directive("//go:generate stringer")));
+        assert!(is_directive(&directive("// eslint-disable-next-line no-console")));
+        assert!(is_directive(&directive("// NOLINT(readability)")));
+        assert!(is_directive(&directive("//lint:ignore SA1019 still needed")));
+        assert!(is_directive(&directive("// tarpaulin::skip")));
+        assert!(!is_directive(&directive("// go to the next item")));
+        assert!(!is_directive(&directive("// go: this loop runs once per item")));
+        assert!(!is_directive(&directive("// lint: fix the naming later")));
+        assert!(!is_directive(&directive("// tarpaulin reports this branch as missed")));
+    }
+
+    #[test]
+    fn test_line_ranges() {
+        let range: LineRange = "10:20".parse().unwrap();
+        assert!(range.contains(10) && range.contains(20) && !range.contains(21) && !range.contains(9));
+        let open: LineRange = "40:".parse().unwrap();
+        assert!(open.contains(1000) && !open.contains(39));
+        assert!("20:10".parse::<LineRange>().is_err());
+        assert!("0:5".parse::<LineRange>().is_err());
+        assert!("12".parse::<LineRange>().is_err());
+    }
+
+    #[test]
+    fn test_keep_license_keeps_spdx_and_copyright_comments() {
+        assert!(is_license("// SPDX-License-Identifier: MIT"));
+        assert!(is_license("/* Copyright 2024 Example Corp. */"));
+        assert!(is_license("// (c) 2019 Jane Doe"));
+        assert!(is_license("// © Example"));
+        assert!(!is_license("// copy the buffer"));
+
+        let settings = crate::scrub::ScrubSettings {
+            filter: CommentFilter {
+                keep_license: true,
+                ..CommentFilter::default()
+            },
+            ..crate::scrub::ScrubSettings::default()
+        };
+        let input = "// SPDX-License-Identifier: MIT\n// with the notice below\n\n// helper\nfn f() {}\n/* Copyright 2024 Example Corp. */\nfn g() {} // note\n";
+        let (output, report) = crate::scrub::scrub_text(input, &settings).unwrap();
+        assert_eq!(
+            output,
+            "// SPDX-License-Identifier: MIT\n// with the notice below\n\nfn f() {}\n/* Copyright 2024 Example Corp. */\nfn g() {} \n"
+        );
+        assert_eq!(report.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_uses_unsafe() {
+        assert!(uses_unsafe("    let value = unsafe { *ptr };"));
+        assert!(uses_unsafe("unsafe impl Send for Handle {}"));
+        assert!(uses_unsafe("pub unsafe fn from_raw(ptr: *mut u8) -> Self {"));
+        assert!(!uses_unsafe("let unsafe_count = 0;"));
+        assert!(!uses_unsafe("let x = 1; // not unsafe"));
+    }
+}
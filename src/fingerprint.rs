@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/fingerprint.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashMap;
+
+use crate::schema::sha256_hex;
+
+const FINGERPRINT_LEN: usize = 16;
+
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn fingerprint(text: &str, occurrence: usize) -> String {
+    let mut hex = sha256_hex(format!("{}\n{}", normalize(text), occurrence).as_bytes());
+    hex.truncate(FINGERPRINT_LEN);
+    hex
+}
+
+#[derive(Debug, Default)]
+pub struct Fingerprinter {
+    seen: HashMap<String, usize>,
+}
+
+impl Fingerprinter {
+    pub fn next(&mut self, text: &str) -> String {
+        let occurrence = self.seen.entry(normalize(text)).or_insert(0);
+        let result = fingerprint(text, *occurrence);
+        *occurrence += 1;
+        result
+    }
+}
+
+pub fn fingerprints<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut fingerprinter = Fingerprinter::default();
+    texts.into_iter().map(|text| fingerprinter.next(text)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprints_survive_line_churn_and_whitespace() {
+        let before = fingerprints(["// alpha", "// beta", "// alpha"]);
+        let after = fingerprints(["// new", "//   alpha", "// beta", "// alpha"]);
+        assert_eq!(before[0], after[1]);
+        assert_eq!(before[1], after[2]);
+        assert_eq!(before[2], after[3]);
+        assert_ne!(before[0], before[2]);
+        assert_eq!(before[0].len(), FINGERPRINT_LEN);
+    }
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/generated.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+const GENERATED_MARKERS: [&str; 3] = ["@generated", "Code generated by", "DO NOT EDIT"];
+const GENERATED_SCAN_LINES: usize = 5;
+
+fn has_generated_marker<R: BufRead>(reader: R) -> Result<bool, std::io::Error> {
+    for line in reader.lines().take(GENERATED_SCAN_LINES) {
+        let line = line?;
+        if GENERATED_MARKERS.iter().any(|marker| line.contains(marker)) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub fn is_generated(path: &Path) -> Result<bool, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open input file '{}': {}", path.display(), e))?;
+    match has_generated_marker(BufReader::new(file)) {
+        Ok(generated) => Ok(generated),
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => Ok(false),
+        Err(e) => Err(format!("Failed to read input file '{}': {}", path.display(), e)),
+    }
+}
+
+pub fn skip_generated(paths: Vec<PathBuf>, explicit: &[String]) -> Result<(Vec<PathBuf>, Vec<PathBuf>), String> {
+    let mut kept = Vec::with_capacity(paths.len());
+    let mut generated = Vec::new();
+    for path in paths {
+        if explicit.iter().any(|given| Path::new(given) == path) || !is_generated(&path)? {
+            kept.push(path);
+        } else {
+            generated.push(path);
+        }
+    }
+    Ok((kept, generated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_markers_in_leading_lines() {
+        assert!(has_generated_marker("// @generated by prost-build\nfn f() {}\n".as_bytes()).unwrap());
+        assert!(has_generated_marker("// SPDX\n\n// Code generated by bindgen. DO NOT EDIT.\n".as_bytes()).unwrap());
+        assert!(!has_generated_marker("fn f() {}\n".as_bytes()).unwrap());
+        let late = format!("{}// DO NOT EDIT\n", "\n".repeat(GENERATED_SCAN_LINES));
+        assert!(!has_generated_marker(late.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_skip_generated_keeps_explicit_paths() {
+        let dir = std::env::temp_dir().join(format!("rustscrub-generated-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let generated = dir.join("bindings.rs");
+        let explicit = dir.join("explicit.rs");
+        let plain = dir.join("lib.rs");
+        std::fs::write(&generated, "// @generated\n").unwrap();
+        std::fs::write(&explicit, "// DO NOT EDIT\n").unwrap();
+        std::fs::write(&plain, "fn f() {}\n").unwrap();
+        let paths = vec![generated.clone(), explicit.clone(), plain.clone()];
+        let result = skip_generated(paths, &[explicit.to_string_lossy().into_owned()]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.unwrap(), (vec![explicit, plain], vec![generated]));
+    }
+}
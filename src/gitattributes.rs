@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/gitattributes.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use std::fs;
+use std::io;
+use std::path::{self, Path, PathBuf};
+
+use crate::editorconfig::{EditorConfig, EndOfLine};
+use crate::glob::Glob;
+
+const GITATTRIBUTES_FILE: &str = ".gitattributes";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Text {
+    #[default]
+    Unspecified,
+    Set,
+    Unset,
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineEndings {
+    pub text: Text,
+    pub eol: Option<EndOfLine>,
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read '{}': {}", path.display(), e)),
+    }
+}
+
+impl LineEndings {
+    fn apply_file(&mut self, content: &str, relative: &str) {
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            if pattern.starts_with('#') || pattern.starts_with('!') || !Glob::new(pattern).is_match(relative) {
+                continue;
+            }
+            for attribute in fields {
+                match attribute {
+                    "text" => self.text = Text::Set,
+                    "-text" | "binary" => self.text = Text::Unset,
+                    "!text" => self.text = Text::Unspecified,
+                    "text=auto" => self.text = Text::Auto,
+                    "eol=lf" => self.eol = Some(EndOfLine::Lf),
+                    "eol=crlf" => self.eol = Some(EndOfLine::Crlf),
+                    "-eol" | "!eol" => self.eol = None,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    pub fn for_path(path: &Path) -> Result<LineEndings, String> {
+        let absolute = path::absolute(path)
+            .map_err(|e| format!("Failed to resolve path '{}': {}", path.display(), e))?;
+        let Some(root) = absolute.ancestors().skip(1).find(|dir| dir.join(".git").exists()) else {
+            return Ok(LineEndings::default());
+        };
+        let relative_to = |dir: &Path| absolute.strip_prefix(dir).unwrap_or(&absolute).to_string_lossy().replace('\\', "/");
+
+        let mut dirs: Vec<&Path> = absolute.ancestors().skip(1).take_while(|dir| dir.starts_with(root)).collect();
+        dirs.reverse();
+        let mut files: Vec<(PathBuf, String)> = dirs
+            .into_iter()
+            .map(|dir| (dir.join(GITATTRIBUTES_FILE), relative_to(dir)))
+            .collect();
+        files.push((root.join(".git").join("info").join("attributes"), relative_to(root)));
+
+        let mut endings = LineEndings::default();
+        for (file, relative) in files {
+            if let Some(content) = read_optional(&file)? {
+                endings.apply_file(&content, &relative);
+            }
+        }
+        Ok(endings)
+    }
+
+    pub fn apply(self, config: &mut EditorConfig) {
+        match (self.text, self.eol) {
+            (Text::Unset, _) => config.end_of_line = None,
+            (_, Some(eol)) => config.end_of_line = Some(eol),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_later_and_deeper_rules_win() {
+        let mut endings = LineEndings::default();
+        endings.apply_file("* text=auto\n*.rs eol=crlf\n# comment\nsrc/gen/** -text\n", "src/lib.rs");
+        assert_eq!(endings, LineEndings { text: Text::Auto, eol: Some(EndOfLine::Crlf) });
+        endings.apply_file("lib.rs eol=lf\n", "lib.rs");
+        assert_eq!(endings.eol, Some(EndOfLine::Lf));
+
+        let mut generated = LineEndings::default();
+        generated.apply_file("*.rs eol=crlf\nsrc/gen/** binary\n", "src/gen/out.rs");
+        let mut config = EditorConfig { end_of_line: Some(EndOfLine::Lf), ..EditorConfig::default() };
+        generated.apply(&mut config);
+        assert_eq!(config.end_of_line, None);
+    }
+
+    #[test]
+    fn test_for_path_outside_repository() {
+        let dir = std::env::temp_dir().join(format!("rustscrub-gitattributes-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join(GITATTRIBUTES_FILE), "*.rs eol=crlf\n").unwrap();
+        let outside = LineEndings::for_path(&dir.join("src/lib.rs")).unwrap();
+        fs::create_dir_all(dir.join(".git/info")).unwrap();
+        fs::write(dir.join(".git/info/attributes"), "lib.rs -eol\n").unwrap();
+        fs::write(dir.join("src").join(GITATTRIBUTES_FILE), "*.rs text\n").unwrap();
+        let inside = LineEndings::for_path(&dir.join("src/lib.rs")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(outside, LineEndings::default());
+        assert_eq!(inside, LineEndings { text: Text::Set, eol: None });
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/glob.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: Vec<char>,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.trim_start_matches("./");
+        let anchored = if pattern.contains('/') {
+            pattern.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{}", pattern)
+        };
+        Glob {
+            pattern: anchored.chars().collect(),
+        }
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        let path: Vec<char> = path.trim_start_matches("./").replace('\\', "/").chars().collect();
+        match_from(&self.pattern, &path)
+    }
+}
+
+fn match_class(class: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 0;
+    let negated = matches!(class.first(), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    while i < class.len() {
+        if class[i] == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        if i + 2 < class.len() && class[i + 1] == '-' && class[i + 2] != ']' {
+            if class[i] <= c && c <= class[i + 2] {
+                matched = true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                matched = true;
+            }
+            i += 1;
+        }
+        first = false;
+    }
+    None
+}
+
+fn split_alternatives(body: &[char]) -> Vec<Vec<char>> {
+    let mut alternatives = vec![Vec::new()];
+    let mut depth = 0;
+    for &c in body {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(last) = alternatives.last_mut() {
+            last.push(c);
+        }
+    }
+    alternatives
+}
+
+fn closing_brace(pattern: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    for (i, &c) in pattern.iter().enumerate() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn match_from(pattern: &[char], path: &[char]) -> bool {
+    let Some(&p) = pattern.first() else {
+        return path.is_empty();
+    };
+
+    match p {
+        '*' if pattern.get(1) == Some(&'*') => {
+            let mut rest = &pattern[2..];
+            if rest.first() == Some(&'/') {
+                rest = &rest[1..];
+                if match_from(rest, path) {
+                    return true;
+                }
+                return (0..path.len())
+                    .filter(|&i| path[i] == '/')
+                    .any(|i| match_from(rest, &path[i + 1..]));
+            }
+            (0..=path.len()).any(|i| match_from(rest, &path[i..]))
+        }
+        '*' => {
+            let rest = &pattern[1..];
+            for i in 0..=path.len() {
+                if match_from(rest, &path[i..]) {
+                    return true;
+                }
+                if i < path.len() && path[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        '?' => !path.is_empty() && path[0] != '/' && match_from(&pattern[1..], &path[1..]),
+        '[' => {
+            let Some(&c) = path.first() else {
+                return false;
+            };
+            match match_class(&pattern[1..], c) {
+                Some((true, consumed)) => c != '/' && match_from(&pattern[1 + consumed..], &path[1..]),
+                Some((false, _)) => false,
+                None => c == '[' && match_from(&pattern[1..], &path[1..]),
+            }
+        }
+        '{' => match closing_brace(pattern) {
+            Some(end) => split_alternatives(&pattern[1..end]).into_iter().any(|alternative| {
+                let mut expanded = alternative;
+                expanded.extend_from_slice(&pattern[end + 1..]);
+                match_from(&expanded, path)
+            }),
+            None => path.first() == Some(&'{') && match_from(&pattern[1..], &path[1..]),
+        },
+        '\\' if pattern.len() > 1 => path.first() == Some(&pattern[1]) && match_from(&pattern[2..], &path[1..]),
+        _ => path.first() == Some(&p) && match_from(&pattern[1..], &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basename_pattern_matches_anywhere() {
+        let glob = Glob::new("*.rs");
+        assert!(glob.is_match("main.rs"));
+        assert!(glob.is_match("src/bin/main.rs"));
+        assert!(!glob.is_match("src/main.rs.bak"));
+    }
+
+    #[test]
+    fn test_double_star_crosses_directories() {
+        let glob = Glob::new("benches/**");
+        assert!(glob.is_match("benches/a/b.rs"));
+        assert!(!glob.is_match("src/benches.rs"));
+        assert!(Glob::new("src/**/mod.rs").is_match("src/mod.rs"));
+        assert!(Glob::new("src/**/mod.rs").is_match("src/a/b/mod.rs"));
+    }
+
+    #[test]
+    fn test_single_star_stays_in_component() {
+        let glob = Glob::new("src/*.rs");
+        assert!(glob.is_match("src/lib.rs"));
+        assert!(!glob.is_match("src/a/lib.rs"));
+    }
+
+    #[test]
+    fn test_classes_and_alternatives() {
+        assert!(Glob::new("file[0-9].rs").is_match("file7.rs"));
+        assert!(!Glob::new("file[!0-9].rs").is_match("file7.rs"));
+        assert!(Glob::new("*.{rs,toml}").is_match("Cargo.toml"));
+        assert!(!Glob::new("*.{rs,toml}").is_match("README.md"));
+    }
+}
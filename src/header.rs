@@ -104,10 +104,10 @@ pub fn detect_header(file_path: &Path) -> Result<(usize, String), String> {
 }
 
 pub fn ask_yes_no_question(question: &str) -> bool {
-    use std::io::{stdin, stdout};
+    use std::io::{stderr, stdin};
 
-    print!("{} [y/N]: ", question);
-    stdout().flush().unwrap_or(());
+    eprint!("{} [y/N]: ", question);
+    stderr().flush().unwrap_or(());
 
     let mut response = String::new();
 
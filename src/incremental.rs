@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/incremental.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use ropey::Rope;
+
+use crate::scrub::{ChangeInfo, Checkpoint, ScrubSettings, ScrubbedLine, Scrubber};
+
+struct IndexedLine {
+    checkpoint: Option<Checkpoint>,
+    scrubbed: ScrubbedLine,
+}
+
+impl IndexedLine {
+    fn shift(&mut self, delta: isize) {
+        let shift = |line: &mut usize| *line = line.saturating_add_signed(delta);
+        shift(&mut self.scrubbed.line_number);
+        for change in &mut self.scrubbed.changes {
+            shift(&mut change.start_line);
+            shift(&mut change.end_line);
+        }
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            checkpoint.shift(delta);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScrubbedRegion {
+    pub first_line: usize,
+    pub replaced_lines: usize,
+    pub lines: Vec<ScrubbedLine>,
+}
+
+pub struct IncrementalScrubber {
+    settings: ScrubSettings,
+    text: Rope,
+    lines: Vec<IndexedLine>,
+}
+
+fn line_count(text: &Rope) -> usize {
+    let lines = text.len_lines();
+    if text.line(lines - 1).len_chars() == 0 {
+        lines - 1
+    } else {
+        lines
+    }
+}
+
+impl IncrementalScrubber {
+    pub fn new(text: &str, settings: ScrubSettings) -> Self {
+        let mut scrubber = IncrementalScrubber {
+            settings,
+            text: Rope::from_str(text),
+            lines: Vec::new(),
+        };
+        scrubber.lines = scrubber.rescan(0, 0, 0).0;
+        scrubber
+    }
+
+    pub fn text(&self) -> String {
+        self.text.to_string()
+    }
+
+    pub fn scrubbed(&self) -> String {
+        self.lines.iter().map(|line| line.scrubbed.text.as_str()).collect()
+    }
+
+    pub fn comments(&self) -> impl Iterator<Item = &ChangeInfo> {
+        self.lines.iter().flat_map(|line| &line.scrubbed.changes)
+    }
+
+    pub fn comments_on(&self, line_number: usize) -> impl Iterator<Item = &ChangeInfo> {
+        self.comments()
+            .filter(move |change| change.start_line <= line_number && line_number <= change.end_line)
+    }
+
+    fn rescan(&self, start: usize, edited_end: usize, delta: isize) -> (Vec<IndexedLine>, Option<usize>) {
+        let mut scrubber = match self.lines.get(start).and_then(|line| line.checkpoint.as_ref()) {
+            Some(checkpoint) => Scrubber::resume(self.settings.clone(), checkpoint),
+            None => Scrubber::new(self.settings.clone()),
+        };
+        let mut checkpoints = Vec::new();
+        let mut scrubbed = Vec::new();
+        let mut reuse_from = None;
+        for (index, line) in (start..line_count(&self.text)).zip(self.text.lines_at(start)) {
+            let checkpoint = scrubber.checkpoint();
+            if index >= edited_end {
+                let old_index = index.checked_add_signed(-delta);
+                let old_checkpoint = old_index.and_then(|old| self.lines.get(old)).and_then(|old| old.checkpoint.as_ref());
+                if let (Some(new), Some(old)) = (&checkpoint, old_checkpoint) {
+                    if new.matches(old) {
+                        reuse_from = old_index;
+                        break;
+                    }
+                }
+            }
+            checkpoints.push(checkpoint);
+            scrubbed.extend(scrubber.scrub_line(&Cow::from(line)));
+        }
+        if reuse_from.is_none() {
+            scrubbed.extend(scrubber.finish_lines());
+            if scrubbed.len() > checkpoints.len() {
+                let rest: String = scrubbed.drain(checkpoints.len()..).map(|line| line.text).collect();
+                if let Some(last) = scrubbed.last_mut() {
+                    last.text.push_str(&rest);
+                }
+            }
+        }
+        let lines = checkpoints
+            .into_iter()
+            .zip(scrubbed)
+            .map(|(checkpoint, scrubbed)| IndexedLine { checkpoint, scrubbed })
+            .collect();
+        (lines, reuse_from)
+    }
+
+    pub fn apply_edit(&mut self, range: Range<usize>, replacement: &str) -> Result<Option<ScrubbedRegion>, String> {
+        let length = self.text.len_chars();
+        if range.start > range.end || range.end > length {
+            return Err(format!(
+                "Edit range {}..{} is outside the buffer of {} characters.",
+                range.start, range.end, length
+            ));
+        }
+        let first = self.text.char_to_line(range.start);
+        let old_end = self.text.char_to_line(range.end) + 1;
+        let old_count = self.lines.len();
+        self.text.remove(range.clone());
+        self.text.insert(range.start, replacement);
+        let delta = line_count(&self.text) as isize - old_count as isize;
+        let edited_end = old_end.saturating_add_signed(delta).max(first + 1);
+
+        let start = (0..first.min(old_count.saturating_sub(1)) + 1)
+            .rev()
+            .find(|&index| self.lines.get(index).is_some_and(|line| line.checkpoint.is_some()))
+            .unwrap_or(0);
+        let (rescanned, reuse_from) = self.rescan(start, edited_end, delta);
+
+        let mut tail = self.lines.split_off(reuse_from.unwrap_or(old_count));
+        for line in &mut tail {
+            line.shift(delta);
+        }
+        let replaced = self.lines.split_off(start);
+        let same = |old: &IndexedLine, new: &IndexedLine| old.scrubbed.text == new.scrubbed.text;
+        let prefix = replaced.iter().zip(&rescanned).take_while(|(old, new)| same(old, new)).count();
+        let suffix = replaced[prefix..]
+            .iter()
+            .rev()
+            .zip(rescanned[prefix..].iter().rev())
+            .take_while(|(old, new)| same(old, new))
+            .count();
+        let region = (prefix + suffix < replaced.len().max(rescanned.len())).then(|| ScrubbedRegion {
+            first_line: start + prefix + 1,
+            replaced_lines: replaced.len() - prefix - suffix,
+            lines: rescanned[prefix..rescanned.len() - suffix]
+                .iter()
+                .map(|line| line.scrubbed.clone())
+                .collect(),
+        });
+        self.lines.extend(rescanned);
+        self.lines.extend(tail);
+        Ok(region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blank_lines::BlankLineLimit;
+    use crate::filter::CommentFilter;
+    use crate::scrub::scrub_text;
+
+    const SOURCE: &str = "fn a() {} // note\n// gone\nfn b() {}\nlet s = \"// kept\";\nfn c() {}\n";
+
+    fn apply(lines: &mut Vec<String>, region: &ScrubbedRegion) {
+        let start = region.first_line - 1;
+        let texts = region.lines.iter().map(|line| line.text.clone());
+        lines.splice(start..start + region.replaced_lines, texts);
+    }
+
+    #[test]
+    fn test_edit_returns_only_changed_lines() {
+        let mut scrubber = IncrementalScrubber::new(SOURCE, ScrubSettings::default());
+        assert_eq!(scrubber.scrubbed(), "fn a() {} \nfn b() {}\nlet s = \"// kept\";\nfn c() {}\n");
+
+        let offset = SOURCE.find("gone").unwrap();
+        assert!(scrubber.apply_edit(offset..offset + 4, "removed").unwrap().is_none());
+
+        let offset = scrubber.text().find("fn b").unwrap();
+        let region = scrubber.apply_edit(offset + 3..offset + 4, "beta").unwrap().unwrap();
+        assert_eq!(region.first_line, 3);
+        assert_eq!(region.replaced_lines, 1);
+        assert_eq!(region.lines[0].text, "fn beta() {}\n");
+        assert_eq!(scrubber.comments_on(2).count(), 1);
+    }
+
+    #[test]
+    fn test_edits_match_full_scrub() {
+        let snippets = ["/*", "*/", "\"", "//", "\n", "x", "r#\"", "\"#", "'", "{", "}", "/// doc\n", "\n\n", "#[cfg(test)]\n"];
+        let filtered = ScrubSettings {
+            header_lines: 1,
+            blank_line_limit: Some(BlankLineLimit(1)),
+            strip_tests: true,
+            filter: CommentFilter { keep_safety: true, min_length: Some(12), ..CommentFilter::default() },
+            ..ScrubSettings::default()
+        };
+        for settings in [ScrubSettings::default(), filtered] {
+            let mut scrubber = IncrementalScrubber::new(&SOURCE.repeat(4), settings.clone());
+            let mut lines: Vec<String> = scrubber.lines.iter().map(|line| line.scrubbed.text.clone()).collect();
+            let mut seed: usize = 7;
+            for _ in 0..300 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let length = scrubber.text.len_chars();
+                let start = (seed >> 33) % (length + 1);
+                let end = (start + (seed >> 17) % 3).min(length);
+                let snippet = snippets[(seed >> 7) % snippets.len()];
+                if let Some(region) = scrubber.apply_edit(start..end, snippet).unwrap() {
+                    apply(&mut lines, &region);
+                }
+                let (expected, _) = scrub_text(&scrubber.text(), &settings).unwrap();
+                assert_eq!(scrubber.scrubbed(), expected, "after editing to {:?}", scrubber.text());
+                assert_eq!(lines.concat(), expected);
+            }
+            assert!(scrubber.apply_edit(0..usize::MAX, "").is_err());
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/interrupt.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+use rustscrub::cleanup;
+
+use crate::status::Status;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static INSTALL: Once = Once::new();
+
+pub fn install() -> Result<(), String> {
+    let mut result = Ok(());
+    INSTALL.call_once(|| {
+        result = ctrlc::set_handler(|| {
+            if REQUESTED.swap(true, Ordering::SeqCst) {
+                cleanup::abort();
+                process::exit(Status::Interrupted as i32);
+            }
+            eprintln!("RustScrub: Interrupted, finishing the current file. Press Ctrl-C again to abort immediately.");
+        })
+        .map_err(|e| format!("Failed to install interrupt handler: {}", e));
+    });
+    result
+}
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+pub fn set_requested(requested: bool) {
+    REQUESTED.store(requested, Ordering::SeqCst);
+}
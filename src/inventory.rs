@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/inventory.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::filter::DocStyle;
+use crate::fingerprint::fingerprints;
+use crate::schema;
+use crate::scrub::{ChangeInfo, VerboseCommentType};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InventoryEntry {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    #[schemars(extend("enum" = ["line", "block"]))]
+    pub kind: &'static str,
+    #[schemars(extend("enum" = ["outer", "inner", null]))]
+    pub doc: Option<&'static str>,
+    pub text: String,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InventoryFile {
+    pub path: String,
+    pub comments: Vec<InventoryEntry>,
+}
+
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct Inventory {
+    pub files: Vec<InventoryFile>,
+}
+
+impl InventoryEntry {
+    pub fn from_change(change: &ChangeInfo, fingerprint: String) -> Self {
+        InventoryEntry {
+            start_line: change.start_line,
+            start_column: change.start_column,
+            end_line: change.end_line,
+            end_column: change.end_column,
+            kind: match change.comment_type {
+                VerboseCommentType::Line => "line",
+                VerboseCommentType::Block => "block",
+            },
+            doc: change.as_comment().doc_style().map(|style| match style {
+                DocStyle::Outer => "outer",
+                DocStyle::Inner => "inner",
+            }),
+            text: change.text.clone(),
+            fingerprint,
+        }
+    }
+
+    pub fn entries(changes: &[ChangeInfo]) -> Vec<Self> {
+        let fingerprints = fingerprints(changes.iter().map(|change| change.text.as_str()));
+        changes.iter().zip(fingerprints).map(|(change, fingerprint)| InventoryEntry::from_change(change, fingerprint)).collect()
+    }
+}
+
+impl Inventory {
+    pub fn add_file(&mut self, path: &str, changes: &[ChangeInfo]) {
+        self.files.push(InventoryFile {
+            path: path.to_string(),
+            comments: InventoryEntry::entries(changes),
+        });
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        schema::to_json("inventory", self).map_err(|e| format!("Failed to serialize inventory: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrub::{scrub_text, ScrubSettings};
+    use serde_json::Value;
+
+    #[test]
+    fn test_inventory_json_lists_removed_comments() {
+        let (_, report) = scrub_text("//! Crate.\nlet é = 1; /* a\n b */\n", &ScrubSettings::default()).unwrap();
+        let mut inventory = Inventory::default();
+        inventory.add_file("src/lib.rs", &report.changes);
+        let json: Value = serde_json::from_str(&inventory.to_json().unwrap()).unwrap();
+        assert_eq!(json["report"], "inventory");
+        assert_eq!(json["files"][0]["path"], "src/lib.rs");
+        let comments = json["files"][0]["comments"].as_array().unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(
+            (&comments[0]["kind"], &comments[0]["doc"], &comments[0]["text"]),
+            (&Value::from("line"), &Value::from("inner"), &Value::from("//! Crate."))
+        );
+        assert_eq!((&comments[0]["start_column"], &comments[0]["end_column"]), (&Value::from(1), &Value::from(10)));
+        assert_eq!((&comments[1]["kind"], &comments[1]["doc"]), (&Value::from("block"), &Value::Null));
+        assert_eq!((&comments[1]["start_line"], &comments[1]["start_column"]), (&Value::from(2), &Value::from(12)));
+        assert_eq!((&comments[1]["end_line"], &comments[1]["end_column"]), (&Value::from(3), &Value::from(5)));
+        assert_eq!(comments[1]["text"], "/* a\n b */");
+        assert_eq!(comments[1]["fingerprint"].as_str().unwrap().len(), 16);
+    }
+}
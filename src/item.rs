@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/item.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::path::{Component, Path};
+
+use rustscrub::filter::LineRange;
+
+type ItemSpans = Vec<(Vec<String>, (usize, usize))>;
+
+fn module_path(file: &Path) -> Vec<String> {
+    let components: Vec<String> = file
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    let start = components.iter().rposition(|name| name == "src").map_or(components.len().saturating_sub(1), |index| index + 1);
+    let mut segments = components[start..].to_vec();
+    if let Some(last) = segments.pop() {
+        let stem = last.strip_suffix(".rs").unwrap_or(&last);
+        if !matches!(stem, "lib" | "main" | "mod") {
+            segments.push(stem.to_string());
+        }
+    }
+    segments
+}
+
+fn matches_path(requested: &[&str], module: &[String], item: &[String]) -> bool {
+    let Some(split) = requested.len().checked_sub(item.len()) else {
+        return false;
+    };
+    let (prefix, tail) = requested.split_at(split);
+    if tail.iter().zip(item).any(|(requested, item)| requested != item) {
+        return false;
+    }
+    let module_ends_with = |prefix: &[&str]| prefix.len() <= module.len() && module[module.len() - prefix.len()..].iter().zip(prefix).all(|(a, b)| a == b);
+    match prefix.split_first() {
+        Some((&"crate", rest)) => module_ends_with(rest),
+        Some((_, rest)) => module_ends_with(prefix) || (prefix.len() == module.len() + 1 && module_ends_with(rest)),
+        None => true,
+    }
+}
+
+#[cfg(feature = "items")]
+mod spans {
+    use syn::spanned::Spanned;
+    use syn::{ImplItem, Item, TraitItem, Type};
+
+    fn lines(node: &impl Spanned) -> (usize, usize) {
+        let span = node.span();
+        (span.start().line, span.end().line)
+    }
+
+    fn named(path: &[String], name: String) -> Vec<String> {
+        let mut path = path.to_vec();
+        path.push(name);
+        path
+    }
+
+    pub fn collect(items: &[Item], prefix: &[String], found: &mut super::ItemSpans) {
+        for item in items {
+            let name = match item {
+                Item::Const(item) => Some(item.ident.to_string()),
+                Item::Enum(item) => Some(item.ident.to_string()),
+                Item::Fn(item) => Some(item.sig.ident.to_string()),
+                Item::Macro(item) => item.ident.as_ref().map(ToString::to_string),
+                Item::Static(item) => Some(item.ident.to_string()),
+                Item::Struct(item) => Some(item.ident.to_string()),
+                Item::Type(item) => Some(item.ident.to_string()),
+                Item::Union(item) => Some(item.ident.to_string()),
+                Item::Mod(module) => {
+                    let path = named(prefix, module.ident.to_string());
+                    if let Some((_, content)) = &module.content {
+                        collect(content, &path, found);
+                    }
+                    found.push((path, lines(module)));
+                    None
+                }
+                Item::Trait(item) => {
+                    let path = named(prefix, item.ident.to_string());
+                    for trait_item in &item.items {
+                        if let TraitItem::Fn(function) = trait_item {
+                            found.push((named(&path, function.sig.ident.to_string()), lines(function)));
+                        }
+                    }
+                    found.push((path, lines(item)));
+                    None
+                }
+                Item::Impl(item) => {
+                    if let Type::Path(self_ty) = &*item.self_ty {
+                        if let Some(segment) = self_ty.path.segments.last() {
+                            let path = named(prefix, segment.ident.to_string());
+                            for impl_item in &item.items {
+                                match impl_item {
+                                    ImplItem::Fn(function) => found.push((named(&path, function.sig.ident.to_string()), lines(function))),
+                                    ImplItem::Const(constant) => found.push((named(&path, constant.ident.to_string()), lines(constant))),
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                    None
+                }
+                _ => None,
+            };
+            if let Some(name) = name {
+                found.push((named(prefix, name), lines(item)));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "items")]
+fn item_spans(source: &str, file: &Path) -> Result<ItemSpans, String> {
+    let parsed = syn::parse_file(source).map_err(|e| format!("Failed to parse '{}' to locate items: {}", file.display(), e))?;
+    let mut found = Vec::new();
+    spans::collect(&parsed.items, &[], &mut found);
+    Ok(found)
+}
+
+#[cfg(not(feature = "items"))]
+fn item_spans(_source: &str, _file: &Path) -> Result<ItemSpans, String> {
+    check_available().map(|_| Vec::new())
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "items") {
+        Ok(())
+    } else {
+        Err("--item and --except-item require building rustscrub with the 'items' feature.".to_string())
+    }
+}
+
+pub fn item_line_ranges(source: &str, file: &Path, item: &str, except: bool) -> Result<Vec<LineRange>, String> {
+    let requested: Vec<&str> = item.split("::").filter(|segment| !segment.is_empty()).collect();
+    let module = module_path(file);
+    let matched: ItemSpans = item_spans(source, file)?.into_iter().filter(|(path, _)| matches_path(&requested, &module, path)).collect();
+    let Some(depth) = matched.iter().map(|(path, _)| path.len()).max() else {
+        return Err(format!("Item '{}' not found in '{}'.", item, file.display()));
+    };
+    let mut spans: Vec<(usize, usize)> = matched.into_iter().filter(|(path, _)| path.len() == depth).map(|(_, span)| span).collect();
+    spans.sort();
+    if !except {
+        return Ok(spans.into_iter().map(|(start, end)| LineRange { start, end: Some(end) }).collect());
+    }
+    let mut ranges = Vec::new();
+    let mut next = 1;
+    for (start, end) in spans {
+        if start > next {
+            ranges.push(LineRange { start: next, end: Some(start - 1) });
+        }
+        next = next.max(end + 1);
+    }
+    ranges.push(LineRange { start: next, end: None });
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_paths_match_module_and_crate_prefixes() {
+        let module = module_path(Path::new("./src/net/client.rs"));
+        assert_eq!(module, vec!["net".to_string(), "client".to_string()]);
+        assert!(module_path(Path::new("src/net/mod.rs")) == vec!["net".to_string()]);
+        let item = vec!["Client".to_string(), "connect".to_string()];
+        assert!(matches_path(&["Client", "connect"], &module, &item));
+        assert!(matches_path(&["client", "Client", "connect"], &module, &item));
+        assert!(matches_path(&["my_crate", "net", "client", "Client", "connect"], &module, &item));
+        assert!(!matches_path(&["server", "Client", "connect"], &module, &item));
+        assert!(!matches_path(&["connect"], &module, &item));
+        assert!(matches_path(&["crate", "client", "Client", "connect"], &module, &item));
+        assert!(!matches_path(&["crate", "Client", "connect"], &[], &["connect".to_string()]));
+    }
+
+    #[cfg(not(feature = "items"))]
+    #[test]
+    fn test_item_selection_needs_items_feature() {
+        assert!(check_available().unwrap_err().contains("'items' feature"));
+        assert!(item_line_ranges("fn f() {}\n", Path::new("src/lib.rs"), "f", false).is_err());
+    }
+
+    #[cfg(feature = "items")]
+    #[test]
+    fn test_item_line_ranges() {
+        let source = "// head\nfn other() {}\n\n/// Docs\nfn shared() {\n    // inside\n}\n\nstruct S;\nimpl S {\n    fn shared(&self) {}\n}\n";
+        let file = Path::new("src/lib.rs");
+        let inside = item_line_ranges(source, file, "my_crate::shared", false).unwrap();
+        assert_eq!(inside, vec![LineRange { start: 4, end: Some(7) }]);
+        let outside = item_line_ranges(source, file, "shared", true).unwrap();
+        assert_eq!(outside, vec![LineRange { start: 1, end: Some(3) }, LineRange { start: 8, end: None }]);
+        let method = item_line_ranges(source, file, "S::shared", false).unwrap();
+        assert_eq!(method, vec![LineRange { start: 11, end: Some(11) }]);
+        assert!(item_line_ranges(source, file, "missing", false).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/journal.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+
+pub struct Journal {
+    path: String,
+    file: File,
+}
+
+pub fn completed(path: &str) -> Result<HashSet<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text.lines().filter(|line| !line.is_empty()).map(str::to_string).collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(e) => Err(format!("Failed to read journal '{}': {}", path, e)),
+    }
+}
+
+impl Journal {
+    pub fn open(path: &str, resume: bool) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(resume)
+            .write(true)
+            .truncate(!resume)
+            .open(path)
+            .map_err(|e| format!("Failed to open journal '{}': {}", path, e))?;
+        Ok(Journal {
+            path: path.to_string(),
+            file,
+        })
+    }
+
+    pub fn record(&mut self, input: &str) -> Result<(), String> {
+        writeln!(self.file, "{}", input)
+            .and_then(|_| self.file.sync_data())
+            .map_err(|e| format!("Failed to write journal '{}': {}", self.path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_resumes_completed_files() {
+        let path = std::env::temp_dir().join(format!("rustscrub-journal-{}.txt", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        assert!(completed(&path).unwrap().is_empty());
+
+        let mut journal = Journal::open(&path, false).unwrap();
+        journal.record("src/a.rs").unwrap();
+        drop(journal);
+        let mut journal = Journal::open(&path, true).unwrap();
+        journal.record("src/b.rs").unwrap();
+        drop(journal);
+        let done = completed(&path).unwrap();
+        assert!(done.contains("src/a.rs") && done.contains("src/b.rs"));
+
+        Journal::open(&path, false).unwrap();
+        assert!(completed(&path).unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+}
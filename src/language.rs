@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/language.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use schemars::JsonSchema;
+use serde::Serialize;
+#[cfg(feature = "language-detection")]
+pub use whatlang::Lang;
+
+#[cfg(feature = "language-detection")]
+use rustscrub::classify::{classify, comment_blocks, CommentCategory};
+use rustscrub::scrub::ChangeInfo;
+
+#[cfg(feature = "language-detection")]
+const MIN_WORDS: usize = 4;
+#[cfg(feature = "language-detection")]
+const MIN_CONFIDENCE: f64 = 0.5;
+const SNIPPET_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ForeignComment {
+    pub line: usize,
+    pub language: &'static str,
+    pub code: &'static str,
+    pub snippet: String,
+}
+
+#[cfg(not(feature = "language-detection"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {}
+
+#[cfg(not(feature = "language-detection"))]
+impl Lang {
+    pub fn eng_name(self) -> &'static str {
+        match self {}
+    }
+}
+
+#[cfg(feature = "language-detection")]
+pub fn parse_language(code: &str) -> Result<Lang, String> {
+    Lang::from_code(code.to_ascii_lowercase())
+        .ok_or_else(|| format!("Unknown language code '{}'. Use an ISO 639-3 code such as 'eng' or 'deu'.", code))
+}
+
+#[cfg(not(feature = "language-detection"))]
+pub fn parse_language(_code: &str) -> Result<Lang, String> {
+    Err("--detect-language requires building rustscrub with the 'language-detection' feature.".to_string())
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "language-detection") {
+        Ok(())
+    } else {
+        Err("--detect-language requires building rustscrub with the 'language-detection' feature.".to_string())
+    }
+}
+
+pub fn snippet(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > SNIPPET_CHARS {
+        format!("{}...", text.chars().take(SNIPPET_CHARS).collect::<String>())
+    } else {
+        text
+    }
+}
+
+#[cfg(feature = "language-detection")]
+fn prose_words(text: &str) -> String {
+    text.split_whitespace()
+        .filter(|token| !token.ends_with(':'))
+        .map(|token| token.trim_matches(|c: char| !c.is_alphabetic()))
+        .filter(|word| !word.is_empty() && word.chars().all(char::is_alphabetic))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(feature = "language-detection")]
+fn is_prose(change: &ChangeInfo) -> bool {
+    matches!(
+        classify(&change.as_comment()),
+        CommentCategory::Prose | CommentCategory::Doc | CommentCategory::Todo
+    )
+}
+
+#[cfg(not(feature = "language-detection"))]
+pub fn detect_foreign(_changes: &[ChangeInfo], expected: Lang) -> Vec<ForeignComment> {
+    match expected {}
+}
+
+#[cfg(feature = "language-detection")]
+pub fn detect_foreign(changes: &[ChangeInfo], expected: Lang) -> Vec<ForeignComment> {
+    comment_blocks(changes.iter().filter(|change| is_prose(change)))
+        .into_iter()
+        .filter_map(|(line, text)| {
+            let words = prose_words(&text);
+            if words.split(' ').count() < MIN_WORDS {
+                return None;
+            }
+            let info = whatlang::detect(&words)?;
+            let confident = info.is_reliable() && info.confidence() >= MIN_CONFIDENCE;
+            (confident && info.lang() != expected).then(|| ForeignComment {
+                line,
+                language: info.lang().eng_name(),
+                code: info.lang().code(),
+                snippet: snippet(&text),
+            })
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "language-detection"))]
+mod tests {
+    use super::*;
+    use rustscrub::filter::CommentFilter;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    #[test]
+    fn test_detects_non_english_comment_blocks() {
+        let source = "// Diese Funktion berechnet die Summe aller Werte\n// und gibt das Ergebnis zurück.\nfn sum() {}\n// This function returns the sum of all the values in the list.\nfn total() {}\n// ok\n";
+        let settings = ScrubSettings {
+            filter: CommentFilter {
+                keep_directives: false,
+                ..CommentFilter::default()
+            },
+            ..ScrubSettings::default()
+        };
+        let (_, report) = scrub_text(source, &settings).unwrap();
+        let foreign = detect_foreign(&report.changes, parse_language("eng").unwrap());
+        assert_eq!(foreign.len(), 1);
+        assert_eq!(foreign[0].line, 1);
+        assert_eq!(foreign[0].code, "deu");
+        assert!(parse_language("xx").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/lexer.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use crate::scrub::State;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteClass {
+    Slash,
+    Star,
+    DoubleQuote,
+    SingleQuote,
+    Backslash,
+    RawPrefix,
+    Newline,
+    Structural,
+    Other,
+}
+
+const CLASS_COUNT: usize = ByteClass::Other as usize + 1;
+const STATE_COUNT: usize = State::InRawString as usize + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Emit(State),
+    EmitCode,
+    Buffer,
+    OpenComment,
+    OpenRawString,
+    CloseLineComment,
+    CloseBlockComment,
+    CloseRawString,
+}
+
+const fn classify(byte: u8) -> ByteClass {
+    match byte {
+        b'/' => ByteClass::Slash,
+        b'*' => ByteClass::Star,
+        b'"' => ByteClass::DoubleQuote,
+        b'\'' => ByteClass::SingleQuote,
+        b'\\' => ByteClass::Backslash,
+        b'r' => ByteClass::RawPrefix,
+        b'\n' => ByteClass::Newline,
+        b'{' | b'}' | b';' => ByteClass::Structural,
+        _ => ByteClass::Other,
+    }
+}
+
+const fn transition(state: State, class: ByteClass) -> Action {
+    match (state, class) {
+        (State::Normal, ByteClass::Slash) => Action::OpenComment,
+        (State::Normal, ByteClass::DoubleQuote) => Action::Emit(State::StringLiteral),
+        (State::Normal, ByteClass::SingleQuote) => Action::Emit(State::CharLiteral),
+        (State::Normal, ByteClass::RawPrefix) => Action::OpenRawString,
+        (State::Normal, ByteClass::Structural) => Action::EmitCode,
+        (State::Normal, _) => Action::Emit(State::Normal),
+        (State::LineComment, ByteClass::Newline) => Action::CloseLineComment,
+        (State::LineComment, _) => Action::Buffer,
+        (State::BlockComment, ByteClass::Star) => Action::CloseBlockComment,
+        (State::BlockComment, _) => Action::Buffer,
+        (State::StringLiteral, ByteClass::Backslash) => Action::Emit(State::StringEscape),
+        (State::StringLiteral, ByteClass::DoubleQuote) => Action::Emit(State::Normal),
+        (State::StringLiteral, _) => Action::Emit(State::StringLiteral),
+        (State::StringEscape, _) => Action::Emit(State::StringLiteral),
+        (State::CharLiteral, ByteClass::Backslash) => Action::Emit(State::CharEscape),
+        (State::CharLiteral, ByteClass::SingleQuote) => Action::Emit(State::Normal),
+        (State::CharLiteral, _) => Action::Emit(State::CharLiteral),
+        (State::CharEscape, _) => Action::Emit(State::CharLiteral),
+        (State::InRawString, ByteClass::DoubleQuote) => Action::CloseRawString,
+        (State::InRawString, _) => Action::Emit(State::InRawString),
+    }
+}
+
+const STATES: [State; STATE_COUNT] = [
+    State::Normal,
+    State::LineComment,
+    State::BlockComment,
+    State::StringLiteral,
+    State::StringEscape,
+    State::CharLiteral,
+    State::CharEscape,
+    State::InRawString,
+];
+
+const CLASSES: [ByteClass; CLASS_COUNT] = [
+    ByteClass::Slash,
+    ByteClass::Star,
+    ByteClass::DoubleQuote,
+    ByteClass::SingleQuote,
+    ByteClass::Backslash,
+    ByteClass::RawPrefix,
+    ByteClass::Newline,
+    ByteClass::Structural,
+    ByteClass::Other,
+];
+
+static BYTE_CLASSES: [ByteClass; 256] = {
+    let mut classes = [ByteClass::Other; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        classes[byte] = classify(byte as u8);
+        byte += 1;
+    }
+    classes
+};
+
+static TRANSITIONS: [[Action; CLASS_COUNT]; STATE_COUNT] = {
+    let mut table = [[Action::Buffer; CLASS_COUNT]; STATE_COUNT];
+    let mut state = 0;
+    while state < STATE_COUNT {
+        let mut class = 0;
+        while class < CLASS_COUNT {
+            table[state][class] = transition(STATES[state], CLASSES[class]);
+            class += 1;
+        }
+        state += 1;
+    }
+    table
+};
+
+pub fn byte_class(byte: u8) -> ByteClass {
+    BYTE_CLASSES[byte as usize]
+}
+
+pub fn action(state: State, byte: u8) -> Action {
+    TRANSITIONS[state as usize][byte_class(byte) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_matches_transition_rules() {
+        for state in STATES {
+            for byte in 0..=255u8 {
+                assert_eq!(action(state, byte), transition(state, classify(byte)));
+            }
+        }
+        assert_eq!(byte_class(0xE2), ByteClass::Other);
+        assert_eq!(action(State::StringEscape, b'"'), Action::Emit(State::StringLiteral));
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/lib.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+pub mod adapters;
+pub mod banned;
+pub mod blank_lines;
+pub mod classify;
+pub mod cleanup;
+pub mod edits;
+pub mod filter;
+pub mod fingerprint;
+pub mod inventory;
+pub mod lexer;
+pub mod options;
+pub mod plugin;
+pub mod redundant;
+pub mod rewrap;
+pub mod schema;
+pub mod spill;
+pub mod scrub;
+pub mod summary;
+pub mod tokens;
+pub mod transform;
+#[cfg(feature = "tokio")]
+pub mod async_io;
+#[cfg(feature = "incremental")]
+pub mod incremental;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "rules")]
+pub mod rules;
+#[cfg(feature = "wasm")]
+pub mod wasm;
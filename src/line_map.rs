@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/line_map.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::schema;
+use rustscrub::scrub::ScrubReport;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LineMapFile {
+    pub path: String,
+    pub lines: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LineMap {
+    pub files: Vec<LineMapFile>,
+}
+
+impl LineMap {
+    pub fn new(reports: &[(String, ScrubReport)]) -> Self {
+        LineMap {
+            files: reports
+                .iter()
+                .map(|(path, report)| LineMapFile {
+                    path: path.clone(),
+                    lines: report.line_map.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn write(path: &str, reports: &[(String, ScrubReport)]) -> Result<(), String> {
+    let json = schema::to_json("line-map", &LineMap::new(reports)).map_err(|e| format!("Failed to serialize line map: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write line map '{}': {}", path, e))
+}
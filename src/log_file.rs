@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/log_file.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use rustscrub::scrub::{ChangeInfo, ScrubReport, VerboseCommentType};
+
+pub struct LogFile {
+    path: String,
+    start: Instant,
+    writer: RefCell<BufWriter<File>>,
+}
+
+fn describe_change(change: &ChangeInfo) -> String {
+    let kind = match change.comment_type {
+        VerboseCommentType::Line => "line",
+        VerboseCommentType::Block => "block",
+    };
+    let lines = if change.start_line == change.end_line {
+        format!("line {}", change.start_line)
+    } else {
+        format!("lines {}-{}", change.start_line, change.end_line)
+    };
+    format!("removed {} comment at {}: {:?}", kind, lines, change.text)
+}
+
+impl LogFile {
+    pub fn create(path: &str) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create log file '{}': {}", path, e))?;
+        Ok(LogFile {
+            path: path.to_string(),
+            start: Instant::now(),
+            writer: RefCell::new(BufWriter::new(file)),
+        })
+    }
+
+    pub fn record(&self, message: &str) -> Result<(), String> {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        writeln!(self.writer.borrow_mut(), "[{:>9.3}s] {}", elapsed, message)
+            .map_err(|e| format!("Failed to write log file '{}': {}", self.path, e))
+    }
+
+    pub fn file_timing(&self, input: &str, elapsed: Duration) -> Result<(), String> {
+        self.record(&format!("{}: processed in {:.3} ms", input, elapsed.as_secs_f64() * 1000.0))
+    }
+
+    pub fn file_report(&self, input: &str, report: &ScrubReport) -> Result<(), String> {
+        self.record(&format!(
+            "{}: {} comments removed, {} -> {} lines, {} -> {} bytes",
+            input,
+            report.changes.len(),
+            report.lines.before,
+            report.lines.after,
+            report.bytes.before,
+            report.bytes.after
+        ))?;
+        for change in &report.changes {
+            self.record(&format!("{}: {}", input, describe_change(change)))?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        self.record("Run finished")?;
+        self.writer
+            .into_inner()
+            .flush()
+            .map_err(|e| format!("Failed to write log file '{}': {}", self.path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+    use std::fs;
+
+    #[test]
+    fn test_log_records_changes_and_timings() {
+        let path = std::env::temp_dir().join(format!("rustscrub-log-{}.log", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let (_, report) = scrub_text("fn f() {} // note\n/* a\n b */\n", &ScrubSettings::default()).unwrap();
+        let log = LogFile::create(&path).unwrap();
+        log.file_timing("a.rs", Duration::from_millis(3)).unwrap();
+        log.file_report("a.rs", &report).unwrap();
+        log.finish().unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(text.contains("a.rs: processed in 3.000 ms"));
+        assert!(text.contains("a.rs: 2 comments removed"));
+        assert!(text.contains("a.rs: removed line comment at line 1: \"// note\""));
+        assert!(text.contains("a.rs: removed block comment at lines 2-3: "));
+        assert!(text.lines().last().unwrap().ends_with("Run finished"));
+    }
+}
@@ -6,19 +6,127 @@
 // Copyright (c) 2025 Volker Schwaberow
 
 use clap::Parser;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, BufWriter, Write};
-use std::path::Path;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc;
+use std::thread::{self, Scope};
+use std::time::Instant;
+mod amalgamate;
+mod archive;
+mod banned_report;
+mod baseline;
+mod check;
+mod classify_report;
+mod clone;
+mod comment_age;
+mod comment_owners;
+mod compress;
+mod diff_comments;
+mod doc_coverage;
+mod config;
+mod doctests;
+mod events;
+mod duplicates;
+mod editorconfig;
+mod generated;
+mod gitattributes;
+mod glob;
 mod header;
-mod scrub;
+mod interrupt;
+mod item;
+mod journal;
+mod language;
+mod line_map;
+mod log_file;
+mod modgraph;
+mod ordered;
+mod pack;
+mod plugins;
+mod progress;
+mod policy;
+mod remote;
+mod schema_report;
+mod self_update;
+mod spell;
+mod stats;
+mod status;
+mod stale;
+mod suffix;
+mod suppressions;
+mod timings;
+mod transaction;
+mod trash;
+mod verbose;
+mod walk;
+use crate::amalgamate::amalgamate;
+use crate::banned_report::BannedArgs;
+use crate::archive::{archive_kind, scrub_archive};
+use crate::check::{CheckArgs, CheckThresholds};
+use crate::classify_report::ClassifyArgs;
+use crate::compress::{Compress, CompressedWriter};
+use crate::config::Config;
+use crate::diff_comments::DiffCommentsArgs;
+use crate::events::{Event, EventFormat, EventStream};
+use crate::editorconfig::{EditorConfig, EditorConfigWriter, FinalNewline};
+use crate::generated::is_generated;
 use crate::header::{detect_header, ask_yes_no_question};
-use crate::scrub::{ChangeInfo, StreamState, VerboseCommentType, process_line_streaming};
+use crate::item::item_line_ranges;
+use crate::journal::Journal;
+use crate::log_file::LogFile;
+use crate::ordered::{worker_count, Prefetch};
+use crate::pack::PackArgs;
+use crate::plugins::{load_plugins, plugin_error};
+use crate::progress::{Progress, ProgressFormat};
+use crate::remote::{is_url, open_input};
+use crate::schema_report::SchemaArgs;
+use crate::self_update::SelfUpdateArgs;
+use crate::spell::SpellArgs;
+use crate::stats::StatsArgs;
+use crate::status::{exit_code, Failure, Status, Warnings};
+use crate::timings::{format_timings, CountingAllocator, FileTiming};
+use crate::transaction::Transaction;
+use crate::trash::{Trash, UndoArgs, TRASH_DIR};
+use crate::verbose::{print_verbose, VerboseFormat};
+use crate::walk::{walk_source_files, WalkArgs};
+use rustscrub::cleanup;
+use rustscrub::inventory::Inventory;
+use rustscrub::options::ScrubArgs;
+use rustscrub::schema::{self, Tool, REPORT_SCHEMA};
+use rustscrub::scrub::{scrub_stream, ChangeDetail, ScrubReport, ScrubSettings, VerboseCommentType};
+use rustscrub::spill::{parse_size, MemoryBudget, SpillBuffer};
+use rustscrub::summary::{print_file_summary, print_summary, FileRow, FileSummary, SummaryFormat, Tally};
+use rustscrub::tokens::TokenModel;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 #[derive(Parser, Debug)]
 #[clap(name = "rustscrub", author = "Volker Schwaberow <volker@schwaberow.de>", version, about = "RustScrub: Removes comments from Rust files.", long_about = None)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    #[clap(value_parser)]
-    input: String,
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    #[clap(long, value_name = "DATE", global = true, value_parser = schema::parse_date)]
+    date: Option<u64>,
+
+    #[clap(value_parser, required_unless_present_any = ["clone", "schema"])]
+    input: Vec<String>,
+
+    #[clap(long, value_name = "URL", conflicts_with_all = ["input", "output", "amalgamate", "suffix"])]
+    clone: Option<String>,
+
+    #[clap(long = "ref", value_name = "REV", requires = "clone")]
+    git_ref: Option<String>,
+
+    #[clap(long, value_name = "DIR", requires = "clone")]
+    output_dir: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, exclusive = true)]
+    schema: bool,
 
     #[clap(short = 'H', long, default_value_t = 0)]
     header_lines: usize,
@@ -26,153 +134,915 @@ struct Args {
     #[clap(short, long)]
     output: Option<String>,
 
+    #[clap(long, value_name = "TEMPLATE", conflicts_with_all = ["output", "amalgamate", "check"])]
+    suffix: Option<String>,
+
+    #[clap(long, value_enum)]
+    compress: Option<Compress>,
+
+    #[clap(long, value_name = "FILE")]
+    extract_doctests: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    redact: bool,
+
+    #[clap(long, value_name = "FILE")]
+    config: Option<String>,
+
+    #[clap(long, value_name = "SECONDS", default_value_t = remote::DEFAULT_TIMEOUT_SECS)]
+    timeout: u64,
+
     #[clap(short, long, action = clap::ArgAction::SetTrue)]
     verbose: bool,
 
+    #[clap(long, value_enum, default_value_t = VerboseFormat::Text, requires = "verbose")]
+    verbose_format: VerboseFormat,
+
     #[clap(short, long, action = clap::ArgAction::SetTrue)]
     dry_run: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    strict: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    deny_warnings: bool,
+
+    #[clap(long, value_name = "PATH")]
+    log_file: Option<String>,
+
+    #[clap(long, value_enum, value_name = "FORMAT")]
+    events: Option<EventFormat>,
+
+    #[clap(long, value_name = "PATH", requires = "events")]
+    events_file: Option<String>,
+
+    #[clap(long, value_name = "FILE", requires = "suffix", conflicts_with = "dry_run")]
+    journal: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, requires = "suffix", conflicts_with_all = ["dry_run", "journal"])]
+    transactional: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, requires = "transactional")]
+    verify: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    trash: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    fail_fast: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, requires = "journal")]
+    resume: bool,
+
+    #[clap(long, value_enum, value_name = "FORMAT")]
+    progress: Option<ProgressFormat>,
+
+    #[clap(long, value_name = "FD", requires = "progress")]
+    progress_fd: Option<u32>,
+
+    #[clap(long, value_name = "MODEL", num_args = 0..=1, require_equals = true, default_missing_value = "cl100k")]
+    count_tokens: Option<TokenModel>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    summary: bool,
+
+    #[clap(long, value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "10")]
+    timings: Option<usize>,
+
+    #[clap(long, value_name = "BYTES", value_parser = parse_size, conflicts_with_all = ["inventory", "extract_doctests"])]
+    max_memory: Option<usize>,
+
+    #[clap(short, long, value_name = "N", default_value_t = 0)]
+    jobs: usize,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    no_editorconfig: bool,
+
+    #[clap(long, value_enum, value_name = "POLICY")]
+    final_newline: Option<FinalNewline>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    include_generated: bool,
+
+    #[clap(long, value_name = "PATH", conflicts_with_all = ["except_item", "lines", "strip_blank_lines"])]
+    item: Option<String>,
+
+    #[clap(long, value_name = "PATH", conflicts_with_all = ["lines", "strip_blank_lines"])]
+    except_item: Option<String>,
+
+    #[clap(long, value_name = "FILE")]
+    plugin: Vec<String>,
+
+    #[clap(long, value_name = "CMD")]
+    filter_cmd: Option<String>,
+
+    #[clap(long, value_enum, default_value_t = SummaryFormat::None)]
+    summary_format: SummaryFormat,
+
+    #[clap(long, value_name = "TEMPLATE", default_value = "// ===== {path} =====")]
+    delimiter: String,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with = "delimiter")]
+    no_delimiters: bool,
+
+    #[clap(long, value_name = "FILE", conflicts_with = "output")]
+    amalgamate: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["output", "amalgamate", "dry_run"])]
+    check: bool,
+
+    #[clap(long, value_name = "N", requires = "check")]
+    max_comments: Option<usize>,
+
+    #[clap(long, value_name = "PERCENT", requires = "check")]
+    max_comment_ratio: Option<f64>,
+
+    #[clap(long, value_name = "FILE")]
+    line_map: Option<String>,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+
+    #[clap(flatten)]
+    scrub: ScrubArgs,
+}
+
+fn file_settings(args: &Args, input: &str, header_lines: usize, batch_settings: &ScrubSettings) -> Result<ScrubSettings, String> {
+    let mut settings = ScrubSettings {
+        header_lines,
+        ..batch_settings.clone()
+    };
+    let item = match (&args.item, &args.except_item) {
+        (Some(item), _) => Some((item, false)),
+        (None, Some(item)) => Some((item, true)),
+        (None, None) => None,
+    };
+    if let Some((item, except)) = item {
+        let source = fs::read_to_string(input).map_err(|e| format!("Failed to read input file '{}': {}", input, e))?;
+        settings.filter.line_ranges = item_line_ranges(&source, Path::new(input), item, except)?;
+        settings.blank_line_limit = None;
+        settings.strip_tests = false;
+    }
+    Ok(settings)
 }
 
-fn main() -> Result<(), String> {
-    let mut args = Args::parse();
+fn output_editorconfig(args: &Args, path: &Path) -> Result<EditorConfig, String> {
+    EditorConfig::for_output(path, !args.no_editorconfig, args.final_newline)
+}
 
-    let input_path = Path::new(&args.input);
-    if !input_path.exists() {
-        return Err(format!("Input file '{}' does not exist.", args.input));
+struct LineTracker<'a> {
+    inner: &'a mut dyn Write,
+    at_line_start: bool,
+}
+
+impl Write for LineTracker<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(last) = buf[..written].last() {
+            self.at_line_start = *last == b'\n';
+        }
+        Ok(written)
     }
-    if !input_path.is_file() {
-        return Err(format!("Input path '{}' is not a file.", args.input));
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
-    
-    if args.header_lines == 0 {
-        match detect_header(input_path) {
-            Ok((detected_header_lines, preview)) => {
-                if detected_header_lines > 0 {
-                    println!("Automatically detected a header with {} lines:", detected_header_lines);
-                    println!("\n{}\n", preview);
-                    
-                    if ask_yes_no_question("Should this section be treated as a header (preserve comments)?") {
-                        args.header_lines = detected_header_lines;
-                        println!("Header will be set to {} lines.", args.header_lines);
-                    } else {
-                        println!("Header detection ignored. Processing the entire file.");
-                    }
+}
+
+fn prompt_header_lines(input: &str, warnings: Warnings) -> Result<usize, Failure> {
+    match detect_header(Path::new(input)) {
+        Ok((detected_header_lines, preview)) => {
+            if detected_header_lines > 0 {
+                eprintln!("Automatically detected a header with {} lines:", detected_header_lines);
+                eprintln!("\n{}\n", preview);
+
+                if ask_yes_no_question("Should this section be treated as a header (preserve comments)?") {
+                    eprintln!("Header will be set to {} lines.", detected_header_lines);
+                    return Ok(detected_header_lines);
                 }
-            },
-            Err(e) => {
-                eprintln!("Warning: Header detection failed: {}", e);
+                eprintln!("Header detection ignored. Processing the entire file.");
+            }
+        },
+        Err(e) => warnings.warn(Status::Io, format!("Header detection failed for '{}': {}", input, e))?,
+    }
+    Ok(0)
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    #[clap(about = "Scrub files and concatenate them into a single context file for LLM ingestion.")]
+    Pack(PackArgs),
+    #[clap(about = "Classify comments (doc, license, TODO, commented-out code, directive, prose) and report counts per file.")]
+    Classify(ClassifyArgs),
+    #[clap(about = "Check comment budgets, or evaluate the comment policy from rustscrub.toml with --policy.")]
+    Check(CheckArgs),
+    #[clap(about = "Spell-check comment text only, with camelCase splitting and the file's identifiers as a code-aware dictionary.")]
+    Spell(SpellArgs),
+    #[clap(about = "Scan comments for profanity and banned phrases (codenames, competitor names) from a word list or rustscrub.toml.")]
+    Banned(BannedArgs),
+    #[clap(about = "Show comment counts, density and TODOs, or compare them between two git revisions with --compare REV1..REV2.")]
+    Stats(StatsArgs),
+    #[clap(about = "Compare the comments of two files and list added, removed and modified comments.")]
+    DiffComments(DiffCommentsArgs),
+    #[clap(about = "Print the JSON Schema of the JSON reports, the --events stream or the --inventory file, generated from the serialized types.")]
+    Schema(SchemaArgs),
+    #[clap(about = "Check the latest GitHub release and replace the running binary with it after verifying its minisign signature.")]
+    SelfUpdate(SelfUpdateArgs),
+    #[clap(about = "Restore the outputs a --trash run overwrote, from the latest run or the given run ID.")]
+    Undo(UndoArgs),
+}
+
+type FileReports = Vec<(String, ScrubReport)>;
+
+#[derive(Default)]
+struct Reports {
+    keep: bool,
+    files: FileReports,
+    summaries: Vec<FileSummary>,
+    rows: Vec<FileRow>,
+    tokens: Vec<(String, Tally)>,
+    unterminated: Vec<String>,
+    line_comments: usize,
+    block_comments: usize,
+    elided: usize,
+    changed: bool,
+}
+
+impl Reports {
+    fn new(args: &Args) -> Self {
+        Reports {
+            keep: args.check
+                || args.verbose
+                || args.scrub.inventory.is_some()
+                || args.line_map.is_some()
+                || args.extract_doctests.is_some(),
+            ..Reports::default()
+        }
+    }
+
+    fn add(&mut self, args: &Args, warnings: Warnings, memory: &MemoryBudget, input: &str, mut report: ScrubReport) -> Result<(), String> {
+        if let Some(log) = warnings.log {
+            log.file_report(input, &report)?;
+        }
+        if let Some(kind) = report.unterminated {
+            self.unterminated.push(unterminated_message(input, kind));
+        }
+        if args.summary {
+            self.summaries.push(FileSummary {
+                path: input.to_string(),
+                bytes: report.bytes,
+                lines: report.lines,
+                tokens: report.tokens,
+            });
+        }
+        if args.summary_format != SummaryFormat::None {
+            self.rows.push(FileRow::new(input, &report, !args.dry_run && !args.check));
+        }
+        if args.count_tokens.is_some() {
+            self.tokens.push((input.to_string(), report.tokens));
+        }
+        for change in &report.changes {
+            match change.comment_type {
+                VerboseCommentType::Line => self.line_comments += 1,
+                VerboseCommentType::Block => self.block_comments += 1,
+            }
+        }
+        self.changed |= !report.changes.is_empty() || report.bytes.before != report.bytes.after;
+        if self.keep {
+            let change_bytes = report.changes.iter().map(|change| change.text.len()).sum();
+            if !memory.reserve(change_bytes) {
+                for change in &mut report.changes {
+                    change.text = String::new();
+                }
+                self.elided += 1;
             }
+            self.files.push((input.to_string(), report));
         }
+        Ok(())
     }
+}
+
+#[derive(Default)]
+struct Batch {
+    reports: Reports,
+    files: usize,
+    generated: Vec<String>,
+    failed: Vec<(String, String)>,
+    timings: Vec<FileTiming>,
+    interrupted: bool,
+}
+
+type Prefetched<'a> = Option<Result<(SpillBuffer<'a>, ScrubReport), String>>;
+
+fn unterminated_message(input: &str, kind: &str) -> String {
+    format!("Input '{}' ends inside an unterminated {}", input, kind)
+}
 
-    let input_file = File::open(&args.input)
-        .map_err(|e| format!("Failed to open input file '{}': {}", args.input, e))?;
-    let mut buf_reader = BufReader::new(input_file);
+fn print_output_written(args: &Args, output: &str) {
+    if args.verbose {
+        eprintln!("RustScrub: Output written to {}", output);
+    } else {
+        println!("RustScrub: Output written to {}", output);
+    }
+}
 
-    let mut writer_holder: Option<Box<dyn Write>> = if !args.dry_run {
-        if let Some(output_path_str) = &args.output {
+fn walk_inputs(args: &Args, done: &HashSet<String>, visit: &mut dyn FnMut(String) -> bool) -> Result<Vec<String>, String> {
+    let mut generated = Vec::new();
+    let mut failure = None;
+    walk_source_files(&args.input, &args.walk, &mut |path| {
+        let input = path.to_string_lossy().into_owned();
+        if done.contains(&input) {
+            return true;
+        }
+        let may_be_generated = !args.include_generated && !is_url(&input) && !args.input.contains(&input);
+        match may_be_generated.then(|| is_generated(Path::new(&input))).transpose() {
+            Ok(Some(true)) => {
+                generated.push(input);
+                true
+            }
+            Ok(_) => visit(input),
+            Err(e) => {
+                failure = Some(e);
+                false
+            }
+        }
+    })?;
+    failure.map_or(Ok(generated), Err)
+}
+
+fn scrub_files<'scope, 'env>(
+    scope: &'scope Scope<'scope, 'env>,
+    args: &'env Args,
+    done: &'env HashSet<String>,
+    batch_settings: &ScrubSettings,
+    warnings: Warnings,
+    trash: Option<&Trash>,
+    memory: &'env MemoryBudget,
+) -> Result<Batch, Failure> {
+    let fail_fast = args.fail_fast || args.transactional;
+    let buffers_output = !args.dry_run && !args.check;
+    let jobs = worker_count(args.jobs);
+    let mut progress = match args.progress {
+        Some(_) => {
+            let mut total = 0;
+            walk_inputs(args, done, &mut |_| {
+                total += 1;
+                true
+            })?;
+            Some(Progress::new(args.progress_fd, total)?)
+        }
+        None => None,
+    };
+
+    let (sender, receiver) = mpsc::sync_channel(jobs * 2);
+    let walker = scope.spawn(move || walk_inputs(args, done, &mut |input| sender.send(input).is_ok()));
+    let mut receiver = receiver.into_iter();
+    let first: Vec<String> = receiver.by_ref().take(2).collect();
+    let header_lines = match (first.as_slice(), args.header_lines) {
+        ([input], 0) if !is_url(input) => prompt_header_lines(input, warnings)?,
+        _ => args.header_lines,
+    };
+    let use_delimiters = first.len() > 1 && !args.no_delimiters;
+    let source = first.into_iter().chain(receiver);
+
+    let mut writer_holder: Option<EditorConfigWriter<CompressedWriter<Box<dyn Write>>>> = if !args.dry_run && !args.check && args.amalgamate.is_none() && args.suffix.is_none() {
+        let config = match &args.output {
+            Some(output_path_str) => output_editorconfig(args, Path::new(output_path_str))?,
+            None => EditorConfig::default().with_final_newline(args.final_newline),
+        };
+        let (sink, compress): (Box<dyn Write>, _) = if let Some(output_path_str) = &args.output {
+            if let Some(trash) = trash {
+                trash.preserve(Path::new(output_path_str))?;
+            }
             let output_file = File::create(output_path_str)
                 .map_err(|e| format!("Failed to create output file '{}': {}", output_path_str, e))?;
-            Some(Box::new(BufWriter::new(output_file)))
+            cleanup::remove_on_abort(Path::new(output_path_str));
+            (Box::new(BufWriter::new(output_file)), args.compress.or_else(|| Compress::from_path(output_path_str)))
         } else {
             let stdout = io::stdout();
-            Some(Box::new(BufWriter::new(stdout.lock())))
-        }
+            (Box::new(BufWriter::new(stdout.lock())), args.compress)
+        };
+        Some(EditorConfigWriter::new(CompressedWriter::new(sink, compress)?, config))
     } else {
         None
     };
 
-    let mut actual_header_lines_counted = 0;
-    let mut line_buffer = String::new(); 
-
-    if args.header_lines > 0 {
-        for _ in 0..args.header_lines {
-            line_buffer.clear();
-            match buf_reader.read_line(&mut line_buffer) {
-                Ok(0) => break, 
-                Ok(_) => {
-                    if let Some(writer) = writer_holder.as_mut() {
-                        writer.write_all(line_buffer.as_bytes())
-                            .map_err(|e| format!("Failed to write header line: {}", e))?;
+    let files: Box<dyn Iterator<Item = (String, Prefetched<'env>)> + '_> = if jobs > 1 && !fail_fast {
+        let batch_settings = batch_settings.clone();
+        Box::new(Prefetch::start(scope, source, jobs, move |input: String| {
+            let result = (|| -> Result<(SpillBuffer, ScrubReport), String> {
+                let settings = file_settings(args, &input, header_lines, &batch_settings)?;
+                let mut buffer = SpillBuffer::new(memory);
+                let writer: Option<&mut dyn Write> = if buffers_output { Some(&mut buffer) } else { None };
+                let report = scrub_stream(&mut open_input(&input, args.timeout)?, writer, &settings)?;
+                Ok((buffer, report))
+            })();
+            (input, Some(result))
+        }))
+    } else {
+        Box::new(source.map(|input| (input, None)))
+    };
+    let mut transaction = args.transactional.then(Transaction::default);
+    let mut journal = args.journal.as_deref().map(|path| Journal::open(path, args.resume)).transpose()?;
+    let mut at_line_start = true;
+    let mut batch = Batch { reports: Reports::new(args), ..Batch::default() };
+    let mut staged_outputs = Vec::new();
+    let mut amalgamated = Vec::new();
+    for (input, prefetched) in files {
+        if interrupt::requested() {
+            batch.interrupted = true;
+            break;
+        }
+        let input = &input;
+        batch.files += 1;
+        let output_path = args.suffix.as_ref().map(|template| suffix::output_path(Path::new(input), template)).transpose()?;
+        let started = Instant::now();
+        if let Some(progress) = progress.as_mut() {
+            progress.start_file(input)?;
+        }
+        if let Some(events) = warnings.events {
+            events.emit(&Event::FileStart { path: input })?;
+        }
+        let allocations_before = timings::allocations();
+        let result = (|| -> Result<ScrubReport, String> {
+            let settings = file_settings(args, input, header_lines, batch_settings)?;
+            let prefetched = prefetched.transpose()?;
+            let mut buf_reader = match prefetched {
+                Some(_) => None,
+                None => Some(open_input(input, args.timeout)?),
+            };
+            let mut scrub_input = |writer: Option<&mut dyn Write>| match buf_reader.as_mut() {
+                Some(buf_reader) => scrub_stream(buf_reader, writer, &settings),
+                None => Err(format!("Input '{}' was already scrubbed.", input)),
+            };
+            let report = match (writer_holder.as_mut(), output_path.as_ref()) {
+                (Some(writer), _) => {
+                    let buffered = match prefetched {
+                        Some(prefetched) => Some(prefetched),
+                        None if !fail_fast => {
+                            let mut buffer = SpillBuffer::new(memory);
+                            let report = scrub_input(Some(&mut buffer))?;
+                            Some((buffer, report))
+                        }
+                        None => None,
+                    };
+                    let mut tracker = LineTracker { inner: writer, at_line_start };
+                    if use_delimiters {
+                        let separator = if tracker.at_line_start { "" } else { "\n" };
+                        writeln!(tracker, "{}{}", separator, args.delimiter.replace("{path}", input))
+                            .map_err(|e| format!("Failed to write file delimiter: {}", e))?;
                     }
-                    if line_buffer.ends_with('\n') || !line_buffer.is_empty() {
-                        actual_header_lines_counted += 1;
+                    let report = match buffered {
+                        Some((mut buffer, report)) => {
+                            buffer.copy_to(&mut tracker).map_err(|e| format!("Failed to write processed line: {}", e))?;
+                            report
+                        }
+                        None => scrub_input(Some(&mut tracker))?,
+                    };
+                    at_line_start = tracker.at_line_start;
+                    report
+                }
+                (None, Some(output_path)) if !args.dry_run => {
+                    let compress = args.compress.or_else(|| Compress::from_path(&output_path.to_string_lossy()));
+                    if let Some(trash) = trash {
+                        trash.preserve(output_path)?;
                     }
+                    let write_path = match transaction.as_mut() {
+                        Some(transaction) => transaction.stage(output_path, compress.is_some()),
+                        None => output_path.clone(),
+                    };
+                    let config = output_editorconfig(args, output_path)?;
+                    if transaction.is_none() {
+                        cleanup::remove_on_abort(&write_path);
+                    }
+                    let written = compress::create_file(&write_path, compress).and_then(|file_writer| {
+                        let mut file_writer = EditorConfigWriter::new(file_writer, config);
+                        let report = match prefetched {
+                            Some((mut prefetched, report)) => {
+                                prefetched
+                                    .copy_to(&mut file_writer)
+                                    .map_err(|e| format!("Failed to write output file '{}': {}", output_path.display(), e))?;
+                                report
+                            }
+                            None => scrub_input(Some(&mut file_writer))?,
+                        };
+                        file_writer
+                            .finish()
+                            .and_then(|file_writer| file_writer.finish())
+                            .map_err(|e| format!("Failed to flush output file '{}': {}", output_path.display(), e))?;
+                        Ok(report)
+                    });
+                    if transaction.is_none() {
+                        if written.is_err() {
+                            let _ = fs::remove_file(&write_path);
+                        }
+                        cleanup::forget(&write_path);
+                    }
+                    let report = written?;
+                    if let Some(journal) = journal.as_mut() {
+                        journal.record(input)?;
+                    }
+                    if transaction.is_some() {
+                        staged_outputs.push(output_path.clone());
+                    } else if !args.check {
+                        print_output_written(args, &output_path.display().to_string());
+                    }
+                    report
+                }
+                (None, _) if args.amalgamate.is_some() => {
+                    let mut buffer = Vec::new();
+                    let report = match prefetched {
+                        Some((mut prefetched, report)) => {
+                            prefetched.copy_to(&mut buffer).map_err(|e| format!("Failed to read scrubbed output: {}", e))?;
+                            report
+                        }
+                        None => scrub_input(Some(&mut buffer))?,
+                    };
+                    amalgamated.push((PathBuf::from(input), String::from_utf8_lossy(&buffer).into_owned()));
+                    report
+                }
+                (None, _) => match prefetched {
+                    Some((_, report)) => report,
+                    None => scrub_input(None)?,
+                },
+            };
+            if let Some(e) = plugin_error(&batch_settings.filter.plugins) {
+                return Err(e);
+            }
+            Ok(report)
+        })();
+        let report = match result {
+            Ok(report) => report,
+            Err(e) if fail_fast => return Err(e.into()),
+            Err(e) => {
+                if let Some(log) = warnings.log {
+                    log.record(&format!("{}: {}", input, e))?;
+                }
+                if let Some(events) = warnings.events {
+                    events.emit(&Event::FileError { path: input, message: &e })?;
+                }
+                if let Some(progress) = progress.as_mut() {
+                    progress.finish_file(0);
                 }
-                Err(e) => return Err(format!("Failed to read header line: {}", e)),
+                batch.failed.push((input.clone(), e));
+                continue;
             }
+        };
+        if let Some(log) = warnings.log {
+            log.file_timing(input, started.elapsed())?;
+        }
+        if let Some(events) = warnings.events {
+            events.file_report(input, &report, Some(started.elapsed()))?;
+        }
+        if let Some(progress) = progress.as_mut() {
+            progress.finish_file(report.bytes.before);
+        }
+        if args.timings.is_some() {
+            batch.timings.push(FileTiming {
+                path: input.clone(),
+                elapsed: started.elapsed(),
+                bytes: report.bytes.before,
+                allocations: timings::allocations() - allocations_before,
+            });
         }
+        batch.reports.add(args, warnings, memory, input, report)?;
     }
 
-    let mut all_changes: Vec<ChangeInfo> = Vec::new();
-    let mut stream_state = StreamState::default();
-    let mut lines_processed_in_body = 0;
+    batch.generated = walker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+    if let Some(progress) = progress {
+        progress.finish()?;
+    }
+    if let Some(writer) = writer_holder {
+        writer
+            .finish()
+            .and_then(|writer| writer.finish())
+            .map_err(|e| format!("Failed to flush output: {}", e))?;
+    }
+    if let Some(output_path) = &args.output {
+        cleanup::forget(Path::new(output_path));
+    }
+    if batch.interrupted {
+        if let Some(output_path) = &args.output {
+            fs::remove_file(output_path)
+                .map_err(|e| format!("Failed to remove partial output file '{}': {}", output_path, e))?;
+        }
+        return Ok(batch);
+    }
+    if let Some(transaction) = transaction {
+        if let Some(message) = batch.reports.unterminated.first() {
+            return Err(Failure::parse(message.clone()));
+        }
+        if args.verify {
+            transaction.verify().map_err(Failure::parse)?;
+        }
+        transaction.commit()?;
+        for output_path in &staged_outputs {
+            print_output_written(args, &output_path.display().to_string());
+        }
+    }
+    if let (Some(amalgamate_path), false) = (&args.amalgamate, args.dry_run) {
+        if let Some(trash) = trash {
+            trash.preserve(Path::new(amalgamate_path))?;
+        }
+        let config = output_editorconfig(args, Path::new(amalgamate_path))?;
+        let mut amalgamate_writer =
+            EditorConfigWriter::new(compress::create_file(Path::new(amalgamate_path), args.compress)?, config);
+        amalgamate_writer
+            .write_all(amalgamate(&amalgamated).as_bytes())
+            .and_then(|_| amalgamate_writer.finish())
+            .and_then(|writer| writer.finish().map(drop))
+            .map_err(|e| format!("Failed to write amalgamation file '{}': {}", amalgamate_path, e))?;
+    }
+    Ok(batch)
+}
 
-    loop {
-        line_buffer.clear();
-        match buf_reader.read_line(&mut line_buffer) {
-            Ok(0) => break, 
-            Ok(_) => {
-                let current_original_line_num = actual_header_lines_counted + lines_processed_in_body + 1;
-                
-                let (processed_segment, line_specific_changes) = process_line_streaming(
-                    &line_buffer,
-                    current_original_line_num,
-                    &mut stream_state,
-                );
-
-                if let Some(writer) = writer_holder.as_mut() {
-                    writer.write_all(processed_segment.as_bytes())
-                        .map_err(|e| format!("Failed to write processed line: {}", e))?;
-                }
-                all_changes.extend(line_specific_changes);
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let log = match args.log_file.as_deref().map(LogFile::create).transpose() {
+        Ok(log) => log,
+        Err(e) => return exit_code(Err(e.into())),
+    };
+    let events = match args.events.map(|_| EventStream::new(args.events_file.as_deref())).transpose() {
+        Ok(events) => events,
+        Err(e) => return exit_code(Err(e.into())),
+    };
+    let result = run(
+        &args,
+        Warnings {
+            deny: args.deny_warnings,
+            log: log.as_ref(),
+            events: events.as_ref(),
+        },
+    );
+    exit_code(match log {
+        Some(log) => finish_log(log, result),
+        None => result,
+    })
+}
 
-                if line_buffer.ends_with('\n') || !line_buffer.is_empty() { 
-                     lines_processed_in_body += 1; 
-                }
+fn collect_batch(args: &Args, warnings: Warnings, memory: &MemoryBudget, file_reports: FileReports) -> Result<Batch, String> {
+    let mut batch = Batch { reports: Reports::new(args), files: file_reports.len(), ..Batch::default() };
+    for (input, report) in file_reports {
+        if let Some(events) = warnings.events {
+            events.file_report(&input, &report, None)?;
+        }
+        batch.reports.add(args, warnings, memory, &input, report)?;
+    }
+    Ok(batch)
+}
 
+fn finish_log(log: LogFile, result: Result<Status, Failure>) -> Result<Status, Failure> {
+    let outcome = match &result {
+        Ok(status) => format!("Exit code {}", *status as u8),
+        Err(failure) => format!("Error: {} (exit code {})", failure.message, failure.status as u8),
+    };
+    log.record(&outcome).and_then(|_| log.finish())?;
+    result
+}
 
+fn check_features(args: &Args) -> Result<(), String> {
+    if args.item.is_some() || args.except_item.is_some() {
+        item::check_available()?;
+    }
+    if args.count_tokens.is_some() {
+        rustscrub::tokens::check_available()?;
+    }
+    if args.clone.is_some() {
+        clone::check_available()?;
+    }
+    if args.verify {
+        transaction::check_available()?;
+    }
+    let outputs = [args.output.as_deref(), args.suffix.as_deref()];
+    for compress in args.compress.into_iter().chain(outputs.into_iter().flatten().filter_map(Compress::from_path)) {
+        compress.check_available()?;
+    }
+    for input in &args.input {
+        if is_url(input) {
+            remote::check_available()?;
+        } else if archive_kind(input).is_some() {
+            archive::check_available(input)?;
+        }
+    }
+    Ok(())
+}
+
+fn run(args: &Args, warnings: Warnings) -> Result<Status, Failure> {
+    schema::set_tool(Tool::new(std::env::args().skip(1).collect(), schema::build_date(args.date)?));
+    match &args.command {
+        Some(Command::Pack(pack_args)) => {
+            pack_args.check_features().map_err(Failure::usage)?;
+            return pack::run(pack_args).map(|_| Status::Clean).map_err(Failure::from);
+        }
+        Some(Command::Classify(classify_args)) => {
+            classify_args.check_features().map_err(Failure::usage)?;
+            return classify_report::run(classify_args).map(|_| Status::Clean).map_err(Failure::from)
+        }
+        Some(Command::Check(check_args)) => {
+            check_args.check_features().map_err(Failure::usage)?;
+            return check::run(check_args).map(|_| Status::Clean);
+        }
+        Some(Command::Spell(spell_args)) => return spell::run(spell_args).map(|_| Status::Clean),
+        Some(Command::Banned(banned_args)) => return banned_report::run(banned_args).map(|_| Status::Clean),
+        Some(Command::Stats(stats_args)) => {
+            stats_args.check_features().map_err(Failure::usage)?;
+            return stats::run(stats_args).map(|_| Status::Clean).map_err(Failure::from);
+        }
+        Some(Command::DiffComments(diff_args)) => return diff_comments::run(diff_args).map_err(Failure::from),
+        Some(Command::Schema(schema_args)) => return schema_report::run(schema_args).map(|_| Status::Clean).map_err(Failure::from),
+        Some(Command::SelfUpdate(update_args)) => {
+            remote::check_available().map_err(Failure::usage)?;
+            return self_update::run(update_args).map(|_| Status::Clean).map_err(Failure::from);
+        }
+        Some(Command::Undo(undo_args)) => return trash::run(undo_args).map(|_| Status::Clean).map_err(Failure::from),
+        None => {}
+    }
+    if args.schema {
+        print!("{}", REPORT_SCHEMA);
+        return Ok(Status::Clean);
+    }
+    if let Some(log) = warnings.log {
+        log.record(&format!("Run started: {}", std::env::args().collect::<Vec<_>>().join(" ")))?;
+    }
+    check_features(args).map_err(Failure::usage)?;
+    interrupt::install()?;
+    if args.timings.is_some() {
+        timings::count_allocations();
+    }
+    for input in &args.input {
+        if is_url(input) {
+            if args.suffix.is_some() {
+                return Err(Failure::usage(format!("Remote input '{}' cannot be combined with --suffix.", input)));
             }
-            Err(e) => return Err(format!("Failed to read line for processing: {}", e)),
+        } else if !Path::new(input).exists() {
+            return Err(format!("Input path '{}' does not exist.", input).into());
+        } else if !Path::new(input).is_dir() && !Path::new(input).is_file() {
+            return Err(format!("Input path '{}' is not a file.", input).into());
         }
     }
-    
-    if let Some(mut writer) = writer_holder { 
-        writer.flush().map_err(|e| format!("Failed to flush output: {}", e))?;
+    let done = match (&args.journal, args.resume) {
+        (Some(journal_path), true) => {
+            let done = journal::completed(journal_path)?;
+            eprintln!("RustScrub: Resuming from {}, skipping {} completed files", journal_path, done.len());
+            done
+        }
+        _ => HashSet::new(),
+    };
+
+    let token_model = args.count_tokens.or((args.summary && cfg!(feature = "tokenizer")).then(TokenModel::default));
+    let needs_comment_text = args.scrub.inventory.is_some()
+        || args.extract_doctests.is_some()
+        || args.events.is_some()
+        || (args.verbose && args.verbose_format == VerboseFormat::Json);
+    let mut batch_settings = ScrubSettings {
+        header_lines: args.header_lines,
+        token_model,
+        line_map: args.line_map.is_some(),
+        change_detail: if needs_comment_text { ChangeDetail::Full } else { ChangeDetail::Lines },
+        ..args.scrub.settings()
+    };
+    let config = Config::load(args.config.as_deref())?;
+    if args.redact {
+        batch_settings.filter.redact = Some(banned_report::load_banned(&config.banned, &[], &[])?);
+    }
+    plugins::check_available(&args.plugin, &config.rules).map_err(Failure::usage)?;
+    batch_settings.filter.plugins = load_plugins(&args.plugin, args.filter_cmd.as_deref(), &config.rules)?;
+    let trash = args.trash.then(|| Trash::new(Path::new(TRASH_DIR))).transpose()?;
+    let memory = MemoryBudget::new(args.max_memory);
+    let batch = match (&args.clone, args.input.iter().find(|input| archive_kind(input).is_some())) {
+        (Some(url), _) => {
+            let output_dir = (!args.dry_run && !args.check)
+                .then(|| args.output_dir.as_deref().ok_or("--clone requires --output-dir for the scrubbed export."))
+                .transpose()
+                .map_err(|e| Failure::usage(e.to_string()))?;
+            clone::validate_source(url, args.git_ref.as_deref()).map_err(Failure::usage)?;
+            let reports = clone::export_revision(
+                url,
+                args.git_ref.as_deref(),
+                output_dir.map(Path::new),
+                &batch_settings,
+                !args.no_editorconfig,
+                args.final_newline,
+            )?;
+            collect_batch(args, warnings, &memory, reports)?
+        }
+        (None, Some(archive_input)) => {
+            if args.input.len() > 1 || args.amalgamate.is_some() || args.suffix.is_some() {
+                return Err(Failure::usage(format!(
+                    "Archive input '{}' must be the only input and cannot be combined with --amalgamate or --suffix.",
+                    archive_input
+                )));
+            }
+            let output = (!args.dry_run && !args.check)
+                .then(|| args.output.as_deref().ok_or("Archive input requires --output for the scrubbed archive."))
+                .transpose()
+                .map_err(|e| Failure::usage(e.to_string()))?;
+            if let (Some(trash), Some(output)) = (&trash, output) {
+                trash.preserve(Path::new(output))?;
+            }
+            let reports = scrub_archive(archive_input, output, &batch_settings, args.final_newline, &memory)?;
+            collect_batch(args, warnings, &memory, reports)?
+        }
+        (None, None) => {
+            thread::scope(|scope| scrub_files(scope, args, &done, &batch_settings, warnings, trash.as_ref(), &memory))?
+        }
+    };
+    let Batch { reports, files, generated, failed, timings, interrupted } = batch;
+    if let Some(e) = plugin_error(&batch_settings.filter.plugins) {
+        return Err(e.into());
+    }
+    for message in reports.unterminated {
+        if args.strict {
+            return Err(Failure::parse(message));
+        }
+        warnings.warn(Status::Parse, message)?;
+    }
+    if reports.elided > 0 {
+        warnings.warn(
+            Status::Io,
+            format!(
+                "Dropped removed comment text for {} files to stay within --max-memory; reports list only their positions.",
+                reports.elided
+            ),
+        )?;
+    }
+
+    if args.summary {
+        print_summary(&reports.summaries, token_model);
+        if !generated.is_empty() {
+            eprintln!("RustScrub: Skipped {} generated files (use --include-generated to scrub them):", generated.len());
+            for input in &generated {
+                eprintln!("RustScrub:   {}", input);
+            }
+        }
+    } else if !generated.is_empty() {
+        eprintln!("RustScrub: Skipped {} generated files; use --include-generated to scrub them", generated.len());
     }
 
+    print_file_summary(&reports.rows, args.summary_format)?;
 
+    if let Some(inventory_path) = &args.scrub.inventory {
+        let mut inventory = Inventory::default();
+        for (input, report) in &reports.files {
+            inventory.add_file(input, &report.changes);
+        }
+        fs::write(inventory_path, inventory.to_json()?)
+            .map_err(|e| format!("Failed to write inventory file '{}': {}", inventory_path, e))?;
+    }
+    if let Some(line_map_path) = &args.line_map {
+        line_map::write(line_map_path, &reports.files)?;
+    }
+    if let (Some(doctests_path), false) = (&args.extract_doctests, args.dry_run) {
+        let extracted: Vec<doctests::Doctest> = reports
+            .files
+            .iter()
+            .flat_map(|(input, report)| doctests::extract(input, &report.changes))
+            .collect();
+        fs::write(doctests_path, doctests::render(&extracted))
+            .map_err(|e| format!("Failed to write doctest file '{}': {}", doctests_path, e))?;
+        eprintln!("RustScrub: Extracted {} doctests into {}", extracted.len(), doctests_path);
+    }
     if args.verbose {
-        if !all_changes.is_empty() {
-            eprintln!("RustScrub: Comments Removed (Verbose Mode):");
-            for change in &all_changes { 
-                match change.comment_type {
-                    VerboseCommentType::Line => {
-                        eprintln!("- Line {}: Removed line comment.", change.start_line);
-                    }
-                    VerboseCommentType::Block => {
-                        if change.start_line == change.end_line {
-                            eprintln!("- Line {}: Removed block comment.", change.start_line);
-                        } else {
-                            eprintln!(
-                                "- Lines {}-{}: Removed block comment.",
-                                change.start_line, change.end_line
-                            );
-                        }
-                    }
-                }
-            }
-            let line_comments_removed = all_changes.iter().filter(|c| c.comment_type == VerboseCommentType::Line).count();
-            let block_comments_removed = all_changes.iter().filter(|c| c.comment_type == VerboseCommentType::Block).count();
-            eprintln!("---");
-            eprintln!("RustScrub Statistics:");
-            eprintln!("- Total line comments removed: {}", line_comments_removed);
-            eprintln!("- Total block comments removed: {}", block_comments_removed);
-            eprintln!("---");
+        print_verbose(&reports.files, args.verbose_format)?;
+    }
 
-        } else {
-             eprintln!("RustScrub: No comments found to remove in the processed section (Verbose Mode).");
+    if let Some(model) = args.count_tokens {
+        let mut token_tally = Tally::default();
+        eprintln!("RustScrub Token Counts ({}):", model);
+        for (input, tokens) in &reports.tokens {
+            token_tally.add(tokens);
+            eprintln!(
+                "- {}: {} -> {} ({:.1}% saved)",
+                input, tokens.before, tokens.after, tokens.saved_percent()
+            );
+        }
+        eprintln!(
+            "- Total: {} -> {} ({:.1}% saved)",
+            token_tally.before, token_tally.after, token_tally.saved_percent()
+        );
+    }
+
+    if let Some(slowest) = args.timings {
+        eprint!("{}", format_timings(&timings, slowest));
+    }
+
+    if interrupted {
+        return Err(Failure::interrupted(format!("Interrupted after {} files", files)));
+    }
+    if !failed.is_empty() {
+        eprintln!("RustScrub: {} of {} files failed:", failed.len(), files);
+        for (input, error) in &failed {
+            eprintln!("- {}: {}", input, error);
         }
+        return Err(Failure::from(format!("{} of {} files failed", failed.len(), files)));
+    }
+
+    if args.check {
+        let thresholds = CheckThresholds {
+            max_comments: args.max_comments,
+            max_comment_ratio: args.max_comment_ratio,
+        };
+        return check::evaluate(&reports.files, thresholds).map(|_| Status::Clean);
     }
 
     if args.dry_run {
@@ -180,16 +1050,23 @@ fn main() -> Result<(), String> {
             eprintln!("RustScrub: Dry run complete. No output file written.");
         } else { 
             println!("RustScrub: Dry run complete. {} line comments and {} block comments would be removed. No output file written.",
-                all_changes.iter().filter(|c| c.comment_type == VerboseCommentType::Line).count(),
-                all_changes.iter().filter(|c| c.comment_type == VerboseCommentType::Block).count()
+                reports.line_comments,
+                reports.block_comments
             );
         }
-    } else if args.output.is_some() && !args.verbose { 
-         println!("RustScrub: Output written to {}", args.output.unwrap_or_default());
-    } else if args.output.is_some() && args.verbose { 
-         eprintln!("RustScrub: Output written to {}", args.output.unwrap_or_default());
+    } else if let (None, Some(output)) = (&args.suffix, args.output.as_ref().or(args.amalgamate.as_ref()).or(args.output_dir.as_ref())) {
+        print_output_written(args, output);
     }
-    Ok(())
+    if let Some(trash) = trash.as_ref().filter(|trash| trash.count() > 0) {
+        eprintln!(
+            "RustScrub: Saved {} overwritten files to {}/{}; restore them with `rustscrub undo {}`",
+            trash.count(),
+            TRASH_DIR,
+            trash.run_id(),
+            trash.run_id()
+        );
+    }
+    Ok(if reports.changed { Status::Changes } else { Status::Clean })
 }
 
 #[cfg(test)]
@@ -496,4 +1373,175 @@ mod tests {
         let expected = "let x = 1;";
         assert_code_eq(&scrub_comments_string(input, 0), expected);
     }
+
+    static RUN_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn run_args(args: &[&std::ffi::OsStr]) -> Result<super::Status, super::Failure> {
+        use clap::Parser;
+
+        let args = super::Args::parse_from(std::iter::once("rustscrub".as_ref()).chain(args.iter().copied()));
+        super::run(&args, super::Warnings { deny: false, log: None, events: None })
+    }
+
+    #[test]
+    fn test_multiple_inputs_are_delimited_without_header_prompt() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("rustscrub-delimiters-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("a.rs");
+        let second = dir.join("b.rs");
+        let output = dir.join("out.rs");
+        std::fs::write(&first, "// header\nfn a() {}").unwrap();
+        std::fs::write(&second, "// doc\nfn b() {}\n").unwrap();
+        let status = run_args(&[first.as_os_str(), second.as_os_str(), "-o".as_ref(), output.as_os_str()]);
+        let written = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(status, Ok(super::Status::Changes));
+        assert_eq!(
+            written,
+            format!("// ===== {} =====\nfn a() {{}}\n// ===== {} =====\nfn b() {{}}\n", first.display(), second.display())
+        );
+    }
+
+    #[test]
+    fn test_directory_batch_writes_files_in_walk_order_on_every_core() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("rustscrub-stream-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        let mut expected = String::new();
+        for index in 0..24 {
+            let path = dir.join("src").join(format!("f{:02}.rs", index));
+            std::fs::write(&path, format!("// {}\nfn f{}() {{}}\n", "x".repeat(index * 500), index)).unwrap();
+            expected.push_str(&format!("// ===== {} =====\nfn f{}() {{}}\n", path.display(), index));
+        }
+        let output = dir.join("out.rs");
+        let status = run_args(&[dir.join("src").as_os_str(), "-o".as_ref(), output.as_os_str()]);
+        let written = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(status, Ok(super::Status::Changes));
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn test_interrupted_batch_exits_with_130_and_removes_partial_output() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("rustscrub-interrupt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("a.rs");
+        let second = dir.join("b.rs");
+        let output = dir.join("out.rs");
+        std::fs::write(&first, "fn a() {} // a\n").unwrap();
+        std::fs::write(&second, "fn b() {} // b\n").unwrap();
+        super::interrupt::set_requested(true);
+        let status = run_args(&[first.as_os_str(), second.as_os_str(), "-H1".as_ref(), "-o".as_ref(), output.as_os_str()]);
+        super::interrupt::set_requested(false);
+        let output_exists = output.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let failure = status.unwrap_err();
+        assert_eq!(failure.status, super::Status::Interrupted);
+        assert_eq!(failure.message, "Interrupted after 0 files");
+        assert_eq!(super::exit_code(Err(failure)), std::process::ExitCode::from(130));
+        assert!(!output_exists);
+    }
+
+    #[test]
+    fn test_failing_file_fails_the_batch_after_the_remaining_files() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("rustscrub-failures-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let broken = dir.join("a.rs");
+        let valid = dir.join("b.rs");
+        let valid_output = dir.join("b.clean.rs");
+        std::fs::write(&broken, b"\xff\xfe // not utf-8\n").unwrap();
+        std::fs::write(&valid, "// b\nfn b() {}\n").unwrap();
+        let inputs = [broken.as_os_str(), valid.as_os_str(), "--suffix".as_ref(), ".clean.rs".as_ref()];
+
+        let failure = run_args(&inputs).unwrap_err();
+        let continued = std::fs::read_to_string(&valid_output);
+        std::fs::remove_file(&valid_output).unwrap_or(());
+        let fail_fast = run_args(&[&inputs[..], &["--fail-fast".as_ref()]].concat()).unwrap_err();
+        let stopped = !valid_output.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(failure.status, super::Status::Io);
+        assert_eq!(failure.message, "1 of 2 files failed");
+        assert_eq!(continued.unwrap(), "fn b() {}\n");
+        assert_eq!(fail_fast.status, super::Status::Io);
+        assert!(fail_fast.message.contains("valid UTF-8"));
+        assert!(stopped);
+    }
+
+    #[test]
+    fn test_max_memory_keeps_check_ratio() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let path = std::env::temp_dir().join(format!("rustscrub-max-memory-{}.rs", std::process::id()));
+        std::fs::write(&path, "fn f() {} // a fairly long trailing comment\n").unwrap();
+        let check = [path.as_os_str(), "--check".as_ref(), "--max-comments".as_ref(), "1".as_ref()];
+        let ratio = ["--max-comment-ratio".as_ref(), "10".as_ref(), "--max-memory".as_ref(), "1".as_ref()];
+        let failure = run_args(&[&check[..], &ratio[..]].concat());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(failure.unwrap_err().status, super::Status::Changes);
+        let inventory = <super::Args as clap::Parser>::try_parse_from(["rustscrub", "a.rs", "--inventory", "i.json", "--max-memory", "1K"]);
+        assert!(inventory.is_err());
+    }
+
+    #[test]
+    fn test_pack_final_newline_never_strips_every_trailing_newline() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("rustscrub-pack-newline-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("lib.rs");
+        let output = dir.join("pack.txt");
+        std::fs::write(&input, "fn f() {}\n// gone\n\n\n").unwrap();
+        let status = run_args(&["pack".as_ref(), input.as_os_str(), "-o".as_ref(), output.as_os_str(), "--final-newline".as_ref(), "never".as_ref()]);
+        let written = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(status, Ok(super::Status::Clean));
+        assert_eq!(written, format!("==== {} ====\nfn f() {{}}", input.display()));
+    }
+
+    #[test]
+    fn test_missing_features_fail_as_usage_errors() {
+        let _lock = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let path = std::env::temp_dir().join(format!("rustscrub-features-{}.rs", std::process::id()));
+        std::fs::write(&path, "pub fn f() {}\n").unwrap();
+        let file = path.to_str().unwrap();
+        let cases = [
+            (cfg!(feature = "items"), vec![file, "--item", "f"]),
+            (cfg!(feature = "items"), vec!["check", file, "--min-doc-coverage", "80"]),
+            (cfg!(feature = "plugins"), vec![file, "-H1", "--plugin", "filter.wasm"]),
+            (cfg!(feature = "tokenizer"), vec![file, "--count-tokens"]),
+            (cfg!(feature = "tokenizer"), vec!["pack", file, "--budget", "100"]),
+            (cfg!(feature = "git"), vec!["stats", file, "--compare", "HEAD~1..HEAD"]),
+            (cfg!(feature = "git"), vec!["--clone", "https://example.com/repo.git", "--output-dir", "out"]),
+            (cfg!(feature = "http"), vec!["https://example.com/lib.rs"]),
+            (cfg!(feature = "zstd"), vec![file, "-o", "out.rs.zst"]),
+            (cfg!(feature = "archives"), vec!["src.zip", "-o", "out.zip"]),
+            (cfg!(feature = "language-detection"), vec!["classify", file, "--detect-language"]),
+            (cfg!(feature = "verify"), vec![file, "--suffix", ".clean.rs", "--transactional", "--verify"]),
+        ];
+        let failures: Vec<_> = cases
+            .iter()
+            .filter(|(enabled, _)| !enabled)
+            .map(|(_, args)| (args, run_args(&args.iter().map(std::ffi::OsStr::new).collect::<Vec<_>>())))
+            .collect();
+        std::fs::remove_file(&path).unwrap();
+        for (args, result) in failures {
+            let failure = result.unwrap_err();
+            assert_eq!(failure.status, super::Status::Usage, "{:?}: {}", args, failure.message);
+            assert!(failure.message.contains("feature"), "{:?}: {}", args, failure.message);
+        }
+    }
+
+    #[test]
+    fn test_jobs_defaults_to_every_core() {
+        let args = <super::Args as clap::Parser>::parse_from(["rustscrub", "a.rs"]);
+        assert_eq!(args.jobs, 0);
+    }
+
+    #[test]
+    fn test_zero_jobs_uses_every_core() {
+        assert_eq!(super::worker_count(3), 3);
+        assert_eq!(super::worker_count(0), std::thread::available_parallelism().map_or(1, |count| count.get()));
+    }
 }
@@ -0,0 +1,318 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/modgraph.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn string_literal(chars: &[char], mut i: usize) -> (String, usize) {
+    let mut value = String::new();
+    while i < chars.len() && chars[i] != '"' {
+        if chars[i] == '\\' {
+            i += 1;
+        }
+        if let Some(&c) = chars.get(i) {
+            value.push(c);
+        }
+        i += 1;
+    }
+    (value, i + 1)
+}
+
+fn raw_string_literal(chars: &[char], mut i: usize) -> Option<(String, usize)> {
+    let hashes = chars[i..].iter().take_while(|&&c| c == '#').count();
+    i += hashes;
+    if chars.get(i) != Some(&'"') {
+        return None;
+    }
+    let start = i + 1;
+    let mut end = start;
+    while end < chars.len() {
+        if chars[end] == '"' && chars[end + 1..].iter().take(hashes).filter(|&&c| c == '#').count() == hashes {
+            return Some((chars[start..end].iter().collect(), end + 1 + hashes));
+        }
+        end += 1;
+    }
+    Some((chars[start..].iter().collect(), chars.len()))
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if c == '"' {
+            let (value, end) = string_literal(&chars, i + 1);
+            tokens.push(Token::Str(value));
+            i = end;
+        } else if c == '\'' {
+            if next == Some('\\') {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            } else if chars.get(i + 2) == Some(&'\'') {
+                i += 3;
+            } else {
+                i += 1;
+            }
+        } else if is_ident_char(c) {
+            let start = i;
+            while i < chars.len() && is_ident_char(chars[i]) {
+                i += 1;
+            }
+            let ident: String = chars[start..i].iter().collect();
+            let after = chars.get(i).copied();
+            if matches!(ident.as_str(), "r" | "br" | "cr") && matches!(after, Some('"') | Some('#')) {
+                if let Some((value, end)) = raw_string_literal(&chars, i) {
+                    tokens.push(Token::Str(value));
+                    i = end;
+                } else {
+                    i += 1;
+                }
+            } else if matches!(ident.as_str(), "b" | "c") && after == Some('"') {
+                let (value, end) = string_literal(&chars, i + 1);
+                tokens.push(Token::Str(value));
+                i = end;
+            } else if !(c.is_ascii_digit() || ident == "b" && after == Some('\'')) {
+                tokens.push(Token::Ident(ident));
+            }
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn path_attribute(tokens: &[Token]) -> Option<String> {
+    match tokens {
+        [Token::Ident(name), Token::Punct('='), Token::Str(path)] if name == "path" => Some(path.clone()),
+        _ => None,
+    }
+}
+
+fn declared_modules(file: &Path, is_mod_rs: bool, source: &str) -> Vec<(String, Vec<(PathBuf, bool)>)> {
+    let tokens = tokenize(source);
+    let file_dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+    let file_base = if is_mod_rs {
+        file_dir.clone()
+    } else {
+        file_dir.join(file.file_stem().unwrap_or_default())
+    };
+    let mut modules = Vec::new();
+    let mut inline: Vec<(PathBuf, usize)> = Vec::new();
+    let mut depth = 0;
+    let mut pending_path = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let base = inline.last().map_or(&file_base, |(dir, _)| dir).clone();
+        match &tokens[i] {
+            Token::Punct('#') => {
+                let open = if tokens.get(i + 1) == Some(&Token::Punct('!')) { i + 2 } else { i + 1 };
+                if tokens.get(open) != Some(&Token::Punct('[')) {
+                    i += 1;
+                    continue;
+                }
+                let mut close = open + 1;
+                let mut brackets = 1;
+                while close < tokens.len() {
+                    match tokens[close] {
+                        Token::Punct('[') => brackets += 1,
+                        Token::Punct(']') => brackets -= 1,
+                        _ => {}
+                    }
+                    if brackets == 0 {
+                        break;
+                    }
+                    close += 1;
+                }
+                if let Some(path) = path_attribute(&tokens[open + 1..close.min(tokens.len())]) {
+                    pending_path = Some(path);
+                }
+                i = close + 1;
+                continue;
+            }
+            Token::Ident(keyword) if keyword == "mod" => {
+                if let (Some(Token::Ident(name)), Some(Token::Punct(next))) = (tokens.get(i + 1), tokens.get(i + 2)) {
+                    match next {
+                        ';' => {
+                            let candidates = match pending_path.take() {
+                                Some(path) if inline.is_empty() => vec![(file_dir.join(path), true)],
+                                Some(path) => vec![(base.join(path), true)],
+                                None => vec![(base.join(format!("{}.rs", name)), false), (base.join(name).join("mod.rs"), true)],
+                            };
+                            modules.push((name.clone(), candidates));
+                        }
+                        '{' => {
+                            depth += 1;
+                            let dir = base.join(pending_path.take().unwrap_or_else(|| name.clone()));
+                            inline.push((dir, depth));
+                        }
+                        _ => {}
+                    }
+                    i += 3;
+                    continue;
+                }
+            }
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                if inline.last().is_some_and(|(_, open)| *open == depth) {
+                    inline.pop();
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        if matches!(tokens[i], Token::Punct(';') | Token::Punct('{') | Token::Punct('}')) {
+            pending_path = None;
+        }
+        i += 1;
+    }
+    modules
+}
+
+pub fn included_files(file: &Path, source: &str) -> Vec<PathBuf> {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    tokenize(source)
+        .windows(5)
+        .filter_map(|window| match window {
+            [Token::Ident(name), Token::Punct('!'), Token::Punct('(' | '[' | '{'), Token::Str(path), Token::Punct(')' | ']' | '}')]
+                if name == "include" || name == "include_str" =>
+            {
+                Some(dir.join(path))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn crate_roots(dir: &Path) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = ["src/lib.rs", "src/main.rs"].iter().map(|root| dir.join(root)).filter(|root| root.is_file()).collect();
+    if let Ok(entries) = fs::read_dir(dir.join("src/bin")) {
+        let mut bins: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .map(|path| if path.is_dir() { path.join("main.rs") } else { path })
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "rs"))
+            .collect();
+        bins.sort();
+        roots.extend(bins);
+    }
+    if roots.is_empty() {
+        roots.extend(["lib.rs", "main.rs"].iter().map(|root| dir.join(root)).filter(|root| root.is_file()));
+    }
+    roots
+}
+
+pub fn crate_files(roots: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending: Vec<(PathBuf, bool)> = roots.iter().rev().map(|root| (root.clone(), true)).collect();
+    while let Some((file, is_mod_rs)) = pending.pop() {
+        if !seen.insert(fs::canonicalize(&file).unwrap_or_else(|_| file.clone())) {
+            continue;
+        }
+        let source = fs::read_to_string(&file).map_err(|e| format!("Failed to read '{}': {}", file.display(), e))?;
+        let mut children = Vec::new();
+        for (name, candidates) in declared_modules(&file, is_mod_rs, &source) {
+            match candidates.into_iter().find(|(path, _)| path.is_file()) {
+                Some(child) => children.push(child),
+                None => eprintln!("RustScrub: Skipping module '{}' declared in '{}': no source file found.", name, file.display()),
+            }
+        }
+        pending.extend(children.into_iter().rev());
+        files.push(file);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crate_files_follow_mod_declarations() {
+        let root = std::env::temp_dir().join(format!("rustscrub-modgraph-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/a")).unwrap();
+        fs::create_dir_all(root.join("src/c")).unwrap();
+        fs::create_dir_all(root.join("src/gen")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "// mod commented;\nconst S: &str = \"mod quoted;\";\nconst R: &str = r#\"mod raw;\"#;\nfn f<'a>(_: &'a str) -> char { '{' }\n\
+             pub mod a;\n#[cfg(test)]\n#[path = \"gen/bindings.rs\"]\nmod b;\npub(crate) mod c {\n    mod d;\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/a.rs"), "mod inner;\n").unwrap();
+        fs::write(root.join("src/a/inner.rs"), "").unwrap();
+        fs::write(root.join("src/gen/bindings.rs"), "").unwrap();
+        fs::write(root.join("src/c/d.rs"), "").unwrap();
+        fs::write(root.join("src/orphan.rs"), "").unwrap();
+
+        let roots = crate_roots(&root);
+        let files = crate_files(&roots).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(roots, vec![root.join("src/lib.rs")]);
+        let expected: Vec<PathBuf> = ["src/lib.rs", "src/a.rs", "src/a/inner.rs", "src/gen/bindings.rs", "src/c/d.rs"]
+            .iter()
+            .map(|file| root.join(file))
+            .collect();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn test_included_files_are_relative_to_the_including_file() {
+        let source = "const A: &str = include_str!(\"../shaders/blur.wgsl\");\ninclude!(concat!(env!(\"OUT_DIR\"), \"/gen.rs\"));\n// include_str!(\"skipped.txt\")\nconst B: &[u8] = include_bytes!(\"data.bin\");\ninclude! { \"table.rs\" }\n";
+        let files = included_files(Path::new("src/lib.rs"), source);
+        assert_eq!(files, vec![PathBuf::from("src/../shaders/blur.wgsl"), PathBuf::from("src/table.rs")]);
+    }
+
+    #[test]
+    fn test_crate_files_match_this_crate() {
+        let files = crate_files(&crate_roots(Path::new("."))).unwrap();
+        assert!(files.contains(&PathBuf::from("./src/lib.rs")));
+        assert!(files.contains(&PathBuf::from("./src/main.rs")));
+        assert!(files.contains(&PathBuf::from("./src/modgraph.rs")));
+        assert!(files.contains(&PathBuf::from("./src/lexer.rs")));
+    }
+}
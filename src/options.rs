@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/options.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use serde::Deserialize;
+
+use crate::blank_lines::BlankLineLimit;
+use crate::classify::CommentCategory;
+use crate::filter::{CommentFilter, LineRange};
+use crate::scrub::ScrubSettings;
+use crate::transform::Transform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Minimal,
+    #[default]
+    Standard,
+    Aggressive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoveKind {
+    Redundant,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ScrubArgs {
+    #[clap(long, value_enum, default_value_t = Level::Standard)]
+    pub level: Level,
+
+    #[clap(long, value_name = "keep-N", num_args = 0..=1, require_equals = true, default_missing_value = "keep-0")]
+    pub strip_blank_lines: Option<BlankLineLimit>,
+
+    #[clap(long, value_name = "FILE")]
+    pub inventory: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_outer_doc: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_inner_doc: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_links: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_regions: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_test_annotations: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_in_tests: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_in_macros: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_above_unsafe: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_above_pub: bool,
+
+    #[clap(long, value_name = "N")]
+    pub keep_blocks_over: Option<usize>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub strip_directives: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub keep_license: bool,
+
+    #[clap(long, value_name = "N")]
+    pub min_length: Option<usize>,
+
+    #[clap(long, value_name = "N")]
+    pub max_length: Option<usize>,
+
+    #[clap(long = "keep-category", value_enum, value_delimiter = ',', value_name = "CATEGORY")]
+    pub keep_categories: Vec<CommentCategory>,
+
+    #[clap(long, value_enum, value_name = "KIND")]
+    pub remove: Option<RemoveKind>,
+
+    #[clap(long, value_enum, value_name = "STYLE")]
+    pub transform: Option<Transform>,
+
+    #[clap(long, value_name = "WIDTH")]
+    pub rewrap: Option<usize>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub mark_removals: bool,
+
+    #[clap(long = "lines", value_name = "START:END", conflicts_with = "strip_blank_lines")]
+    pub lines: Vec<LineRange>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScrubOptions {
+    pub header_lines: usize,
+    pub level: Level,
+    pub strip_blank_lines: Option<usize>,
+    pub keep_outer_doc: bool,
+    pub keep_inner_doc: bool,
+    pub keep_links: bool,
+    pub keep_regions: bool,
+    pub keep_test_annotations: bool,
+    pub keep_in_tests: bool,
+    pub keep_in_macros: bool,
+    pub keep_above_unsafe: bool,
+    pub keep_above_pub: bool,
+    pub keep_blocks_over: Option<usize>,
+    pub strip_directives: bool,
+    pub keep_license: bool,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub keep_categories: Vec<CommentCategory>,
+    pub remove: Option<RemoveKind>,
+    pub transform: Option<Transform>,
+    pub rewrap: Option<usize>,
+    pub mark_removals: bool,
+    pub lines: Vec<LineRange>,
+}
+
+impl ScrubOptions {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        if json.trim().is_empty() {
+            return Ok(ScrubOptions::default());
+        }
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse options: {}", e))
+    }
+
+    pub fn settings(&self) -> ScrubSettings {
+        let mut settings = ScrubSettings {
+            header_lines: self.header_lines,
+            blank_line_limit: self.strip_blank_lines.map(BlankLineLimit),
+            filter: CommentFilter {
+                keep_outer_doc: self.keep_outer_doc,
+                keep_inner_doc: self.keep_inner_doc,
+                keep_links: self.keep_links,
+                keep_regions: self.keep_regions,
+                keep_test_annotations: self.keep_test_annotations,
+                keep_in_tests: self.keep_in_tests,
+                keep_in_macros: self.keep_in_macros,
+                keep_above_unsafe: self.keep_above_unsafe,
+                keep_above_pub: self.keep_above_pub,
+                keep_blocks_over: self.keep_blocks_over,
+                keep_directives: !self.strip_directives,
+                keep_license: self.keep_license,
+                min_length: self.min_length,
+                max_length: self.max_length,
+                keep_categories: self.keep_categories.clone(),
+                remove_redundant: self.remove == Some(RemoveKind::Redundant),
+                transform: self.transform,
+                rewrap: self.rewrap,
+                mark_removals: self.mark_removals,
+                line_ranges: self.lines.clone(),
+                ..CommentFilter::default()
+            },
+            ..ScrubSettings::default()
+        };
+        match self.level {
+            Level::Minimal => {
+                settings.filter.keep_doc = true;
+                settings.filter.keep_safety = true;
+            }
+            Level::Standard => {}
+            Level::Aggressive => {
+                settings.blank_line_limit = settings.blank_line_limit.or(Some(BlankLineLimit(0)));
+                settings.strip_tests = true;
+            }
+        }
+        if !self.lines.is_empty() {
+            settings.blank_line_limit = None;
+            settings.strip_tests = false;
+        }
+        settings
+    }
+}
+
+impl ScrubArgs {
+    pub fn options(&self) -> ScrubOptions {
+        ScrubOptions {
+            header_lines: 0,
+            level: self.level,
+            strip_blank_lines: self.strip_blank_lines.map(|limit| limit.0),
+            keep_outer_doc: self.keep_outer_doc,
+            keep_inner_doc: self.keep_inner_doc,
+            keep_links: self.keep_links,
+            keep_regions: self.keep_regions,
+            keep_test_annotations: self.keep_test_annotations,
+            keep_in_tests: self.keep_in_tests,
+            keep_in_macros: self.keep_in_macros,
+            keep_above_unsafe: self.keep_above_unsafe,
+            keep_above_pub: self.keep_above_pub,
+            keep_blocks_over: self.keep_blocks_over,
+            strip_directives: self.strip_directives,
+            keep_license: self.keep_license,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            keep_categories: self.keep_categories.clone(),
+            remove: self.remove,
+            transform: self.transform,
+            rewrap: self.rewrap,
+            mark_removals: self.mark_removals,
+            lines: self.lines.clone(),
+        }
+    }
+
+    pub fn settings(&self) -> ScrubSettings {
+        self.options().settings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_from_json() {
+        let options = ScrubOptions::from_json(r#"{"level": "minimal", "strip_blank_lines": 1, "keep_categories": ["todo"]}"#).unwrap();
+        let settings = options.settings();
+        assert!(settings.filter.keep_doc && settings.filter.keep_safety);
+        assert_eq!(settings.blank_line_limit, Some(BlankLineLimit(1)));
+        assert_eq!(settings.filter.keep_categories, vec![CommentCategory::Todo]);
+        assert!(ScrubOptions::from_json("").is_ok());
+        assert!(ScrubOptions::from_json(r#"{"levle": "minimal"}"#).is_err());
+
+        let options = ScrubOptions::from_json(r#"{"level": "aggressive", "lines": ["3:9", "20:"]}"#).unwrap();
+        let settings = options.settings();
+        assert_eq!(settings.filter.line_ranges.len(), 2);
+        assert!(settings.blank_line_limit.is_none() && !settings.strip_tests);
+        assert!(ScrubOptions::from_json(r#"{"lines": ["9:3"]}"#).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/ordered.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::Scope;
+
+struct State<T> {
+    claimed: usize,
+    consumed: usize,
+    total: Option<usize>,
+    ready: BTreeMap<usize, T>,
+    stopped: bool,
+}
+
+struct Shared<S, T> {
+    window: usize,
+    source: Mutex<S>,
+    state: Mutex<State<T>>,
+    changed: Condvar,
+}
+
+impl<S: Iterator, T> Shared<S, T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn wait<'a>(&self, guard: MutexGuard<'a, State<T>>) -> MutexGuard<'a, State<T>> {
+        self.changed.wait(guard).unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn claim(&self) -> Option<(usize, S::Item)> {
+        let mut source = self.source.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut state = self.lock();
+        while !state.stopped && state.total.is_none() && state.claimed >= state.consumed + self.window {
+            state = self.wait(state);
+        }
+        if state.stopped || state.total.is_some() {
+            return None;
+        }
+        let index = state.claimed;
+        drop(state);
+        let item = source.next();
+        let mut state = self.lock();
+        match item {
+            Some(item) => {
+                state.claimed += 1;
+                Some((index, item))
+            }
+            None => {
+                state.total = Some(index);
+                self.changed.notify_all();
+                None
+            }
+        }
+    }
+}
+
+pub struct Prefetch<S, T> {
+    shared: Arc<Shared<S, T>>,
+}
+
+impl<S, T> Prefetch<S, T>
+where
+    S: Iterator + Send,
+    T: Send,
+{
+    pub fn start<'scope, F>(scope: &'scope Scope<'scope, '_>, source: S, jobs: usize, work: F) -> Self
+    where
+        S: 'scope,
+        T: 'scope,
+        F: Fn(S::Item) -> T + Send + Sync + 'scope,
+    {
+        let jobs = jobs.max(1);
+        let shared = Arc::new(Shared {
+            window: jobs * 2,
+            source: Mutex::new(source),
+            state: Mutex::new(State {
+                claimed: 0,
+                consumed: 0,
+                total: None,
+                ready: BTreeMap::new(),
+                stopped: false,
+            }),
+            changed: Condvar::new(),
+        });
+        let work = Arc::new(work);
+        for _ in 0..jobs {
+            let shared = Arc::clone(&shared);
+            let work = Arc::clone(&work);
+            scope.spawn(move || {
+                while let Some((index, item)) = shared.claim() {
+                    let result = work(item);
+                    shared.lock().ready.insert(index, result);
+                    shared.changed.notify_all();
+                }
+            });
+        }
+        Prefetch { shared }
+    }
+}
+
+impl<S: Iterator, T> Iterator for Prefetch<S, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let mut state = self.shared.lock();
+        loop {
+            let index = state.consumed;
+            if let Some(result) = state.ready.remove(&index) {
+                state.consumed += 1;
+                self.shared.changed.notify_all();
+                return Some(result);
+            }
+            if state.total.is_some_and(|total| index >= total) {
+                return None;
+            }
+            state = self.shared.wait(state);
+        }
+    }
+}
+
+impl<S, T> Drop for Prefetch<S, T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.stopped = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+pub fn worker_count(jobs: usize) -> usize {
+    match jobs {
+        0 => std::thread::available_parallelism().map_or(1, |count| count.get()),
+        jobs => jobs,
+    }
+}
+
+pub fn map_ordered<T, F>(count: usize, jobs: usize, work: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Send + Sync,
+{
+    if jobs <= 1 {
+        return (0..count).map(work).collect();
+    }
+    std::thread::scope(|scope| Prefetch::start(scope, 0..count, jobs, work).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_results_arrive_in_input_order_within_the_window() {
+        let results = map_ordered(40, 4, |index| {
+            std::thread::sleep(Duration::from_millis(((40 - index) % 7) as u64));
+            index * 10
+        });
+        assert_eq!(results, (0..40).map(|index| index * 10).collect::<Vec<_>>());
+
+        let highest = Mutex::new(0);
+        let pulled = Mutex::new(0);
+        std::thread::scope(|scope| {
+            let source = (0..100).inspect(|_| *pulled.lock().unwrap() += 1);
+            let mut prefetch = Prefetch::start(scope, source, 2, |index| {
+                let mut highest = highest.lock().unwrap();
+                *highest = (*highest).max(index);
+                index
+            });
+            assert_eq!(prefetch.next(), Some(0));
+            std::thread::sleep(Duration::from_millis(50));
+            assert!(*highest.lock().unwrap() <= 4);
+            assert!(*pulled.lock().unwrap() <= 5);
+            assert_eq!(prefetch.nth(2), Some(3));
+            assert_eq!(prefetch.count(), 96);
+        });
+    }
+}
@@ -0,0 +1,472 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/pack.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rustscrub::inventory::Inventory;
+use rustscrub::options::ScrubArgs;
+use rustscrub::scrub::{scrub_stream, ChangeDetail, ChangeInfo, ScrubSettings};
+use rustscrub::spill::{parse_size, MemoryBudget, SpillBuffer};
+use rustscrub::summary::{print_summary, FileSummary};
+use rustscrub::tokens::{self, count_tokens, TokenModel};
+
+use crate::compress::{Compress, CompressedWriter};
+use crate::editorconfig::{EditorConfig, EditorConfigWriter, FinalNewline};
+use crate::generated::skip_generated;
+use crate::glob::Glob;
+use crate::ordered::{map_ordered, worker_count};
+use crate::walk::{collect_source_files, WalkArgs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PackOrder {
+    Given,
+    Path,
+    Size,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PackRank {
+    Size,
+    Path,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PackArgs {
+    #[clap(value_parser, required = true)]
+    paths: Vec<String>,
+
+    #[clap(short, long)]
+    output: Option<String>,
+
+    #[clap(long, value_enum)]
+    compress: Option<Compress>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    tree: bool,
+
+    #[clap(long, value_enum, default_value_t = PackOrder::Given)]
+    order: PackOrder,
+
+    #[clap(long, value_name = "TOKENS")]
+    budget: Option<usize>,
+
+    #[clap(long, value_name = "MODEL", default_value_t = TokenModel::default())]
+    model: TokenModel,
+
+    #[clap(long = "priority", value_name = "GLOB")]
+    priorities: Vec<String>,
+
+    #[clap(long, value_enum, default_value_t = PackRank::Size)]
+    rank: PackRank,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    truncate: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    summary: bool,
+
+    #[clap(long, value_name = "BYTES", value_parser = parse_size)]
+    max_memory: Option<usize>,
+
+    #[clap(short, long, value_name = "N", default_value_t = 0)]
+    jobs: usize,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    include_generated: bool,
+
+    #[clap(long, value_enum, value_name = "POLICY")]
+    final_newline: Option<FinalNewline>,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+
+    #[clap(flatten)]
+    scrub: ScrubArgs,
+}
+
+impl PackArgs {
+    pub fn check_features(&self) -> Result<(), String> {
+        if self.budget.is_some() {
+            tokens::check_available()?;
+        }
+        match self.compress.or_else(|| self.output.as_deref().and_then(Compress::from_path)) {
+            Some(compress) => compress.check_available(),
+            None => Ok(()),
+        }
+    }
+}
+
+struct PackedFile<'a> {
+    display_path: String,
+    original_size: usize,
+    scrubbed: SpillBuffer<'a>,
+    given_index: usize,
+    summary: FileSummary,
+    changes: Vec<ChangeInfo>,
+}
+
+#[derive(Default)]
+struct BudgetOutcome {
+    used: usize,
+    truncated: Vec<String>,
+    dropped: Vec<String>,
+}
+
+fn display_path(path: &Path) -> String {
+    path.strip_prefix(".")
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn scrub_file<'a>(
+    given_index: usize,
+    path: &PathBuf,
+    settings: &ScrubSettings,
+    budget: &'a MemoryBudget,
+) -> Result<PackedFile<'a>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read input file '{}': {}", path.display(), e))?;
+    let mut scrubbed = SpillBuffer::new(budget);
+    let report = scrub_stream(&mut content.as_bytes(), Some(&mut scrubbed), settings)?;
+    let display_path = display_path(path);
+    Ok(PackedFile {
+        summary: FileSummary {
+            path: display_path.clone(),
+            bytes: report.bytes,
+            lines: report.lines,
+            tokens: report.tokens,
+        },
+        changes: report.changes,
+        display_path,
+        original_size: content.len(),
+        scrubbed,
+        given_index,
+    })
+}
+
+fn render_tree<'p>(files: impl Iterator<Item = &'p PackedFile<'p>>) -> String {
+    let mut paths: Vec<&str> = files.map(|f| f.display_path.as_str()).collect();
+    paths.sort();
+
+    let mut tree = String::new();
+    let mut previous_dirs: Vec<&str> = Vec::new();
+    for path in paths {
+        let mut parts: Vec<&str> = path.split('/').collect();
+        let file_name = parts.pop().unwrap_or_default();
+        let shared = previous_dirs
+            .iter()
+            .zip(parts.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (depth, dir) in parts.iter().enumerate().skip(shared) {
+            tree.push_str(&format!("{}{}/\n", "  ".repeat(depth), dir));
+        }
+        tree.push_str(&format!("{}{}\n", "  ".repeat(parts.len()), file_name));
+        previous_dirs = parts;
+    }
+    tree
+}
+
+fn write_pack(writer: &mut dyn Write, files: &mut [PackedFile], with_tree: bool) -> io::Result<()> {
+    if with_tree {
+        writeln!(writer, "==== tree ====")?;
+        writer.write_all(render_tree(files.iter()).as_bytes())?;
+        writeln!(writer)?;
+    }
+    for file in files {
+        writer.write_all(delimiter(file).as_bytes())?;
+        file.scrubbed.copy_to(writer)?;
+        if file.scrubbed.last_byte().is_some_and(|byte| byte != b'\n') {
+            writeln!(writer)?;
+        }
+    }
+    Ok(())
+}
+
+fn delimiter(file: &PackedFile) -> String {
+    format!("==== {} ====\n", file.display_path)
+}
+
+fn truncate_to_budget(text: &str, budget: usize, model: TokenModel) -> Result<String, String> {
+    let mut kept = String::new();
+    let mut used = 0;
+    for line in text.split_inclusive('\n') {
+        let cost = count_tokens(line, model)?;
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        kept.push_str(line);
+    }
+    Ok(kept)
+}
+
+fn priority_of(file: &PackedFile, priorities: &[Glob]) -> usize {
+    priorities
+        .iter()
+        .position(|glob| glob.is_match(&file.display_path))
+        .unwrap_or(priorities.len())
+}
+
+fn apply_budget<'a>(
+    files: Vec<PackedFile<'a>>,
+    budget: usize,
+    args: &PackArgs,
+    memory: &'a MemoryBudget,
+) -> Result<(Vec<PackedFile<'a>>, BudgetOutcome), String> {
+    let priorities: Vec<Glob> = args.priorities.iter().map(|p| Glob::new(p)).collect();
+    let mut ranked = files;
+    ranked.sort_by(|a, b| {
+        let by_priority = priority_of(a, &priorities).cmp(&priority_of(b, &priorities));
+        let by_rank = match args.rank {
+            PackRank::Size => a.scrubbed.len().cmp(&b.scrubbed.len()),
+            PackRank::Path => a.display_path.cmp(&b.display_path),
+        };
+        by_priority.then(by_rank)
+    });
+
+    let mut costs = Vec::with_capacity(ranked.len());
+    for file in &mut ranked {
+        let header_cost = count_tokens(&delimiter(file), args.model)?;
+        costs.push((header_cost, header_cost + count_tokens(&file.scrubbed.text()?, args.model)?));
+    }
+
+    let mut reserved = tree_cost(ranked.iter(), args)?;
+    let mut fitting = None;
+    for _ in 0..=ranked.len() {
+        let (picks, used) = plan_budget(&mut ranked, &costs, budget.saturating_sub(reserved), args)?;
+        let picked = ranked.iter().zip(&picks).filter(|(_, pick)| !matches!(pick, Pick::Dropped));
+        let needed = tree_cost(picked.map(|(file, _)| file), args)?;
+        let stable = needed == reserved;
+        if needed <= reserved || fitting.is_none() {
+            fitting = Some((picks, used + needed));
+        }
+        if stable {
+            break;
+        }
+        reserved = needed;
+    }
+    let (picks, used) = fitting.unwrap_or_default();
+
+    let mut outcome = BudgetOutcome { used, ..BudgetOutcome::default() };
+    let mut selected = Vec::new();
+    for (mut file, pick) in ranked.into_iter().zip(picks) {
+        match pick {
+            Pick::Whole => selected.push(file),
+            Pick::Truncated(truncated) => {
+                file.scrubbed = SpillBuffer::new(memory);
+                file.scrubbed
+                    .write_all(truncated.as_bytes())
+                    .map_err(|e| format!("Failed to buffer truncated file '{}': {}", file.display_path, e))?;
+                outcome.truncated.push(file.display_path.clone());
+                selected.push(file);
+            }
+            Pick::Dropped => outcome.dropped.push(file.display_path),
+        }
+    }
+    Ok((selected, outcome))
+}
+
+enum Pick {
+    Whole,
+    Truncated(String),
+    Dropped,
+}
+
+fn tree_cost<'p>(files: impl Iterator<Item = &'p PackedFile<'p>>, args: &PackArgs) -> Result<usize, String> {
+    if args.tree {
+        Ok(count_tokens(&render_tree(files), args.model)? + 2)
+    } else {
+        Ok(0)
+    }
+}
+
+fn plan_budget(
+    ranked: &mut [PackedFile],
+    costs: &[(usize, usize)],
+    budget: usize,
+    args: &PackArgs,
+) -> Result<(Vec<Pick>, usize), String> {
+    let mut picks = Vec::with_capacity(ranked.len());
+    let mut used = 0;
+    let mut truncated_one = false;
+    for (file, &(header_cost, cost)) in ranked.iter_mut().zip(costs) {
+        if used + cost <= budget {
+            used += cost;
+            picks.push(Pick::Whole);
+        } else if args.truncate && !truncated_one && used + header_cost < budget {
+            let truncated = truncate_to_budget(&file.scrubbed.text()?, budget - used - header_cost, args.model)?;
+            used += header_cost + count_tokens(&truncated, args.model)?;
+            truncated_one = true;
+            picks.push(Pick::Truncated(truncated));
+        } else {
+            picks.push(Pick::Dropped);
+        }
+    }
+    Ok((picks, used))
+}
+
+fn report_budget(budget: usize, included: usize, outcome: &BudgetOutcome, model: TokenModel) {
+    eprintln!(
+        "RustScrub: Token budget {} ({}), used {}. Included {} files.",
+        budget, model, outcome.used, included
+    );
+    for path in &outcome.truncated {
+        eprintln!("- Truncated: {}", path);
+    }
+    for path in &outcome.dropped {
+        eprintln!("- Dropped: {}", path);
+    }
+}
+
+pub fn run(args: &PackArgs) -> Result<(), String> {
+    let mut paths = collect_source_files(&args.paths, &args.walk)?;
+    if !args.include_generated {
+        let (kept, generated) = skip_generated(paths, &args.paths)?;
+        if !generated.is_empty() {
+            eprintln!("RustScrub: Skipped {} generated files; use --include-generated to pack them", generated.len());
+        }
+        paths = kept;
+    }
+    let settings = ScrubSettings {
+        token_model: (args.summary && cfg!(feature = "tokenizer")).then_some(args.model),
+        change_detail: if args.scrub.inventory.is_some() { ChangeDetail::Full } else { ChangeDetail::Lines },
+        ..args.scrub.settings()
+    };
+    let memory = MemoryBudget::new(args.max_memory);
+    let mut files = map_ordered(paths.len(), worker_count(args.jobs), |index| scrub_file(index, &paths[index], &settings, &memory))
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    if memory.exceeded() {
+        eprintln!("RustScrub: Pack output exceeded --max-memory; spilled scrubbed files to temporary storage.");
+    }
+
+    if let Some(budget) = args.budget {
+        let (selected, outcome) = apply_budget(files, budget, args, &memory)?;
+        report_budget(budget, selected.len(), &outcome, args.model);
+        files = selected;
+    }
+
+    match args.order {
+        PackOrder::Given => files.sort_by_key(|f| f.given_index),
+        PackOrder::Path => files.sort_by(|a, b| a.display_path.cmp(&b.display_path)),
+        PackOrder::Size => files.sort_by_key(|f| f.original_size),
+    }
+
+    let (sink, compress): (Box<dyn Write>, _) = if let Some(output_path_str) = &args.output {
+        let output_file = File::create(output_path_str)
+            .map_err(|e| format!("Failed to create output file '{}': {}", output_path_str, e))?;
+        (Box::new(BufWriter::new(output_file)), args.compress.or_else(|| Compress::from_path(output_path_str)))
+    } else {
+        (Box::new(BufWriter::new(io::stdout().lock())), args.compress)
+    };
+    let config = EditorConfig::default().with_final_newline(args.final_newline);
+    let mut writer = EditorConfigWriter::new(CompressedWriter::new(sink, compress)?, config);
+    write_pack(&mut writer, &mut files, args.tree)
+        .and_then(|_| writer.finish()?.finish().map(drop))
+        .map_err(|e| format!("Failed to write pack output: {}", e))?;
+
+    if let Some(output_path_str) = &args.output {
+        eprintln!("RustScrub: Packed {} files into {}", files.len(), output_path_str);
+    }
+    if let Some(inventory_path) = &args.scrub.inventory {
+        let mut inventory = Inventory::default();
+        for file in &files {
+            inventory.add_file(&file.display_path, &file.changes);
+        }
+        fs::write(inventory_path, inventory.to_json()?)
+            .map_err(|e| format!("Failed to write inventory file '{}': {}", inventory_path, e))?;
+    }
+    if args.summary {
+        let summaries: Vec<FileSummary> = files.iter().map(|f| f.summary.clone()).collect();
+        print_summary(&summaries, settings.token_model);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_writes_tree_and_delimited_scrubbed_files() {
+        let root = std::env::temp_dir().join(format!("rustscrub-pack-{}", std::process::id()));
+        fs::create_dir_all(root.join("util")).unwrap();
+        fs::write(root.join("lib.rs"), "// crate docs\nmod util;\n").unwrap();
+        fs::write(root.join("util/mod.rs"), "pub fn f() {} /* inline */").unwrap();
+        let memory = MemoryBudget::new(None);
+        let settings = ScrubSettings::default();
+        let mut files: Vec<PackedFile> = ["lib.rs", "util/mod.rs"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let mut file = scrub_file(index, &root.join(name), &settings, &memory).unwrap();
+                file.display_path = name.to_string();
+                file
+            })
+            .collect();
+        fs::remove_dir_all(&root).unwrap();
+
+        let mut output = Vec::new();
+        write_pack(&mut output, &mut files, true).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "==== tree ====\nlib.rs\nutil/\n  mod.rs\n\n==== lib.rs ====\nmod util;\n==== util/mod.rs ====\npub fn f() {} \n"
+        );
+        assert_eq!(files[0].changes.len(), 1);
+        assert_eq!(files[1].original_size, 26);
+    }
+
+    #[cfg(feature = "tokenizer")]
+    #[test]
+    fn test_budget_counts_the_tree_of_the_selected_files_only() {
+        #[derive(clap::Parser)]
+        struct Cli {
+            #[clap(flatten)]
+            pack: PackArgs,
+        }
+        let args = <Cli as clap::Parser>::parse_from(["pack", ".", "--tree", "--budget", "0"]).pack;
+        let memory = MemoryBudget::new(None);
+        let file = |path: &str, text: &str| {
+            let mut scrubbed = SpillBuffer::new(&memory);
+            scrubbed.write_all(text.as_bytes()).unwrap();
+            PackedFile {
+                display_path: path.to_string(),
+                original_size: text.len(),
+                scrubbed,
+                given_index: 0,
+                summary: FileSummary::default(),
+                changes: Vec::new(),
+            }
+        };
+        let long_text = "pub const X: u8 = 0;\n".repeat(50);
+        let sources = [
+            ("a.rs", "fn a() {}\n"),
+            ("b.rs", "fn b() { let x = 1; }\n"),
+            ("generated/protocol/buffers/descriptors/schema_definitions.rs", long_text.as_str()),
+        ];
+        let files = || sources.iter().map(|(path, text)| file(path, text)).collect::<Vec<_>>();
+        let cost = |index: usize| {
+            let (path, text) = sources[index];
+            count_tokens(&format!("==== {} ====\n{}", path, text), args.model).unwrap()
+        };
+        let all = files();
+        let trimmed_tree = tree_cost(all[..2].iter(), &args).unwrap();
+        let budget = trimmed_tree + cost(0) + cost(1);
+        assert!(tree_cost(all.iter(), &args).unwrap() > trimmed_tree);
+        assert!(budget < trimmed_tree + cost(0) + cost(2));
+
+        let (selected, outcome) = apply_budget(files(), budget, &args, &memory).unwrap();
+        let paths: Vec<&str> = selected.iter().map(|f| f.display_path.as_str()).collect();
+        assert_eq!(paths, ["a.rs", "b.rs"]);
+        assert_eq!(outcome.dropped, [sources[2].0]);
+        assert_eq!(outcome.used, budget);
+    }
+}
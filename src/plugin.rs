@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/plugin.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::classify::{classify, CommentCategory};
+use crate::filter::{Comment, DocStyle};
+use crate::scrub::VerboseCommentType;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginComment<'a> {
+    pub text: &'a str,
+    pub kind: &'static str,
+    pub doc: Option<&'static str>,
+    pub category: CommentCategory,
+    pub full_line: bool,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub start_column: usize,
+}
+
+impl<'a> PluginComment<'a> {
+    pub fn new(comment: &Comment<'a>, start_line: usize, end_line: usize, start_column: usize) -> Self {
+        PluginComment {
+            text: comment.text,
+            kind: match comment.comment_type {
+                VerboseCommentType::Line => "line",
+                VerboseCommentType::Block => "block",
+            },
+            doc: comment.doc_style().map(|style| match style {
+                DocStyle::Outer => "outer",
+                DocStyle::Inner => "inner",
+            }),
+            category: classify(comment),
+            full_line: comment.full_line,
+            start_line,
+            end_line,
+            start_column,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "decision", rename_all = "lowercase")]
+pub enum Decision {
+    Default,
+    Keep,
+    Remove,
+    Replace { text: String },
+}
+
+pub trait CommentPlugin: fmt::Debug + Send + Sync {
+    fn decide(&self, comment: &PluginComment) -> Decision;
+
+    fn take_error(&self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(feature = "plugins")]
+mod wasm {
+    use std::fmt;
+    use std::fs;
+    use std::sync::Mutex;
+
+    use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+    use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+    use wasmtime_wasi::WasiCtxBuilder;
+
+    use super::{CommentPlugin, Decision, PluginComment};
+
+    struct WasmState {
+        store: Store<WasiP1Ctx>,
+        memory: Memory,
+        alloc: TypedFunc<i32, i32>,
+        decide: TypedFunc<(i32, i32), i64>,
+    }
+
+    pub struct WasmPlugin {
+        name: String,
+        state: Mutex<WasmState>,
+        error: Mutex<Option<String>>,
+    }
+
+    impl fmt::Debug for WasmPlugin {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("WasmPlugin").field("name", &self.name).finish()
+        }
+    }
+
+    fn export<T>(name: &str, export: &str, found: Option<T>) -> Result<T, String> {
+        found.ok_or_else(|| format!("Plugin '{}' does not export '{}'.", name, export))
+    }
+
+    impl WasmPlugin {
+        pub fn load(path: &str) -> Result<Self, String> {
+            let bytes = fs::read(path).map_err(|e| format!("Failed to read plugin '{}': {}", path, e))?;
+            WasmPlugin::from_bytes(path, &bytes)
+        }
+
+        pub fn from_bytes(name: &str, bytes: &[u8]) -> Result<Self, String> {
+            let failed = |e: wasmtime::Error| format!("Failed to load plugin '{}': {:#}", name, e);
+            let engine = Engine::default();
+            let module = Module::new(&engine, bytes).map_err(failed)?;
+            let mut linker = wasmtime::Linker::new(&engine);
+            preview1::add_to_linker_sync(&mut linker, |ctx: &mut WasiP1Ctx| ctx).map_err(failed)?;
+            let mut store = Store::new(&engine, WasiCtxBuilder::new().inherit_stderr().build_p1());
+            let instance: Instance = linker.instantiate(&mut store, &module).map_err(failed)?;
+            if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+                initialize.call(&mut store, ()).map_err(failed)?;
+            }
+            let memory = export(name, "memory", instance.get_memory(&mut store, "memory"))?;
+            let alloc = export(name, "rustscrub_alloc", instance.get_typed_func(&mut store, "rustscrub_alloc").ok())?;
+            let decide = export(name, "rustscrub_decide", instance.get_typed_func(&mut store, "rustscrub_decide").ok())?;
+            Ok(WasmPlugin {
+                name: name.to_string(),
+                state: Mutex::new(WasmState { store, memory, alloc, decide }),
+                error: Mutex::new(None),
+            })
+        }
+
+        fn call(&self, input: &[u8]) -> Result<Decision, String> {
+            let failed = |e: wasmtime::Error| format!("Plugin '{}' failed: {:#}", self.name, e);
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let WasmState { store, memory, alloc, decide } = &mut *state;
+            let len = i32::try_from(input.len()).map_err(|_| format!("Comment too large for plugin '{}'.", self.name))?;
+            let ptr = alloc.call(&mut *store, len).map_err(failed)?;
+            memory
+                .write(&mut *store, ptr as u32 as usize, input)
+                .map_err(|e| format!("Plugin '{}' returned an invalid buffer: {}", self.name, e))?;
+            let packed = decide.call(&mut *store, (ptr, len)).map_err(failed)? as u64;
+            let mut output = vec![0; (packed & 0xffff_ffff) as usize];
+            memory
+                .read(&*store, (packed >> 32) as usize, &mut output)
+                .map_err(|e| format!("Plugin '{}' returned an invalid decision buffer: {}", self.name, e))?;
+            serde_json::from_slice(&output).map_err(|e| format!("Plugin '{}' returned an invalid decision: {}", self.name, e))
+        }
+    }
+
+    impl CommentPlugin for WasmPlugin {
+        fn decide(&self, comment: &PluginComment) -> Decision {
+            let decision = serde_json::to_vec(comment)
+                .map_err(|e| format!("Failed to serialize comment for plugin '{}': {}", self.name, e))
+                .and_then(|input| self.call(&input));
+            decision.unwrap_or_else(|e| {
+                self.error.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(e);
+                Decision::Default
+            })
+        }
+
+        fn take_error(&self) -> Option<String> {
+            self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+        }
+    }
+}
+
+#[cfg(feature = "plugins")]
+pub use wasm::WasmPlugin;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decisions_parse_from_json() {
+        let parse = |json: &str| serde_json::from_str::<Decision>(json).unwrap();
+        assert_eq!(parse(r#"{"decision":"keep"}"#), Decision::Keep);
+        assert_eq!(parse(r#"{"decision":"replace","text":"// ok"}"#), Decision::Replace { text: "// ok".to_string() });
+        assert!(serde_json::from_str::<Decision>(r#"{"decision":"maybe"}"#).is_err());
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_wasm_plugin_decides() {
+        let decision = r#"{"decision":"replace","text":"// [internal]"}"#;
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 1024))
+                (data (i32.const 0) "{}")
+                (func (export "rustscrub_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "rustscrub_decide") (param i32 i32) (result i64)
+                    (i64.const {})))"#,
+            decision.replace('"', "\\\""),
+            decision.len()
+        );
+        let plugin = WasmPlugin::from_bytes("test.wat", wat.as_bytes()).unwrap();
+        let comment = Comment {
+            text: "// Falcon launch plan",
+            comment_type: VerboseCommentType::Line,
+            full_line: true,
+            continues: None,
+        };
+        let decision = plugin.decide(&PluginComment::new(&comment, 3, 3, 1));
+        assert_eq!(decision, Decision::Replace { text: "// [internal]".to_string() });
+        assert!(plugin.take_error().is_none());
+        assert!(WasmPlugin::from_bytes("empty.wat", b"(module)").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/plugins.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use rustscrub::plugin::{CommentPlugin, Decision, PluginComment};
+
+use crate::config::RulesConfig;
+
+struct FilterProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+pub struct FilterCommand {
+    command: String,
+    process: Mutex<Option<FilterProcess>>,
+    error: Mutex<Option<String>>,
+}
+
+impl fmt::Debug for FilterCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilterCommand").field("command", &self.command).finish()
+    }
+}
+
+fn shell(command: &str) -> Command {
+    let (program, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut shell = Command::new(program);
+    shell.arg(flag).arg(command);
+    shell
+}
+
+impl FilterCommand {
+    pub fn spawn(command: &str) -> Result<Self, String> {
+        let mut child = shell(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("Failed to start filter command '{}': {}", command, e))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(format!("Failed to open pipes to filter command '{}'.", command));
+        };
+        Ok(FilterCommand {
+            command: command.to_string(),
+            process: Mutex::new(Some(FilterProcess { child, stdin, stdout: BufReader::new(stdout) })),
+            error: Mutex::new(None),
+        })
+    }
+
+    fn call(&self, process: &mut FilterProcess, comment: &PluginComment) -> Result<Decision, String> {
+        let mut request = serde_json::to_string(comment).map_err(|e| format!("Failed to serialize comment for filter command '{}': {}", self.command, e))?;
+        request.push('\n');
+        process
+            .stdin
+            .write_all(request.as_bytes())
+            .and_then(|_| process.stdin.flush())
+            .map_err(|e| format!("Failed to write to filter command '{}': {}", self.command, e))?;
+        let mut response = String::new();
+        let read = process
+            .stdout
+            .read_line(&mut response)
+            .map_err(|e| format!("Failed to read from filter command '{}': {}", self.command, e))?;
+        if read == 0 {
+            return Err(format!("Filter command '{}' exited before answering.", self.command));
+        }
+        serde_json::from_str(&response).map_err(|e| format!("Filter command '{}' returned an invalid decision: {}", self.command, e))
+    }
+}
+
+impl CommentPlugin for FilterCommand {
+    fn decide(&self, comment: &PluginComment) -> Decision {
+        let mut guard = self.process.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(process) = guard.as_mut() else {
+            return Decision::Default;
+        };
+        self.call(process, comment).unwrap_or_else(|e| {
+            if let Some(mut process) = guard.take() {
+                let _ = process.child.kill();
+                let _ = process.child.wait();
+            }
+            self.error.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(e);
+            Decision::Default
+        })
+    }
+
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl Drop for FilterCommand {
+    fn drop(&mut self) {
+        if let Some(FilterProcess { mut child, stdin, .. }) = self.process.get_mut().unwrap_or_else(|e| e.into_inner()).take() {
+            drop(stdin);
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(feature = "plugins")]
+fn load_plugin(path: &str) -> Result<Arc<dyn CommentPlugin>, String> {
+    Ok(Arc::new(rustscrub::plugin::WasmPlugin::load(path)?))
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugin(_path: &str) -> Result<Arc<dyn CommentPlugin>, String> {
+    Err("--plugin requires building rustscrub with the 'plugins' feature.".to_string())
+}
+
+#[cfg(feature = "rules")]
+fn load_rules(rules: &RulesConfig) -> Result<Arc<dyn CommentPlugin>, String> {
+    Ok(Arc::new(rustscrub::rules::Rules::compile(&rules.keep, &rules.remove)?))
+}
+
+#[cfg(not(feature = "rules"))]
+fn load_rules(_rules: &RulesConfig) -> Result<Arc<dyn CommentPlugin>, String> {
+    Err("[rules] in the config requires building rustscrub with the 'rules' feature.".to_string())
+}
+
+pub fn check_available(paths: &[String], rules: &RulesConfig) -> Result<(), String> {
+    if !paths.is_empty() && !cfg!(feature = "plugins") {
+        return Err("--plugin requires building rustscrub with the 'plugins' feature.".to_string());
+    }
+    if !rules.is_empty() && !cfg!(feature = "rules") {
+        return Err("[rules] in the config requires building rustscrub with the 'rules' feature.".to_string());
+    }
+    Ok(())
+}
+
+pub fn load_plugins(paths: &[String], filter_cmd: Option<&str>, rules: &RulesConfig) -> Result<Vec<Arc<dyn CommentPlugin>>, String> {
+    let mut plugins: Vec<Arc<dyn CommentPlugin>> = paths.iter().map(|path| load_plugin(path)).collect::<Result<_, _>>()?;
+    if let Some(command) = filter_cmd {
+        plugins.push(Arc::new(FilterCommand::spawn(command)?));
+    }
+    if !rules.is_empty() {
+        plugins.push(load_rules(rules)?);
+    }
+    Ok(plugins)
+}
+
+pub fn plugin_error(plugins: &[Arc<dyn CommentPlugin>]) -> Option<String> {
+    plugins.iter().find_map(|plugin| plugin.take_error())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    #[cfg(unix)]
+    #[test]
+    fn test_filter_command_decides_per_comment() {
+        let script = r#"while read -r line; do case "$line" in *secret*) echo '{"decision":"replace","text":"// [redacted]"}';; *SAFETY*) echo '{"decision":"keep"}';; *) echo '{"decision":"default"}';; esac; done"#;
+        let mut settings = ScrubSettings::default();
+        settings.filter.plugins = load_plugins(&[], Some(script), &RulesConfig::default()).unwrap();
+        let input = "// SAFETY: checked\nlet x = 1; // secret key\n// gone\n";
+        let (output, _) = scrub_text(input, &settings).unwrap();
+        assert_eq!(output, "// SAFETY: checked\nlet x = 1; // [redacted]\n");
+        assert!(plugin_error(&settings.filter.plugins).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_filter_command_failure_is_reported() {
+        let plugins = load_plugins(&[], Some("read -r line; echo nonsense"), &RulesConfig::default()).unwrap();
+        let mut settings = ScrubSettings::default();
+        settings.filter.plugins = plugins;
+        let (output, _) = scrub_text("// a\n// b\n", &settings).unwrap();
+        assert_eq!(output, "");
+        assert!(plugin_error(&settings.filter.plugins).unwrap().contains("invalid decision"));
+    }
+}
@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/policy.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rustscrub::classify::{classify, CommentCategory};
+use rustscrub::filter::{CommentFilter, DocStyle};
+use rustscrub::fingerprint::Fingerprinter;
+use rustscrub::schema::days_from_civil;
+use rustscrub::scrub::{scrub_lines, ScrubSettings, ScrubbedLine};
+
+use crate::baseline::{Baseline, Finding};
+use crate::config::{PolicyConfig, Severity};
+use crate::glob::Glob;
+use crate::status::Failure;
+use crate::suppressions::Suppressions;
+
+pub const DOC_REQUIRED_ON_PUB: &str = "doc-required-on-pub";
+pub const NO_COMMENTED_OUT_CODE: &str = "no-commented-out-code";
+pub const TODO_MAX_AGE: &str = "todo-max-age";
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+const ITEM_KEYWORDS: [&str; 9] = ["fn", "struct", "enum", "trait", "const", "static", "type", "union", "mod"];
+
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub line: usize,
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+fn is_pub_item(code: &str) -> bool {
+    let code = code.trim();
+    let Some(rest) = code.strip_prefix("pub ") else {
+        return false;
+    };
+    let keyword = rest
+        .split_whitespace()
+        .find(|token| !matches!(*token, "async" | "unsafe" | "extern") && !token.starts_with('"'));
+    match keyword {
+        Some("mod") => !code.ends_with(';'),
+        Some(keyword) => ITEM_KEYWORDS.contains(&keyword),
+        None => false,
+    }
+}
+
+pub fn embedded_date(text: &str) -> Option<i64> {
+    let bytes = text.as_bytes();
+    (0..bytes.len().saturating_sub(9)).find_map(|start| {
+        let candidate = text.get(start..start + 10)?;
+        let parts: Vec<&str> = candidate.split('-').collect();
+        let well_formed = parts.len() == 3
+            && [4, 2, 2].iter().zip(&parts).all(|(len, part)| part.len() == *len && part.bytes().all(|b| b.is_ascii_digit()));
+        if !well_formed {
+            return None;
+        }
+        let (year, month, day) = (parts[0].parse().ok()?, parts[1].parse().ok()?, parts[2].parse().ok()?);
+        ((1..=12).contains(&month) && (1..=31).contains(&day)).then(|| days_from_civil(year, month, day))
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    pub author: String,
+    pub day: i64,
+}
+
+pub fn blame_lines(path: &Path) -> Option<HashMap<usize, BlameLine>> {
+    crate::clone::check_available().ok()?;
+    let file_name = path.file_name()?;
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .arg("blame")
+        .arg("--line-porcelain")
+        .arg("--")
+        .arg(file_name)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_blame(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_blame(porcelain: &str) -> HashMap<usize, BlameLine> {
+    let mut lines = HashMap::new();
+    let mut current_line = 0;
+    let mut author = String::new();
+    for line in porcelain.lines() {
+        let mut fields = line.split(' ');
+        let first = fields.next().unwrap_or_default();
+        if first.len() == 40 && first.bytes().all(|b| b.is_ascii_hexdigit()) {
+            current_line = fields.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+        } else if let Some(name) = line.strip_prefix("author ") {
+            author = name.to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            if let Ok(seconds) = time.parse::<i64>() {
+                lines.insert(
+                    current_line,
+                    BlameLine {
+                        author: author.clone(),
+                        day: seconds.div_euclid(SECONDS_PER_DAY),
+                    },
+                );
+            }
+        }
+    }
+    lines
+}
+
+pub fn blame_days(path: &Path) -> Option<HashMap<usize, i64>> {
+    blame_lines(path).map(|lines| lines.into_iter().map(|(line, blame)| (line, blame.day)).collect())
+}
+
+pub fn today() -> i64 {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+    seconds.div_euclid(SECONDS_PER_DAY)
+}
+
+fn is_allowed_in_source(lines: &[&str], line: usize, rule: &str) -> bool {
+    let marker = format!("rustscrub-allow({})", rule);
+    [line, line.saturating_sub(1)]
+        .iter()
+        .filter(|&&number| number >= 1)
+        .any(|&number| lines.get(number - 1).is_some_and(|text| text.contains(&marker)))
+}
+
+fn check_docs(scrubbed: &[ScrubbedLine], documented: &HashSet<usize>, severity: Severity, violations: &mut Vec<Violation>) {
+    for (index, line) in scrubbed.iter().enumerate() {
+        if !is_pub_item(&line.text) {
+            continue;
+        }
+        let mut previous = index;
+        while previous > 0 && scrubbed[previous - 1].text.trim_start().starts_with("#[") {
+            previous -= 1;
+        }
+        if previous == 0 || !documented.contains(&scrubbed[previous - 1].line_number) {
+            violations.push(Violation {
+                line: line.line_number,
+                rule: DOC_REQUIRED_ON_PUB,
+                severity,
+                message: "public item has no doc comment".to_string(),
+            });
+        }
+    }
+}
+
+pub fn evaluate_source(path: &Path, content: &str, policy: &PolicyConfig) -> Vec<Violation> {
+    let settings = ScrubSettings {
+        filter: CommentFilter {
+            keep_directives: false,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    };
+    let scrubbed: Vec<ScrubbedLine> = scrub_lines(content.lines(), &settings).collect();
+    let changes: Vec<_> = scrubbed.iter().flat_map(|line| &line.changes).collect();
+    let mut violations = Vec::new();
+
+    if policy.doc_required_on_pub != Severity::Off {
+        let documented: HashSet<usize> = changes
+            .iter()
+            .filter(|change| change.as_comment().doc_style() == Some(DocStyle::Outer))
+            .map(|change| change.end_line)
+            .collect();
+        check_docs(&scrubbed, &documented, policy.doc_required_on_pub, &mut violations);
+    }
+
+    if policy.no_commented_out_code != Severity::Off {
+        for change in changes.iter().filter(|change| classify(&change.as_comment()) == CommentCategory::Code) {
+            violations.push(Violation {
+                line: change.start_line,
+                rule: NO_COMMENTED_OUT_CODE,
+                severity: policy.no_commented_out_code,
+                message: "commented-out code".to_string(),
+            });
+        }
+    }
+
+    if let Some(rule) = policy.todo_max_age.as_ref().filter(|rule| rule.severity != Severity::Off) {
+        let todos: Vec<_> = changes
+            .iter()
+            .filter(|change| classify(&change.as_comment()) == CommentCategory::Todo)
+            .collect();
+        let blame = if todos.iter().any(|change| embedded_date(&change.text).is_none()) {
+            blame_days(path)
+        } else {
+            None
+        };
+        let today = today();
+        for change in todos {
+            let written = embedded_date(&change.text)
+                .or_else(|| blame.as_ref().and_then(|days| days.get(&change.start_line).copied()));
+            if let Some(age) = written.map(|day| today - day).filter(|age| *age > rule.days as i64) {
+                violations.push(Violation {
+                    line: change.start_line,
+                    rule: TODO_MAX_AGE,
+                    severity: rule.severity,
+                    message: format!("TODO is {} days old (limit {})", age, rule.days),
+                });
+            }
+        }
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    violations.retain(|violation| !is_allowed_in_source(&lines, violation.line, violation.rule));
+    violations.sort_by_key(|violation| violation.line);
+    violations
+}
+
+fn is_suppressed(policy: &PolicyConfig, path: &str, rule: &str, fingerprint: &str) -> bool {
+    policy.suppress.iter().any(|suppression| {
+        suppression.rule.as_deref().is_none_or(|suppressed| suppressed == rule)
+            && (suppression.paths.iter().any(|pattern| Glob::new(pattern).is_match(path))
+                || suppression.fingerprints.iter().any(|suppressed| suppressed == fingerprint))
+    })
+}
+
+pub fn run(paths: &[String], policy: &PolicyConfig, mut baseline: Option<&mut Baseline>, suppressions: &mut Suppressions) -> Result<(), Failure> {
+    let mut errors = 0;
+    let mut warnings = 0;
+    for path in paths {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read input file '{}': {}", path, e))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let mut fingerprinters: HashMap<&str, Fingerprinter> = HashMap::new();
+        for violation in evaluate_source(Path::new(path), &content, policy) {
+            let source = lines.get(violation.line.wrapping_sub(1)).copied().unwrap_or_default();
+            let fingerprint = fingerprinters.entry(violation.rule).or_default().next(source);
+            if is_suppressed(policy, path, violation.rule, &fingerprint) || suppressions.is_suppressed(path, violation.rule, source, &fingerprint) {
+                continue;
+            }
+            if let Some(baseline) = baseline.as_deref_mut() {
+                if !baseline.is_new(Finding::new(path, violation.rule, source, fingerprint.clone())) {
+                    continue;
+                }
+            }
+            let label = match violation.severity {
+                Severity::Error => {
+                    errors += 1;
+                    "error"
+                }
+                Severity::Warning => {
+                    warnings += 1;
+                    "warning"
+                }
+                Severity::Off => continue,
+            };
+            eprintln!("{}:{}: {}[{}]: {} [{}]", path, violation.line, label, violation.rule, violation.message, fingerprint);
+        }
+    }
+    eprintln!("RustScrub Policy: {} errors, {} warnings in {} files", errors, warnings, paths.len());
+    if errors > 0 {
+        Err(Failure::changes(format!("Policy check failed with {} errors", errors)))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TodoAgeRule;
+
+    fn policy() -> PolicyConfig {
+        PolicyConfig {
+            doc_required_on_pub: Severity::Error,
+            no_commented_out_code: Severity::Warning,
+            todo_max_age: Some(TodoAgeRule { days: 180, severity: Severity::Warning }),
+            suppress: Vec::new(),
+        }
+    }
+
+    fn rules(content: &str) -> Vec<(usize, &'static str)> {
+        evaluate_source(Path::new("missing.rs"), content, &policy())
+            .into_iter()
+            .map(|violation| (violation.line, violation.rule))
+            .collect()
+    }
+
+    #[test]
+    fn test_doc_required_on_pub_items() {
+        let content = "/// Documented.\n#[inline]\npub fn a() {}\npub struct B;\npub(crate) fn c() {}\npub mod d;\n";
+        assert_eq!(rules(content), vec![(4, DOC_REQUIRED_ON_PUB)]);
+    }
+
+    #[test]
+    fn test_commented_out_code_and_old_todos() {
+        let content = "// let x = compute();\n// TODO(2001-01-01): remove\n// TODO(2999-01-01): later\nfn f() {} // rustscrub-allow(no-commented-out-code) let y = 1;\n";
+        assert_eq!(rules(content), vec![(1, NO_COMMENTED_OUT_CODE), (2, TODO_MAX_AGE)]);
+    }
+
+    #[test]
+    fn test_parse_blame_porcelain() {
+        let porcelain = "0123456789abcdef0123456789abcdef01234567 1 1 1\nauthor Ada Lovelace\nauthor-mail <ada@example.com>\nauthor-time 86400\n\t// note\n\
+                         89abcdef0123456789abcdef0123456789abcdef 2 2 1\nauthor Grace Hopper\nauthor-time 172800\n\tfn f() {}\n";
+        let lines = parse_blame(porcelain);
+        assert_eq!(lines[&1], BlameLine { author: "Ada Lovelace".to_string(), day: 1 });
+        assert_eq!(lines[&2], BlameLine { author: "Grace Hopper".to_string(), day: 2 });
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(embedded_date("TODO 2000-03-01 fix"), Some(11_017));
+    }
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/progress.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use rustscrub::schema::SCHEMA_VERSION;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProgressFormat {
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct ProgressRecord<'a> {
+    schema_version: u32,
+    files_done: usize,
+    files_total: usize,
+    bytes_processed: usize,
+    current_file: Option<&'a str>,
+}
+
+pub struct Progress {
+    writer: Box<dyn Write>,
+    interval: Duration,
+    last: Option<Instant>,
+    files_total: usize,
+    files_done: usize,
+    bytes_processed: usize,
+}
+
+impl Progress {
+    pub fn new(fd: Option<u32>, files_total: usize) -> Result<Self, String> {
+        let writer: Box<dyn Write> = match fd {
+            Some(fd) => {
+                let path = format!("/dev/fd/{}", fd);
+                Box::new(
+                    OpenOptions::new()
+                        .append(true)
+                        .open(&path)
+                        .map_err(|e| format!("Failed to open progress file descriptor {}: {}", fd, e))?,
+                )
+            }
+            None => Box::new(io::stderr()),
+        };
+        Ok(Progress {
+            writer,
+            interval: PROGRESS_INTERVAL,
+            last: None,
+            files_total,
+            files_done: 0,
+            bytes_processed: 0,
+        })
+    }
+
+    fn write(&mut self, current_file: Option<&str>) -> Result<(), String> {
+        let record = ProgressRecord {
+            schema_version: SCHEMA_VERSION,
+            files_done: self.files_done,
+            files_total: self.files_total,
+            bytes_processed: self.bytes_processed,
+            current_file,
+        };
+        self.last = Some(Instant::now());
+        serde_json::to_writer(&mut self.writer, &record)
+            .map_err(|e| e.to_string())
+            .and_then(|_| writeln!(self.writer).and_then(|_| self.writer.flush()).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to write progress: {}", e))
+    }
+
+    pub fn start_file(&mut self, path: &str) -> Result<(), String> {
+        if self.last.is_some_and(|last| last.elapsed() < self.interval) {
+            return Ok(());
+        }
+        self.write(Some(path))
+    }
+
+    pub fn finish_file(&mut self, bytes: usize) {
+        self.files_done += 1;
+        self.bytes_processed += bytes;
+    }
+
+    pub fn finish(mut self) -> Result<(), String> {
+        self.write(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_progress_records_are_throttled() {
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        let mut progress = Progress {
+            writer: Box::new(Shared(buffer.clone())),
+            interval: Duration::from_secs(3600),
+            last: None,
+            files_total: 2,
+            files_done: 0,
+            bytes_processed: 0,
+        };
+        progress.start_file("a.rs").unwrap();
+        progress.finish_file(10);
+        progress.start_file("b.rs").unwrap();
+        progress.finish_file(5);
+        progress.finish().unwrap();
+
+        let output = String::from_utf8(buffer.borrow().clone()).unwrap();
+        let records: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["current_file"], "a.rs");
+        assert_eq!(records[0]["files_done"], 0);
+        assert_eq!(records[1]["current_file"], Value::Null);
+        assert_eq!((records[1]["files_done"].clone(), records[1]["bytes_processed"].clone()), (2.into(), 15.into()));
+        assert_eq!(records[1]["files_total"], 2);
+    }
+}
@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/python.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+
+use clap::ValueEnum;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::classify::CommentCategory;
+use crate::filter::LineRange;
+use crate::inventory::InventoryEntry;
+use crate::options::{Level, RemoveKind, ScrubOptions};
+use crate::scrub::scrub_text as scrub_text_with;
+use crate::transform::Transform;
+
+#[pyclass(name = "ScrubOptions", get_all, set_all)]
+#[derive(Debug, Clone)]
+pub struct PyScrubOptions {
+    header_lines: usize,
+    level: String,
+    strip_blank_lines: Option<usize>,
+    keep_outer_doc: bool,
+    keep_inner_doc: bool,
+    keep_links: bool,
+    keep_regions: bool,
+    keep_test_annotations: bool,
+    keep_in_tests: bool,
+    keep_in_macros: bool,
+    keep_above_unsafe: bool,
+    keep_above_pub: bool,
+    keep_blocks_over: Option<usize>,
+    strip_directives: bool,
+    keep_license: bool,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    keep_categories: Vec<String>,
+    remove: Option<String>,
+    transform: Option<String>,
+    rewrap: Option<usize>,
+    mark_removals: bool,
+    lines: Vec<String>,
+}
+
+#[pymethods]
+impl PyScrubOptions {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        *,
+        header_lines = 0,
+        level = "standard".to_string(),
+        strip_blank_lines = None,
+        keep_outer_doc = false,
+        keep_inner_doc = false,
+        keep_links = false,
+        keep_regions = false,
+        keep_test_annotations = false,
+        keep_in_tests = false,
+        keep_in_macros = false,
+        keep_above_unsafe = false,
+        keep_above_pub = false,
+        keep_blocks_over = None,
+        strip_directives = false,
+        keep_license = false,
+        min_length = None,
+        max_length = None,
+        keep_categories = Vec::new(),
+        remove = None,
+        transform = None,
+        rewrap = None,
+        mark_removals = false,
+        lines = Vec::new()
+    ))]
+    fn new(
+        header_lines: usize,
+        level: String,
+        strip_blank_lines: Option<usize>,
+        keep_outer_doc: bool,
+        keep_inner_doc: bool,
+        keep_links: bool,
+        keep_regions: bool,
+        keep_test_annotations: bool,
+        keep_in_tests: bool,
+        keep_in_macros: bool,
+        keep_above_unsafe: bool,
+        keep_above_pub: bool,
+        keep_blocks_over: Option<usize>,
+        strip_directives: bool,
+        keep_license: bool,
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+        keep_categories: Vec<String>,
+        remove: Option<String>,
+        transform: Option<String>,
+        rewrap: Option<usize>,
+        mark_removals: bool,
+        lines: Vec<String>,
+    ) -> PyResult<Self> {
+        let options = PyScrubOptions {
+            header_lines,
+            level,
+            strip_blank_lines,
+            keep_outer_doc,
+            keep_inner_doc,
+            keep_links,
+            keep_regions,
+            keep_test_annotations,
+            keep_in_tests,
+            keep_in_macros,
+            keep_above_unsafe,
+            keep_above_pub,
+            keep_blocks_over,
+            strip_directives,
+            keep_license,
+            min_length,
+            max_length,
+            keep_categories,
+            remove,
+            transform,
+            rewrap,
+            mark_removals,
+            lines,
+        };
+        options.to_options()?;
+        Ok(options)
+    }
+}
+
+impl PyScrubOptions {
+    fn to_options(&self) -> PyResult<ScrubOptions> {
+        let level = Level::from_str(&self.level, true)
+            .map_err(|_| PyValueError::new_err(format!("Unknown level '{}'", self.level)))?;
+        let keep_categories = self
+            .keep_categories
+            .iter()
+            .map(|name| {
+                CommentCategory::from_str(name, true)
+                    .map_err(|_| PyValueError::new_err(format!("Unknown comment category '{}'", name)))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let remove = self
+            .remove
+            .as_deref()
+            .map(|kind| {
+                RemoveKind::from_str(kind, true)
+                    .map_err(|_| PyValueError::new_err(format!("Unknown removal kind '{}'", kind)))
+            })
+            .transpose()?;
+        let transform = self
+            .transform
+            .as_deref()
+            .map(|style| {
+                Transform::from_str(style, true)
+                    .map_err(|_| PyValueError::new_err(format!("Unknown transform '{}'", style)))
+            })
+            .transpose()?;
+        let lines = self
+            .lines
+            .iter()
+            .map(|range| range.parse::<LineRange>().map_err(PyValueError::new_err))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(ScrubOptions {
+            header_lines: self.header_lines,
+            level,
+            strip_blank_lines: self.strip_blank_lines,
+            keep_outer_doc: self.keep_outer_doc,
+            keep_inner_doc: self.keep_inner_doc,
+            keep_links: self.keep_links,
+            keep_regions: self.keep_regions,
+            keep_test_annotations: self.keep_test_annotations,
+            keep_in_tests: self.keep_in_tests,
+            keep_in_macros: self.keep_in_macros,
+            keep_above_unsafe: self.keep_above_unsafe,
+            keep_above_pub: self.keep_above_pub,
+            keep_blocks_over: self.keep_blocks_over,
+            strip_directives: self.strip_directives,
+            keep_license: self.keep_license,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            keep_categories,
+            remove,
+            transform,
+            rewrap: self.rewrap,
+            mark_removals: self.mark_removals,
+            lines,
+        })
+    }
+}
+
+#[pyclass(name = "Change", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct PyChange {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    kind: &'static str,
+    doc: Option<&'static str>,
+    text: String,
+    fingerprint: String,
+}
+
+impl From<InventoryEntry> for PyChange {
+    fn from(entry: InventoryEntry) -> Self {
+        PyChange {
+            start_line: entry.start_line,
+            start_column: entry.start_column,
+            end_line: entry.end_line,
+            end_column: entry.end_column,
+            kind: entry.kind,
+            doc: entry.doc,
+            text: entry.text,
+            fingerprint: entry.fingerprint,
+        }
+    }
+}
+
+#[pyclass(name = "ScrubReport", get_all, frozen)]
+#[derive(Debug, Clone)]
+pub struct PyScrubReport {
+    text: String,
+    changes: Vec<PyChange>,
+    bytes_before: usize,
+    bytes_after: usize,
+    lines_before: usize,
+    lines_after: usize,
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, options = None))]
+fn scrub_text(text: &str, options: Option<&PyScrubOptions>) -> PyResult<PyScrubReport> {
+    let settings = match options {
+        Some(options) => options.to_options()?.settings(),
+        None => ScrubOptions::default().settings(),
+    };
+    let (output, report) = scrub_text_with(text, &settings).map_err(PyValueError::new_err)?;
+    Ok(PyScrubReport {
+        text: output,
+        changes: InventoryEntry::entries(&report.changes).into_iter().map(PyChange::from).collect(),
+        bytes_before: report.bytes.before,
+        bytes_after: report.bytes.after,
+        lines_before: report.lines.before,
+        lines_after: report.lines.after,
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, options = None))]
+fn scrub_file(path: &str, options: Option<&PyScrubOptions>) -> PyResult<PyScrubReport> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| PyIOError::new_err(format!("Failed to read input file '{}': {}", path, e)))?;
+    scrub_text(&text, options)
+}
+
+#[pymodule]
+fn rustscrub(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyScrubOptions>()?;
+    module.add_class::<PyScrubReport>()?;
+    module.add_class::<PyChange>()?;
+    module.add_function(wrap_pyfunction!(scrub_text, module)?)?;
+    module.add_function(wrap_pyfunction!(scrub_file, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_scrub_text_reports_changes() {
+        let report = scrub_text("/// Doc.\nfn f() {} // note\n", None).unwrap();
+        assert_eq!(report.text, "fn f() {} \n");
+        assert_eq!((report.lines_before, report.lines_after), (2, 1));
+        let kinds: Vec<_> = report.changes.iter().map(|change| (change.kind, change.doc, change.start_line)).collect();
+        assert_eq!(kinds, vec![("line", Some("outer"), 1), ("line", None, 2)]);
+
+        let minimal = PyScrubOptions { level: "minimal".to_string(), ..options() };
+        assert_eq!(scrub_text("/// Doc.\nfn f() {} // note\n", Some(&minimal)).unwrap().text, "/// Doc.\nfn f() {} \n");
+        let invalid = PyScrubOptions { keep_categories: vec!["poetry".to_string()], ..options() };
+        assert!(scrub_text("", Some(&invalid)).is_err());
+        assert!(scrub_file("/nonexistent/rustscrub.rs", None).is_err());
+    }
+
+    fn options() -> PyScrubOptions {
+        PyScrubOptions::new(
+            0, "standard".to_string(), None, false, false, false, false, false, false, false, false, false, None, false,
+            false, None, None, Vec::new(), None, None, None, false, Vec::new(),
+        )
+        .unwrap()
+    }
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/redundant.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashSet;
+
+const MAX_WORDS: usize = 8;
+const MIN_COVERAGE: f64 = 0.75;
+
+const STOP_WORDS: [&str; 36] = [
+    "a", "all", "an", "and", "are", "as", "at", "be", "by", "each", "every", "for", "from", "here", "in",
+    "into", "is", "it", "its", "just", "new", "now", "of", "on", "or", "our", "simply", "that", "the",
+    "then", "this", "to", "value", "variable", "we", "with",
+];
+
+const CODE_WORDS: [(&str, &[&str]); 20] = [
+    ("+=", &["increment", "increase", "add"]),
+    ("-=", &["decrement", "decrease", "subtract"]),
+    ("*=", &["multiply", "scale"]),
+    ("/=", &["divide"]),
+    ("==", &["equal", "compare", "check"]),
+    ("!=", &["differ", "compare", "check"]),
+    ("+", &["add", "plus", "sum"]),
+    ("-", &["subtract", "minus"]),
+    ("*", &["multiply", "times"]),
+    ("/", &["divide"]),
+    ("=", &["set", "assign", "store", "initialize", "init", "update"]),
+    ("let", &["declare", "define", "create"]),
+    ("fn", &["function", "define"]),
+    ("for", &["loop", "iterate", "over"]),
+    ("while", &["loop", "iterate"]),
+    ("loop", &["iterate"]),
+    ("if", &["check"]),
+    ("push", &["add", "append"]),
+    ("clone", &["copy"]),
+    ("println", &["print", "output"]),
+];
+
+const NUMBER_WORDS: [&str; 4] = ["zero", "one", "two", "three"];
+
+fn stem(word: &str) -> String {
+    let word = word.to_lowercase();
+    let base = if word.ends_with('s') && !word.ends_with("ss") {
+        &word[..word.len() - 1]
+    } else if let Some(base) = word.strip_suffix("ing") {
+        base
+    } else if let Some(base) = word.strip_suffix("ed") {
+        base
+    } else {
+        &word
+    };
+    let base = base.strip_suffix('e').unwrap_or(base);
+    if base.len() >= 3 { base.to_string() } else { word }
+}
+
+fn split_identifier(identifier: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut previous_lower = false;
+    for c in identifier.chars() {
+        if c == '_' {
+            parts.push(String::new());
+        } else {
+            if c.is_uppercase() && previous_lower {
+                parts.push(String::new());
+            }
+            parts.last_mut().unwrap().push(c);
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    parts.into_iter().filter(|part| !part.is_empty()).collect()
+}
+
+fn code_words(code: &str) -> HashSet<String> {
+    let mut words = HashSet::new();
+    let mut rest = code;
+    while !rest.is_empty() {
+        let operator = CODE_WORDS
+            .iter()
+            .filter(|(token, _)| !token.starts_with(char::is_alphabetic))
+            .find(|(token, _)| rest.starts_with(token));
+        if let Some((token, synonyms)) = operator {
+            words.extend(synonyms.iter().map(|synonym| stem(synonym)));
+            rest = &rest[token.len()..];
+            continue;
+        }
+        let length = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len())
+            .max(rest.chars().next().map_or(0, char::len_utf8));
+        let token = &rest[..length];
+        if let Ok(number) = token.parse::<usize>() {
+            words.extend(NUMBER_WORDS.get(number).map(|word| word.to_string()));
+        } else if let Some((_, synonyms)) = CODE_WORDS.iter().find(|(keyword, _)| *keyword == token) {
+            words.extend(synonyms.iter().map(|synonym| stem(synonym)));
+            words.insert(stem(token));
+        } else if token.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            words.insert(token.to_lowercase());
+            words.extend(split_identifier(token).iter().map(|part| stem(part)));
+        }
+        rest = &rest[length..];
+    }
+    words
+}
+
+pub fn restates_code(comment: &str, code: &str) -> bool {
+    let comment_words: Vec<String> = comment
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(&word.to_lowercase().as_str()))
+        .map(stem)
+        .collect();
+    if comment_words.is_empty() || comment_words.len() > MAX_WORDS {
+        return false;
+    }
+    let code_words = code_words(code);
+    let named = code_words.iter().any(|word| word.chars().any(char::is_alphabetic));
+    if !named {
+        return false;
+    }
+    let covered = comment_words.iter().filter(|word| code_words.contains(*word)).count();
+    covered as f64 / comment_words.len() as f64 >= MIN_COVERAGE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_restating_code() {
+        assert!(restates_code("increment i", "    i += 1;"));
+        assert!(restates_code("Return the result", "    return result;"));
+        assert!(restates_code("loop over all the items", "for item in items {"));
+        assert!(restates_code("set the retry count to zero", "self.retry_count = 0;"));
+        assert!(!restates_code("Retry the request three times before giving up", "for _ in 0..3 { send(&request)?; }"));
+        assert!(!restates_code("account for the trailing newline", "len += 1;"));
+        assert!(!restates_code("close the scope", "}"));
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/remote.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor};
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+pub fn is_url(input: &str) -> bool {
+    let lower = input.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "http") {
+        Ok(())
+    } else {
+        Err("Remote inputs and self-update require building rustscrub with the 'http' feature.".to_string())
+    }
+}
+
+#[cfg(feature = "http")]
+pub fn fetch(url: &str, timeout_secs: u64) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+    use std::time::Duration;
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(timeout_secs))
+        .try_proxy_from_env(true)
+        .user_agent(concat!("rustscrub/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let response = agent
+        .get(url)
+        .call()
+        .map_err(|e| format!("Failed to fetch '{}': {}", url, e))?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to read response from '{}': {}", url, e))?;
+    Ok(body)
+}
+
+#[cfg(not(feature = "http"))]
+pub fn fetch(url: &str, _timeout_secs: u64) -> Result<Vec<u8>, String> {
+    Err(format!("Cannot fetch '{}': rustscrub was built without the 'http' feature.", url))
+}
+
+pub fn open_input(input: &str, timeout_secs: u64) -> Result<Box<dyn BufRead>, String> {
+    if is_url(input) {
+        return Ok(Box::new(Cursor::new(fetch(input, timeout_secs)?)));
+    }
+    let input_file = File::open(input)
+        .map_err(|e| format!("Failed to open input file '{}': {}", input, e))?;
+    Ok(Box::new(BufReader::new(input_file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://raw.githubusercontent.com/o/r/main/src/lib.rs"));
+        assert!(is_url("HTTP://example.com/a.rs"));
+        assert!(!is_url("src/lib.rs"));
+        assert!(!is_url("ftp://example.com/a.rs"));
+    }
+}
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/rewrap.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use crate::classify::{classify, CommentCategory};
+use crate::filter::Comment;
+use crate::scrub::VerboseCommentType;
+
+const MIN_TEXT_WIDTH: usize = 20;
+
+struct CommentLine<'a> {
+    indent: &'a str,
+    marker: &'a str,
+    body: &'a str,
+    newline: &'a str,
+}
+
+fn parse_line(line: &str) -> Option<CommentLine<'_>> {
+    let newline_start = line.trim_end_matches(['\r', '\n']).len();
+    let (content, newline) = line.split_at(newline_start);
+    let text = content.trim_start();
+    let indent = &content[..content.len() - text.len()];
+    let marker = if text.starts_with("///") && !text.starts_with("////") {
+        "///"
+    } else if text.starts_with("//!") {
+        "//!"
+    } else if text.starts_with("//") {
+        "//"
+    } else {
+        return None;
+    };
+    let body = &text[marker.len()..];
+    let body = body.strip_prefix(' ').unwrap_or(body);
+    Some(CommentLine {
+        indent,
+        marker,
+        body,
+        newline,
+    })
+}
+
+fn is_prose(line: &str) -> bool {
+    let comment = Comment {
+        text: line.trim(),
+        comment_type: VerboseCommentType::Line,
+        full_line: true,
+        continues: None,
+    };
+    !matches!(
+        classify(&comment),
+        CommentCategory::Code | CommentCategory::Directive | CommentCategory::License
+    )
+}
+
+fn list_marker_width(body: &str) -> Option<usize> {
+    let text = body.trim_start();
+    let leading = body.len() - text.len();
+    if ["- ", "* ", "+ "].iter().any(|marker| text.starts_with(marker)) {
+        return Some(leading + 2);
+    }
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    let rest = &text[digits..];
+    (digits > 0 && (rest.starts_with(". ") || rest.starts_with(") "))).then_some(leading + digits + 2)
+}
+
+fn is_verbatim(body: &str) -> bool {
+    let text = body.trim_start();
+    body.len() - text.len() >= 4
+        || ["#", "|", ">", "<", "[^"].iter().any(|prefix| text.starts_with(prefix))
+        || (text.starts_with('[') && text.contains("]:"))
+}
+
+struct Paragraph {
+    first_prefix: String,
+    hanging_prefix: String,
+    words: Vec<String>,
+    hard_break: bool,
+}
+
+impl Paragraph {
+    fn new(first_prefix: &str, hanging_prefix: String) -> Self {
+        Paragraph {
+            first_prefix: first_prefix.to_string(),
+            hanging_prefix,
+            words: Vec::new(),
+            hard_break: false,
+        }
+    }
+}
+
+fn wrap(paragraph: &Paragraph, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = paragraph.first_prefix.clone();
+    let mut empty = true;
+    for word in &paragraph.words {
+        if !empty && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::replace(&mut current, paragraph.hanging_prefix.clone()));
+            empty = true;
+        }
+        if !empty {
+            current.push(' ');
+        }
+        current.push_str(word);
+        empty = false;
+    }
+    if paragraph.hard_break {
+        current.push_str("  ");
+    }
+    lines.push(current);
+    lines
+}
+
+fn rewrap_bodies(bodies: &[&str], width: usize) -> Vec<String> {
+    let mut output = Vec::new();
+    let mut paragraph: Option<Paragraph> = None;
+    let mut fence: Option<&str> = None;
+    let flush = |paragraph: &mut Option<Paragraph>, output: &mut Vec<String>| {
+        if let Some(paragraph) = paragraph.take() {
+            output.extend(wrap(&paragraph, width));
+        }
+    };
+
+    for &body in bodies {
+        let text = body.trim_start();
+        if let Some(marker) = fence {
+            output.push(body.to_string());
+            if text.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if text.starts_with("```") || text.starts_with("~~~") {
+            flush(&mut paragraph, &mut output);
+            fence = Some(&text[..3]);
+            output.push(body.to_string());
+            continue;
+        }
+        if text.is_empty() {
+            flush(&mut paragraph, &mut output);
+            output.push(String::new());
+            continue;
+        }
+        let continues_item = paragraph
+            .as_ref()
+            .is_some_and(|paragraph| !paragraph.hanging_prefix.is_empty() && body.starts_with(' '));
+        if !continues_item {
+            if let Some(marker_width) = list_marker_width(body) {
+                flush(&mut paragraph, &mut output);
+                paragraph = Some(Paragraph::new(&body[..marker_width], " ".repeat(marker_width)));
+            } else if is_verbatim(body) {
+                flush(&mut paragraph, &mut output);
+                output.push(body.to_string());
+                continue;
+            }
+        }
+        let current = paragraph.get_or_insert_with(|| Paragraph::new("", String::new()));
+        let content = if current.words.is_empty() { &body[current.first_prefix.len()..] } else { body };
+        current.words.extend(content.split_whitespace().map(str::to_string));
+        if body.ends_with("  ") || body.ends_with('\\') {
+            current.hard_break = body.ends_with("  ");
+            flush(&mut paragraph, &mut output);
+        }
+    }
+    flush(&mut paragraph, &mut output);
+    output
+}
+
+pub fn rewrap_lines(lines: &mut [String], width: usize) {
+    let mut start = 0;
+    while start < lines.len() {
+        let Some(first) = parse_line(&lines[start]) else {
+            start += 1;
+            continue;
+        };
+        let (indent, marker, newline) = (first.indent.to_string(), first.marker, first.newline.to_string());
+        let mut end = start + 1;
+        while end < lines.len()
+            && parse_line(&lines[end]).is_some_and(|line| line.indent == indent && line.marker == marker)
+        {
+            end += 1;
+        }
+        let group = &lines[start..end];
+        if group.iter().all(|line| is_prose(line)) {
+            let bodies: Vec<&str> = group.iter().filter_map(|line| parse_line(line)).map(|line| line.body).collect();
+            let text_width = width.saturating_sub(indent.chars().count() + marker.len() + 1).max(MIN_TEXT_WIDTH);
+            let rewrapped: String = rewrap_bodies(&bodies, text_width)
+                .iter()
+                .map(|body| {
+                    if body.is_empty() {
+                        format!("{}{}{}", indent, marker, newline)
+                    } else {
+                        format!("{}{} {}{}", indent, marker, body, newline)
+                    }
+                })
+                .collect();
+            for line in &mut lines[start..end] {
+                line.clear();
+            }
+            lines[start] = rewrapped;
+        }
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrap(text: &str, width: usize) -> String {
+        let mut lines: Vec<String> = text.split_inclusive('\n').map(str::to_string).collect();
+        rewrap_lines(&mut lines, width);
+        lines.concat()
+    }
+
+    #[test]
+    fn test_rewrap_reflows_paragraphs_and_lists() {
+        let input = "    /// Parses the header of a file and returns the number\n    /// of lines.\n    ///\n    /// - first item that is long enough to wrap around\n    /// - second\n";
+        assert_eq!(
+            rewrap(input, 40),
+            "    /// Parses the header of a file and\n    /// returns the number of lines.\n    ///\n    /// - first item that is long enough\n    ///   to wrap around\n    /// - second\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrap_keeps_code_blocks_and_code_comments() {
+        let input = "/// Example:\n///\n/// ```\n/// let value = compute(1, 2, 3, 4, 5, 6, 7, 8, 9, 10);\n/// ```\n// let x = compute(a, b);\n// let y = x;\n";
+        assert_eq!(rewrap(input, 30), input);
+    }
+}
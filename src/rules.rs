@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/rules.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fmt;
+use std::sync::Mutex;
+
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+
+use crate::plugin::{CommentPlugin, Decision, PluginComment};
+
+const MAX_OPERATIONS: u64 = 100_000;
+
+pub struct Rules {
+    engine: Engine,
+    keep: Vec<(String, AST)>,
+    remove: Vec<(String, AST)>,
+    error: Mutex<Option<String>>,
+}
+
+impl fmt::Debug for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rules").field("keep", &self.keep.len()).field("remove", &self.remove.len()).finish()
+    }
+}
+
+impl Rules {
+    pub fn compile(keep: &[String], remove: &[String]) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let compile = |rules: &[String]| -> Result<Vec<(String, AST)>, String> {
+            rules
+                .iter()
+                .map(|rule| {
+                    let ast = engine.compile_expression(rule).map_err(|e| format!("Failed to compile rule '{}': {}", rule, e))?;
+                    Ok((rule.clone(), ast))
+                })
+                .collect()
+        };
+        let keep = compile(keep)?;
+        let remove = compile(remove)?;
+        Ok(Rules {
+            engine,
+            keep,
+            remove,
+            error: Mutex::new(None),
+        })
+    }
+
+    fn comment_value(comment: &PluginComment) -> Result<Dynamic, String> {
+        let mut value: Map = rhai::serde::to_dynamic(comment)
+            .and_then(|value| value.try_cast_result::<Map>().map_err(|_| "comment is not a map".into()))
+            .map_err(|e| format!("Failed to pass comment to rules: {}", e))?;
+        value.insert("lines".into(), Dynamic::from((comment.end_line + 1 - comment.start_line) as i64));
+        Ok(value.into())
+    }
+
+    fn matches(&self, rules: &[(String, AST)], comment: &Dynamic) -> Result<bool, String> {
+        for (rule, ast) in rules {
+            let mut scope = Scope::new();
+            scope.push_constant_dynamic("comment", comment.clone());
+            let matched = self
+                .engine
+                .eval_ast_with_scope::<bool>(&mut scope, ast)
+                .map_err(|e| format!("Rule '{}' failed: {}", rule, e))?;
+            if matched {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn evaluate(&self, comment: &PluginComment) -> Result<Decision, String> {
+        let comment = Rules::comment_value(comment)?;
+        if self.matches(&self.keep, &comment)? {
+            Ok(Decision::Keep)
+        } else if self.matches(&self.remove, &comment)? {
+            Ok(Decision::Remove)
+        } else {
+            Ok(Decision::Default)
+        }
+    }
+}
+
+impl CommentPlugin for Rules {
+    fn decide(&self, comment: &PluginComment) -> Decision {
+        self.evaluate(comment).unwrap_or_else(|e| {
+            self.error.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(e);
+            Decision::Default
+        })
+    }
+
+    fn take_error(&self) -> Option<String> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::filter::Comment;
+    use crate::scrub::VerboseCommentType;
+
+    fn decide(rules: &Rules, text: &'static str, start_line: usize, end_line: usize) -> Decision {
+        let comment = Comment {
+            text,
+            comment_type: VerboseCommentType::Line,
+            full_line: true,
+            continues: None,
+        };
+        rules.decide(&PluginComment::new(&comment, start_line, end_line, 1))
+    }
+
+    #[test]
+    fn test_rules_keep_before_remove() {
+        let rules = Rules::compile(
+            &[r#"comment.text.contains("SAFETY") || comment.lines > 5"#.to_string()],
+            &[r#"comment.category == "todo""#.to_string()],
+        )
+        .unwrap();
+        assert_eq!(decide(&rules, "// SAFETY: aligned", 1, 1), Decision::Keep);
+        assert_eq!(decide(&rules, "// long", 1, 6), Decision::Keep);
+        assert_eq!(decide(&rules, "// TODO: later", 1, 1), Decision::Remove);
+        assert_eq!(decide(&rules, "// plain", 1, 1), Decision::Default);
+        assert!(rules.take_error().is_none());
+    }
+
+    #[test]
+    fn test_rule_errors_are_reported() {
+        assert!(Rules::compile(&["comment.text.contains(".to_string()], &[]).is_err());
+        let rules = Rules::compile(&["comment.lines".to_string()], &[]).unwrap();
+        assert_eq!(decide(&rules, "// x", 1, 1), Decision::Default);
+        assert!(rules.take_error().unwrap().contains("comment.lines"));
+    }
+}
@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/schema.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::ops::RangeInclusive;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const REPORT_SCHEMA: &str = include_str!("../schema/report.schema.json");
+
+static TOOL: Mutex<Option<Tool>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Tool {
+    pub name: String,
+    pub version: String,
+    pub arguments: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_sha256: Option<String>,
+    pub timestamp: String,
+}
+
+impl Tool {
+    pub fn new(arguments: Vec<String>, seconds: u64) -> Self {
+        Tool {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            arguments,
+            config_sha256: None,
+            timestamp: format_timestamp(seconds),
+        }
+    }
+}
+
+pub fn set_tool(tool: Tool) {
+    *TOOL.lock().unwrap_or_else(|e| e.into_inner()) = Some(tool);
+}
+
+pub fn record_config(text: &str) {
+    if let Some(tool) = TOOL.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        tool.config_sha256 = Some(sha256_hex(text.as_bytes()));
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn date_field(field: &str, digits: usize, valid: RangeInclusive<i64>) -> Option<i64> {
+    let value = (field.len() == digits && field.bytes().all(|b| b.is_ascii_digit())).then(|| field.parse().ok())??;
+    valid.contains(&value).then_some(value)
+}
+
+pub fn parse_date(text: &str) -> Result<u64, String> {
+    if let Ok(seconds) = text.parse() {
+        return Ok(seconds);
+    }
+    let (date, time) = text.strip_suffix('Z').and_then(|text| text.split_once('T')).unwrap_or((text, "00:00:00"));
+    let fields: Vec<&str> = date.split('-').chain(time.split(':')).collect();
+    let limits = [(4, 1970..=9999), (2, 1..=12), (2, 1..=31), (2, 0..=23), (2, 0..=59), (2, 0..=59)];
+    let values: Option<Vec<i64>> = (fields.len() == limits.len())
+        .then(|| fields.iter().zip(limits).map(|(field, (digits, valid))| date_field(field, digits, valid)).collect())
+        .flatten();
+    match values.as_deref() {
+        Some(&[year, month, day, hour, minute, second]) => {
+            Ok((days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second) as u64)
+        }
+        _ => Err(format!("Invalid date '{}': expected seconds since 1970, YYYY-MM-DD or YYYY-MM-DDTHH:MM:SSZ.", text)),
+    }
+}
+
+pub fn build_date(date: Option<u64>) -> Result<u64, String> {
+    if let Some(seconds) = date {
+        return Ok(seconds);
+    }
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch
+            .trim()
+            .parse()
+            .map_err(|e| format!("Failed to parse SOURCE_DATE_EPOCH '{}': {}", epoch, e)),
+        Err(_) => Ok(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())),
+    }
+}
+
+fn format_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let day_seconds = seconds % 86_400;
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        day_seconds / 3600,
+        day_seconds % 3600 / 60,
+        day_seconds % 60
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct Versioned<'a, T> {
+    pub schema_version: u32,
+    pub report: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<Tool>,
+    #[serde(flatten)]
+    pub body: &'a T,
+}
+
+pub fn to_json<T: Serialize>(report: &'static str, body: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        report,
+        tool: TOOL.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        body,
+    })
+}
+
+pub fn versioned_schema<T: JsonSchema>(generator: &mut SchemaGenerator, report: Option<&str>) -> Schema {
+    let tool = report.is_some().then(|| generator.subschema_for::<Tool>());
+    let mut schema = T::json_schema(generator);
+    let object = schema.ensure_object();
+    let mut versioned = vec![("schema_version", json!({ "const": SCHEMA_VERSION }))];
+    if let Some(report) = report {
+        versioned.push(("report", json!({ "const": report })));
+    }
+    let properties = object.entry("properties").or_insert_with(|| json!({}));
+    if let Value::Object(properties) = properties {
+        for (name, property) in &versioned {
+            properties.insert(name.to_string(), property.clone());
+        }
+        if let Some(tool) = tool {
+            properties.insert("tool".to_string(), tool.to_value());
+        }
+    }
+    let required = object.entry("required").or_insert_with(|| json!([]));
+    if let Value::Array(required) = required {
+        required.retain(|name| versioned.iter().all(|(versioned, _)| name != versioned));
+        for (index, (name, _)) in versioned.iter().enumerate() {
+            required.insert(index, json!(name));
+        }
+    }
+    schema
+}
+
+pub fn root_schema(mut generator: SchemaGenerator, title: &str, body: Schema) -> Result<String, serde_json::Error> {
+    let mut root = json_schema!({
+        "$schema": generator.settings().meta_schema,
+        "title": title,
+        "description": format!("Schema version {}.", SCHEMA_VERSION),
+    });
+    if let Some(body) = body.as_object() {
+        for (key, value) in body {
+            root.insert(key.clone(), value.clone());
+        }
+    }
+    let definitions = generator.take_definitions(true);
+    if !definitions.is_empty() {
+        root.insert("$defs".to_string(), Value::Object(definitions));
+    }
+    serde_json::to_string_pretty(&root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::Inventory;
+    use crate::scrub::{scrub_text, ScrubSettings};
+    use crate::summary::{FileRow, FileSummaryReport};
+    use serde_json::Value;
+
+    fn assert_matches_schema(json: &str) {
+        let schema: Value = serde_json::from_str(REPORT_SCHEMA).unwrap();
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        let definition = &schema["$defs"][value["report"].as_str().unwrap()];
+        assert_eq!(definition["properties"]["report"]["const"], value["report"]);
+        for required in definition["required"].as_array().unwrap() {
+            assert!(value.get(required.as_str().unwrap()).is_some(), "missing {}", required);
+        }
+        for key in value.as_object().unwrap().keys() {
+            assert!(definition["properties"].get(key).is_some(), "undeclared {}", key);
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_792_238_645), "2026-10-17T12:04:05Z");
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1792238645"), Ok(1_792_238_645));
+        assert_eq!(parse_date("2026-10-17T12:04:05Z"), Ok(1_792_238_645));
+        assert_eq!(parse_date("2000-02-29"), Ok(951_782_400));
+        assert!(parse_date("2000-13-01").is_err());
+        assert!(parse_date("2026-10-17T25:00:00Z").is_err());
+        assert!(parse_date("yesterday").is_err());
+        assert_eq!(build_date(Some(42)), Ok(42));
+    }
+
+    #[test]
+    fn test_reports_match_schema() {
+        let schema: Value = serde_json::from_str(REPORT_SCHEMA).unwrap();
+        assert_eq!(schema["$defs"]["schema_version"]["const"], SCHEMA_VERSION);
+
+        set_tool(Tool::new(vec!["--inventory".to_string(), "inventory.json".to_string()], 0));
+        record_config("[banned]\nprofanity = false\n");
+        let (_, report) = scrub_text("/// Doc.\nfn f() {} // note\n", &ScrubSettings::default()).unwrap();
+        let mut inventory = Inventory::default();
+        inventory.add_file("a.rs", &report.changes);
+        let inventory_json = inventory.to_json().unwrap();
+        assert_matches_schema(&inventory_json);
+        let tool = &serde_json::from_str::<Value>(&inventory_json).unwrap()["tool"];
+        assert_eq!(tool["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(tool["config_sha256"].as_str().unwrap().len(), 64);
+
+        let rows = vec![FileRow::new("a.rs", &report, true)];
+        assert_matches_schema(&to_json("file-summary", &FileSummaryReport { files: &rows }).unwrap());
+    }
+}
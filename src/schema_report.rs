@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/schema_report.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use schemars::{json_schema, JsonSchema, SchemaGenerator};
+
+use rustscrub::inventory::Inventory;
+use rustscrub::schema::{root_schema, versioned_schema};
+use rustscrub::summary::FileSummaryReport;
+
+use crate::classify_report::ClassificationReport;
+use crate::comment_age::CommentAges;
+use crate::comment_owners::CommentOwners;
+use crate::diff_comments::CommentDiff;
+use crate::events::EventLine;
+use crate::line_map::LineMap;
+use crate::stats::{CommentStats, Comparison};
+use crate::verbose::VerboseReport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaKind {
+    Report,
+    Events,
+    Inventory,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SchemaArgs {
+    #[clap(value_enum, default_value_t = SchemaKind::Report)]
+    kind: SchemaKind,
+}
+
+fn define<T: JsonSchema>(generator: &mut SchemaGenerator, report: &str) -> serde_json::Value {
+    let schema = versioned_schema::<T>(generator, Some(report));
+    generator.definitions_mut().insert(report.to_string(), schema.to_value());
+    serde_json::json!({ "$ref": format!("#/$defs/{}", report) })
+}
+
+pub fn generate(kind: SchemaKind) -> Result<String, String> {
+    let mut generator = SchemaGenerator::default();
+    let (title, body) = match kind {
+        SchemaKind::Report => {
+            let reports = vec![
+                define::<ClassificationReport>(&mut generator, "classify"),
+                define::<CommentStats>(&mut generator, "stats"),
+                define::<Comparison>(&mut generator, "stats-comparison"),
+                define::<CommentAges>(&mut generator, "stats-age"),
+                define::<CommentOwners>(&mut generator, "stats-owners"),
+                define::<CommentDiff>(&mut generator, "comment-diff"),
+                define::<Inventory>(&mut generator, "inventory"),
+                define::<FileSummaryReport>(&mut generator, "file-summary"),
+                define::<VerboseReport>(&mut generator, "verbose"),
+                define::<LineMap>(&mut generator, "line-map"),
+            ];
+            ("RustScrub JSON report", json_schema!({ "oneOf": reports }))
+        }
+        SchemaKind::Events => ("RustScrub event stream line", versioned_schema::<EventLine>(&mut generator, None)),
+        SchemaKind::Inventory => ("RustScrub comment inventory", versioned_schema::<Inventory>(&mut generator, Some("inventory"))),
+    };
+    root_schema(generator, title, body).map_err(|e| format!("Failed to serialize schema: {}", e))
+}
+
+pub fn run(args: &SchemaArgs) -> Result<(), String> {
+    println!("{}", generate(args.kind)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comment_age::AgedComment;
+    use rustscrub::schema::to_json;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+    use rustscrub::summary::FileRow;
+    use serde_json::Value;
+
+    fn assert_declares(schema: &Value, json: &str) {
+        let value: Value = serde_json::from_str(json).unwrap();
+        for required in schema["required"].as_array().unwrap() {
+            assert!(value.get(required.as_str().unwrap()).is_some(), "missing {}", required);
+        }
+        for key in value.as_object().unwrap().keys() {
+            assert!(schema["properties"].get(key).is_some(), "undeclared {}", key);
+        }
+        assert_eq!(schema["properties"]["report"]["const"], value["report"]);
+    }
+
+    #[test]
+    fn test_generated_schemas_describe_outputs() {
+        let (_, report) = scrub_text("/// Doc.\nfn f() {} // note\n", &ScrubSettings::default()).unwrap();
+        let mut inventory = Inventory::default();
+        inventory.add_file("a.rs", &report.changes);
+        let inventory_json = inventory.to_json().unwrap();
+        let inventory_schema: Value = serde_json::from_str(&generate(SchemaKind::Inventory).unwrap()).unwrap();
+        assert_declares(&inventory_schema, &inventory_json);
+
+        let reports: Value = serde_json::from_str(&generate(SchemaKind::Report).unwrap()).unwrap();
+        assert_eq!(reports["oneOf"].as_array().unwrap().len(), 10);
+        assert_declares(&reports["$defs"]["inventory"], &inventory_json);
+        let rows = vec![FileRow::new("a.rs", &report, true)];
+        assert_declares(&reports["$defs"]["file-summary"], &to_json("file-summary", &FileSummaryReport { files: &rows }).unwrap());
+        let line_map = LineMap::new(&[("a.rs".to_string(), report.clone())]);
+        assert_declares(&reports["$defs"]["line-map"], &to_json("line-map", &line_map).unwrap());
+        let verbose = VerboseReport::new(&[("a.rs".to_string(), report)]);
+        assert_declares(&reports["$defs"]["verbose"], &to_json("verbose", &verbose).unwrap());
+        let aged = vec![AgedComment { path: "a.rs".to_string(), line: 1, age_days: 400, text: "// note".to_string() }];
+        assert_declares(&reports["$defs"]["stats-age"], &to_json("stats-age", &CommentAges::new(aged, 0, 1, 10)).unwrap());
+        let mut owners = CommentOwners::default();
+        owners.add("Ada", 1, None);
+        assert_declares(&reports["$defs"]["stats-owners"], &to_json("stats-owners", &owners).unwrap());
+    }
+}
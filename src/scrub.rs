@@ -5,17 +5,51 @@
 // Author: Volker Schwaberow <volker@schwaberow.de>
 // Copyright (c) 2025 Volker Schwaberow
 
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+use crate::blank_lines::{BlankLineFilter, BlankLineLimit};
+use crate::filter::{pragma, Comment, CommentFilter, KeepReason, Pragma};
+use crate::plugin::Decision;
+use crate::lexer::{self, Action};
+use crate::rewrap::rewrap_lines;
+use crate::summary::Tally;
+use crate::tokens::{self, count_tokens, TokenModel};
+use crate::transform::{block_to_line, line_to_block, Transform};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VerboseCommentType {
     Line,
     Block,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeDetail {
+    #[default]
+    Full,
+    Lines,
+}
+
 #[derive(Debug, Clone)]
 pub struct ChangeInfo {
     pub start_line: usize,
     pub end_line: usize,
     pub comment_type: VerboseCommentType,
+    pub start_column: usize,
+    pub end_column: usize,
+    pub text: String,
+    pub text_len: usize,
+}
+
+impl ChangeInfo {
+    pub fn as_comment(&self) -> Comment<'_> {
+        Comment {
+            text: &self.text,
+            comment_type: self.comment_type,
+            full_line: false,
+            continues: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,12 +64,61 @@ pub enum State {
     InRawString,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BraceRegion {
+    pub base_depth: usize,
+    pub opened: bool,
+    pub closed: bool,
+}
+
+impl BraceRegion {
+    fn open_at(base_depth: usize) -> Self {
+        BraceRegion {
+            base_depth,
+            opened: false,
+            closed: false,
+        }
+    }
+
+    fn track(&mut self, current_char: char, brace_depth: usize) {
+        match current_char {
+            '{' => self.opened = true,
+            '}' if self.opened && brace_depth <= self.base_depth => self.closed = true,
+            ';' if !self.opened && brace_depth == self.base_depth => self.closed = true,
+            _ => {}
+        }
+    }
+}
+
+const LINE_REMOVAL_MARKER: &str = "// …";
+const BLOCK_REMOVAL_MARKER: &str = "/* … */";
+const PRAGMA_SCAN_LINES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StreamState {
     pub current_parse_state: State,
     pub raw_string_hash_count: usize,
     pub active_block_comment_start_line: Option<usize>,
+    pub active_comment_start_column: usize,
     pub is_processing_full_line_comment: bool,
+    pub comment_buffer: String,
+    pub comment_prefix: String,
+    pub last_kept_full_line_comment: Option<(usize, KeepReason)>,
+    pub brace_depth: usize,
+    pub test_region: Option<BraceRegion>,
+    pub line_in_test_region: bool,
+    pub macro_region: Option<BraceRegion>,
+    pub line_in_macro_region: bool,
+    pub pragma_off: bool,
+    pub keep_next_line: Option<usize>,
+    pub line_kept_by_pragma: bool,
+    pub ignore_file: bool,
+    pub replacement: Option<String>,
+    pub line_is_comment_only: bool,
+    pub line_touched_block_comment: bool,
+    pub block_comment_indent: String,
+    pub last_removed_full_line_comment: Option<usize>,
+    pub change_detail: ChangeDetail,
 }
 
 impl Default for StreamState {
@@ -44,19 +127,436 @@ impl Default for StreamState {
             current_parse_state: State::Normal,
             raw_string_hash_count: 0,
             active_block_comment_start_line: None,
+            active_comment_start_column: 0,
             is_processing_full_line_comment: false,
+            comment_buffer: String::new(),
+            comment_prefix: String::new(),
+            last_kept_full_line_comment: None,
+            brace_depth: 0,
+            test_region: None,
+            line_in_test_region: false,
+            macro_region: None,
+            line_in_macro_region: false,
+            pragma_off: false,
+            keep_next_line: None,
+            line_kept_by_pragma: false,
+            ignore_file: false,
+            replacement: None,
+            line_is_comment_only: false,
+            line_touched_block_comment: false,
+            block_comment_indent: String::new(),
+            last_removed_full_line_comment: None,
+            change_detail: ChangeDetail::Full,
+        }
+    }
+}
+
+fn is_test_attribute(trimmed_line: &str) -> bool {
+    trimmed_line.starts_with("#[test]")
+        || trimmed_line.starts_with("#[cfg(test)]")
+        || trimmed_line.starts_with("#[cfg(all(test")
+        || (trimmed_line.starts_with("#[") && (trimmed_line.contains("::test]") || trimmed_line.contains("::test(")))
+}
+
+impl StreamState {
+    fn begin_line(&mut self, line_content: &str) {
+        self.line_is_comment_only = false;
+        self.line_touched_block_comment = self.current_parse_state == State::BlockComment;
+        self.line_in_test_region = self.test_region.is_some();
+        self.line_in_macro_region = self.macro_region.is_some();
+        self.line_kept_by_pragma = is_keep_line_pragma(line_content);
+        if self.current_parse_state != State::Normal {
+            return;
+        }
+        let trimmed_line = line_content.trim_start();
+        if self.test_region.is_none() && is_test_attribute(trimmed_line) {
+            self.test_region = Some(BraceRegion::open_at(self.brace_depth));
+            self.line_in_test_region = true;
+        }
+        if self.macro_region.is_none() && trimmed_line.starts_with("macro_rules!") {
+            self.macro_region = Some(BraceRegion::open_at(self.brace_depth));
+            self.line_in_macro_region = true;
+        }
+    }
+
+    fn track_code_char(&mut self, current_char: char) {
+        match current_char {
+            '{' => self.brace_depth += 1,
+            '}' => self.brace_depth = self.brace_depth.saturating_sub(1),
+            ';' => {}
+            _ => return,
+        }
+        for region in [self.test_region.as_mut(), self.macro_region.as_mut()].into_iter().flatten() {
+            region.track(current_char, self.brace_depth);
+        }
+    }
+
+    fn end_line(&mut self) {
+        if self.test_region.is_some_and(|region| region.closed) {
+            self.test_region = None;
+        }
+        if self.macro_region.is_some_and(|region| region.closed) {
+            self.macro_region = None;
+        }
+    }
+}
+
+fn is_attribute_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with("#[") && trimmed.ends_with(']')
+}
+
+fn is_keep_line_pragma(line: &str) -> bool {
+    line.trim_end()
+        .strip_suffix("rustscrub:keep-line")
+        .is_some_and(|rest| rest.trim_end().ends_with("//"))
+}
+
+fn is_ignore_file_pragma(line: &str) -> bool {
+    let comment = Comment {
+        text: line.trim(),
+        comment_type: VerboseCommentType::Line,
+        full_line: true,
+        continues: None,
+    };
+    comment.text.starts_with("//") && pragma(&comment) == Some(Pragma::IgnoreFile)
+}
+
+fn keep_reason(
+    filter: &CommentFilter,
+    comment: &Comment,
+    stream_state: &mut StreamState,
+    line: usize,
+    end_line: usize,
+) -> Option<KeepReason> {
+    let kept_by_pragma = stream_state.line_kept_by_pragma || stream_state.keep_next_line == Some(line);
+    if stream_state.pragma_off || kept_by_pragma || pragma(comment).is_some() {
+        return Some(KeepReason::Pragma);
+    }
+    if !filter.in_lines(line) {
+        return Some(KeepReason::OutsideLines);
+    }
+    if filter.keep_in_tests && stream_state.line_in_test_region {
+        return Some(KeepReason::InTest);
+    }
+    if filter.keep_in_macros && stream_state.line_in_macro_region {
+        return Some(KeepReason::InMacro);
+    }
+    match filter.plugin_decision(comment, line, end_line, stream_state.active_comment_start_column) {
+        Decision::Default => filter.keep_reason(comment),
+        Decision::Keep => Some(KeepReason::Plugin),
+        Decision::Remove => None,
+        Decision::Replace { text } => {
+            stream_state.replacement = Some(text);
+            Some(KeepReason::Plugin)
         }
     }
 }
 
+fn finish_line_comment(
+    output_segment: &mut String,
+    stream_state: &mut StreamState,
+    filter: &CommentFilter,
+    original_line_num: usize,
+    line_changes: &mut Vec<ChangeInfo>,
+    with_newline: bool,
+) {
+    let text = std::mem::take(&mut stream_state.comment_buffer);
+    let full_line = stream_state.is_processing_full_line_comment;
+    stream_state.line_is_comment_only = full_line && !stream_state.line_touched_block_comment;
+    let continues = match stream_state.last_kept_full_line_comment {
+        Some((line, reason)) if full_line && line + 1 == original_line_num => Some(reason),
+        _ => None,
+    };
+    let comment = Comment {
+        text: &text,
+        comment_type: VerboseCommentType::Line,
+        full_line,
+        continues,
+    };
+
+    let reason = keep_reason(filter, &comment, stream_state, original_line_num, original_line_num);
+    match pragma(&comment) {
+        Some(Pragma::Off) => stream_state.pragma_off = true,
+        Some(Pragma::On) => stream_state.pragma_off = false,
+        Some(Pragma::KeepNext) => stream_state.keep_next_line = Some(original_line_num + 1),
+        _ => {}
+    }
+    match reason {
+        Some(reason) => {
+            if full_line {
+                output_segment.push_str(&stream_state.comment_prefix);
+            }
+            let replacement = stream_state.replacement.take();
+            let transformed = match filter.transform {
+                Some(Transform::LineToBlock) if !reason.is_verbatim() && replacement.is_none() => line_to_block(&text),
+                _ => None,
+            };
+            output_segment.push_str(&filter.kept_text(replacement.as_deref().or(transformed.as_deref()).unwrap_or(&text)));
+            if with_newline {
+                output_segment.push('\n');
+            }
+            stream_state.last_kept_full_line_comment = full_line.then_some((original_line_num, reason));
+            stream_state.last_removed_full_line_comment = None;
+        }
+        None => {
+            let full = stream_state.change_detail == ChangeDetail::Full;
+            let start_column = stream_state.active_comment_start_column;
+            line_changes.push(ChangeInfo {
+                start_line: original_line_num,
+                end_line: original_line_num,
+                comment_type: VerboseCommentType::Line,
+                start_column,
+                end_column: if full { start_column + text.chars().count() - 1 } else { 0 },
+                text: if full { text.clone() } else { String::new() },
+                text_len: text.len(),
+            });
+            let continues_removal = full_line
+                && stream_state.last_removed_full_line_comment.is_some_and(|line| line + 1 == original_line_num);
+            if filter.mark_removals && !continues_removal {
+                if full_line {
+                    output_segment.push_str(&stream_state.comment_prefix);
+                }
+                output_segment.push_str(LINE_REMOVAL_MARKER);
+                if full_line && with_newline {
+                    output_segment.push('\n');
+                }
+            }
+            if with_newline && !full_line {
+                output_segment.push('\n');
+            }
+            stream_state.last_kept_full_line_comment = None;
+            stream_state.last_removed_full_line_comment = full_line.then_some(original_line_num);
+        }
+    }
+
+    stream_state.comment_buffer = text;
+    stream_state.comment_buffer.clear();
+    stream_state.comment_prefix.clear();
+    stream_state.current_parse_state = State::Normal;
+    stream_state.is_processing_full_line_comment = false;
+}
+
+fn finish_block_comment(
+    output_segment: &mut String,
+    stream_state: &mut StreamState,
+    filter: &CommentFilter,
+    original_line_num: usize,
+    end_column: usize,
+    ends_line: bool,
+    line_changes: &mut Vec<ChangeInfo>,
+) {
+    let mut text = std::mem::take(&mut stream_state.comment_buffer);
+    let start_line = stream_state.active_block_comment_start_line.unwrap_or(original_line_num);
+    let comment = Comment {
+        text: &text,
+        comment_type: VerboseCommentType::Block,
+        full_line: stream_state.is_processing_full_line_comment,
+        continues: None,
+    };
+
+    let reason = keep_reason(filter, &comment, stream_state, start_line, original_line_num)
+        .or_else(|| filter.keeps_span(original_line_num - start_line + 1).then_some(KeepReason::Length));
+    if let Some(reason) = reason {
+        if let Some(replacement) = stream_state.replacement.take() {
+            text = replacement;
+        } else if filter.transform == Some(Transform::BlockToLine) && ends_line && !reason.is_verbatim() {
+            text = block_to_line(&text, &stream_state.block_comment_indent);
+        }
+        output_segment.push_str(&filter.kept_text(&text));
+    } else {
+        let full = stream_state.change_detail == ChangeDetail::Full;
+        line_changes.push(ChangeInfo {
+            start_line,
+            end_line: original_line_num,
+            comment_type: VerboseCommentType::Block,
+            start_column: stream_state.active_comment_start_column,
+            end_column,
+            text: if full { text.clone() } else { String::new() },
+            text_len: text.len(),
+        });
+        if filter.mark_removals {
+            output_segment.push_str(BLOCK_REMOVAL_MARKER);
+        }
+    }
+
+    stream_state.comment_buffer = text;
+    stream_state.comment_buffer.clear();
+    stream_state.active_block_comment_start_line = None;
+    stream_state.current_parse_state = State::Normal;
+    stream_state.is_processing_full_line_comment = false;
+    stream_state.last_kept_full_line_comment = None;
+    stream_state.last_removed_full_line_comment = None;
+}
+
+struct Columns<'a> {
+    line: &'a str,
+    counted_bytes: usize,
+    counted_chars: usize,
+    enabled: bool,
+}
+
+impl Columns<'_> {
+    fn at(&mut self, index: usize) -> usize {
+        if !self.enabled {
+            return 0;
+        }
+        self.counted_chars += self.line[self.counted_bytes..index].chars().count();
+        self.counted_bytes = index;
+        self.counted_chars + 1
+    }
+}
+
 pub fn process_line_streaming(
     line_content: &str,
     original_line_num: usize,
     stream_state: &mut StreamState,
+    filter: &CommentFilter,
+) -> (String, Vec<ChangeInfo>) {
+    let bytes = line_content.as_bytes();
+    let mut output_segment = String::with_capacity(line_content.len());
+    let mut line_changes = Vec::new();
+    let mut columns = Columns {
+        line: line_content,
+        counted_bytes: 0,
+        counted_chars: 0,
+        enabled: stream_state.change_detail == ChangeDetail::Full,
+    };
+    stream_state.begin_line(line_content);
+
+    let mut index = 0;
+    while index < bytes.len() {
+        let state = stream_state.current_parse_state;
+        let action = lexer::action(state, bytes[index]);
+        if action == Action::Buffer || action == Action::Emit(state) {
+            let mut run = bytes[index..].iter().take_while(|&&b| lexer::action(state, b) == action).count();
+            while !line_content.is_char_boundary(index + run) {
+                run += 1;
+            }
+            let span = &line_content[index..index + run];
+            match action {
+                Action::Buffer => stream_state.comment_buffer.push_str(span),
+                _ => output_segment.push_str(span),
+            }
+            index += run;
+            continue;
+        }
+        let current = line_content[index..].chars().next().unwrap_or_default();
+        index += current.len_utf8();
+        let next = bytes.get(index).copied();
+        match action {
+            Action::Emit(state) => {
+                output_segment.push(current);
+                stream_state.current_parse_state = state;
+            }
+            Action::EmitCode => {
+                stream_state.track_code_char(current);
+                output_segment.push(current);
+            }
+            Action::Buffer => stream_state.comment_buffer.push(current),
+            Action::OpenComment => match next {
+                Some(b'/') => {
+                    index += 1;
+                    if output_segment.trim().is_empty() {
+                        stream_state.comment_prefix = std::mem::take(&mut output_segment);
+                        stream_state.is_processing_full_line_comment = true;
+                    } else {
+                        stream_state.is_processing_full_line_comment = false;
+                    }
+                    stream_state.current_parse_state = State::LineComment;
+                    stream_state.comment_buffer.push_str("//");
+                    stream_state.active_comment_start_column = columns.at(index - 2);
+                }
+                Some(b'*') => {
+                    index += 1;
+                    stream_state.current_parse_state = State::BlockComment;
+                    stream_state.line_touched_block_comment = true;
+                    stream_state.is_processing_full_line_comment = output_segment.trim().is_empty();
+                    stream_state.comment_buffer.push_str("/*");
+                    if stream_state.active_block_comment_start_line.is_none() {
+                        stream_state.active_block_comment_start_line = Some(original_line_num);
+                        let indent = line_content.len() - line_content.trim_start().len();
+                        stream_state.block_comment_indent = line_content[..indent].to_string();
+                        stream_state.active_comment_start_column = columns.at(index - 2);
+                    }
+                }
+                _ => output_segment.push(current),
+            },
+            Action::OpenRawString => {
+                let start = index - 1;
+                let hashes = bytes[index..].iter().take_while(|&&b| b == b'#').count();
+                index += hashes;
+                if bytes.get(index) == Some(&b'"') {
+                    index += 1;
+                    stream_state.raw_string_hash_count = hashes;
+                    stream_state.current_parse_state = State::InRawString;
+                }
+                output_segment.push_str(&line_content[start..index]);
+            }
+            Action::CloseLineComment => finish_line_comment(
+                &mut output_segment,
+                stream_state,
+                filter,
+                original_line_num,
+                &mut line_changes,
+                true,
+            ),
+            Action::CloseBlockComment => {
+                if next == Some(b'/') {
+                    index += 1;
+                    stream_state.comment_buffer.push_str("*/");
+                    finish_block_comment(
+                        &mut output_segment,
+                        stream_state,
+                        filter,
+                        original_line_num,
+                        columns.at(index - 1),
+                        line_content[index..].chars().all(char::is_whitespace),
+                        &mut line_changes,
+                    );
+                } else {
+                    stream_state.comment_buffer.push(current);
+                }
+            }
+            Action::CloseRawString => {
+                output_segment.push(current);
+                let wanted = stream_state.raw_string_hash_count;
+                let hashes = bytes[index..].iter().take(wanted).take_while(|&&b| b == b'#').count();
+                output_segment.push_str(&line_content[index..index + hashes]);
+                index += hashes;
+                if hashes == wanted {
+                    stream_state.current_parse_state = State::Normal;
+                    stream_state.raw_string_hash_count = 0;
+                }
+            }
+        }
+    }
+    if stream_state.current_parse_state == State::LineComment {
+        finish_line_comment(
+            &mut output_segment,
+            stream_state,
+            filter,
+            original_line_num,
+            &mut line_changes,
+            false,
+        );
+    }
+    stream_state.end_line();
+    (output_segment, line_changes)
+}
+
+#[cfg(feature = "legacy-lexer")]
+pub fn process_line_streaming_legacy(
+    line_content: &str,
+    original_line_num: usize,
+    stream_state: &mut StreamState,
+    filter: &CommentFilter,
 ) -> (String, Vec<ChangeInfo>) {
     let mut output_segment = String::with_capacity(line_content.len());
     let mut chars = line_content.chars().peekable();
     let mut line_changes = Vec::new();
+    let line_char_count = line_content.chars().count();
+    stream_state.begin_line(line_content);
 
     while let Some(current_char) = chars.next() {
         match stream_state.current_parse_state {
@@ -66,22 +566,25 @@ pub fn process_line_streaming(
                         if chars.peek() == Some(&'/') {
                             chars.next();
                             if output_segment.trim().is_empty() {
-                                output_segment.clear();
+                                stream_state.comment_prefix = std::mem::take(&mut output_segment);
                                 stream_state.is_processing_full_line_comment = true;
                             } else {
                                 stream_state.is_processing_full_line_comment = false;
                             }
                             stream_state.current_parse_state = State::LineComment;
-                            line_changes.push(ChangeInfo {
-                                start_line: original_line_num,
-                                end_line: original_line_num,
-                                comment_type: VerboseCommentType::Line,
-                            });
+                            stream_state.comment_buffer.push_str("//");
+                            stream_state.active_comment_start_column = line_char_count - chars.clone().count() - 1;
                         } else if chars.peek() == Some(&'*') {
                             chars.next();
                             stream_state.current_parse_state = State::BlockComment;
+                            stream_state.line_touched_block_comment = true;
+                            stream_state.is_processing_full_line_comment = output_segment.trim().is_empty();
+                            stream_state.comment_buffer.push_str("/*");
                             if stream_state.active_block_comment_start_line.is_none() {
                                 stream_state.active_block_comment_start_line = Some(original_line_num);
+                                let indent = line_content.len() - line_content.trim_start().len();
+                                stream_state.block_comment_indent = line_content[..indent].to_string();
+                                stream_state.active_comment_start_column = line_char_count - chars.clone().count() - 1;
                             }
                         } else {
                             output_segment.push(current_char);
@@ -112,31 +615,40 @@ pub fn process_line_streaming(
                         }
                     }
                     _ => {
+                        stream_state.track_code_char(current_char);
                         output_segment.push(current_char);
                     }
                 }
             }
             State::LineComment => {
                 if current_char == '\n' {
-                    if !stream_state.is_processing_full_line_comment {
-                        output_segment.push(current_char);
-                    }
-                    stream_state.current_parse_state = State::Normal;
-                    stream_state.is_processing_full_line_comment = false;
+                    finish_line_comment(
+                        &mut output_segment,
+                        stream_state,
+                        filter,
+                        original_line_num,
+                        &mut line_changes,
+                        true,
+                    );
+                } else {
+                    stream_state.comment_buffer.push(current_char);
                 }
             }
             State::BlockComment => {
                 if current_char == '*' && chars.peek() == Some(&'/') {
                     chars.next();
-                    stream_state.current_parse_state = State::Normal;
-                    if let Some(start_line) = stream_state.active_block_comment_start_line {
-                        line_changes.push(ChangeInfo {
-                            start_line,
-                            end_line: original_line_num,
-                            comment_type: VerboseCommentType::Block,
-                        });
-                        stream_state.active_block_comment_start_line = None;
-                    }
+                    stream_state.comment_buffer.push_str("*/");
+                    finish_block_comment(
+                        &mut output_segment,
+                        stream_state,
+                        filter,
+                        original_line_num,
+                        line_char_count - chars.clone().count(),
+                        chars.clone().all(char::is_whitespace),
+                        &mut line_changes,
+                    );
+                } else {
+                    stream_state.comment_buffer.push(current_char);
                 }
             }
             State::StringLiteral => {
@@ -197,6 +709,903 @@ pub fn process_line_streaming(
             }
         }
     }
+    if stream_state.current_parse_state == State::LineComment {
+        finish_line_comment(
+            &mut output_segment,
+            stream_state,
+            filter,
+            original_line_num,
+            &mut line_changes,
+            false,
+        );
+    }
+    stream_state.end_line();
     (output_segment, line_changes)
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct ScrubSettings {
+    pub header_lines: usize,
+    pub token_model: Option<TokenModel>,
+    pub blank_line_limit: Option<BlankLineLimit>,
+    pub filter: CommentFilter,
+    pub strip_tests: bool,
+    pub line_map: bool,
+    pub change_detail: ChangeDetail,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScrubReport {
+    pub changes: Vec<ChangeInfo>,
+    pub bytes: Tally,
+    pub lines: Tally,
+    pub tokens: Tally,
+    pub unterminated: Option<&'static str>,
+    pub line_map: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScrubbedLine {
+    pub line_number: usize,
+    pub text: String,
+    pub removed: bool,
+    pub changes: Vec<ChangeInfo>,
+}
+
+struct OutputSink {
+    blank_line_filter: Option<BlankLineFilter>,
+    open_line: bool,
+}
+
+impl OutputSink {
+    fn count(&mut self, report: &mut ScrubReport, segment: &str, settings: &ScrubSettings) {
+        if segment.is_empty() {
+            return;
+        }
+        report.bytes.after += segment.len();
+        report.lines.after += segment.matches('\n').count();
+        self.open_line = !segment.ends_with('\n');
+        if let Some(model) = settings.token_model {
+            report.tokens.after += count_tokens(segment, model).unwrap_or(0);
+        }
+    }
+
+    fn emit(&mut self, report: &mut ScrubReport, segment: String, settings: &ScrubSettings) -> String {
+        let emitted = match self.blank_line_filter.as_mut() {
+            Some(filter) => filter.push(&segment),
+            None => segment,
+        };
+        self.count(report, &emitted, settings);
+        emitted
+    }
+
+    fn finish(&mut self, report: &mut ScrubReport, settings: &ScrubSettings) -> String {
+        let rest = match self.blank_line_filter.as_mut() {
+            Some(filter) => filter.finish(),
+            None => String::new(),
+        };
+        self.count(report, &rest, settings);
+        if self.open_line {
+            report.lines.after += 1;
+        }
+        rest
+    }
+}
+
+fn restore_comments(original: &str, changes: &[ChangeInfo], filter: &CommentFilter) -> String {
+    let mut restored = original.to_string();
+    for change in changes.iter().rev() {
+        let Some((start, _)) = original.char_indices().nth(change.start_column - 1) else {
+            continue;
+        };
+        let end = start + change.text.len();
+        if original.get(start..end) == Some(change.text.as_str()) {
+            restored.replace_range(start..end, &filter.kept_text(&change.text));
+        }
+    }
+    restored
+}
+
+#[derive(Debug, Clone)]
+struct PendingLine {
+    line_number: usize,
+    original: String,
+    processed: String,
+    changes: Vec<ChangeInfo>,
+    suppressed: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CommentBlock {
+    lines: Vec<PendingLine>,
+}
+
+impl CommentBlock {
+    fn flush(
+        &mut self,
+        sink: &mut OutputSink,
+        ready: &mut Vec<ScrubbedLine>,
+        report: &mut ScrubReport,
+        settings: &ScrubSettings,
+        next_line: &str,
+    ) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let bodies: Vec<&str> = self.lines.iter().map(|line| line.original.trim()).collect();
+        let keep_block = settings.filter.keeps_block(&bodies, next_line);
+
+        let lines = std::mem::take(&mut self.lines);
+        let mut texts: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                if line.suppressed {
+                    String::new()
+                } else if keep_block {
+                    settings.filter.kept_text(&line.original).into_owned()
+                } else {
+                    line.processed.clone()
+                }
+            })
+            .collect();
+        if let Some(width) = settings.filter.rewrap {
+            rewrap_lines(&mut texts, width);
+        }
+
+        for (line, text) in lines.into_iter().zip(texts) {
+            let changes = if keep_block && !line.suppressed { Vec::new() } else { line.changes };
+            let text = sink.emit(report, text, settings);
+            report.changes.extend(changes.iter().cloned());
+            ready.push(ScrubbedLine {
+                line_number: line.line_number,
+                removed: text.is_empty(),
+                text,
+                changes,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    line: usize,
+    stream_state: StreamState,
+}
+
+impl Checkpoint {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn shift(&mut self, delta: isize) {
+        let shift = |line: usize| line.saturating_add_signed(delta);
+        let state = &mut self.stream_state;
+        self.line = shift(self.line);
+        state.active_block_comment_start_line = state.active_block_comment_start_line.map(shift);
+        state.last_kept_full_line_comment = state.last_kept_full_line_comment.map(|(line, reason)| (shift(line), reason));
+        state.last_removed_full_line_comment = state.last_removed_full_line_comment.map(shift);
+        state.keep_next_line = state.keep_next_line.map(shift);
+    }
+
+    pub fn matches(&self, other: &Checkpoint) -> bool {
+        let mut aligned = other.clone();
+        aligned.shift(self.line as isize - other.line as isize);
+        let state = &mut aligned.stream_state;
+        state.line_in_test_region = self.stream_state.line_in_test_region;
+        state.line_in_macro_region = self.stream_state.line_in_macro_region;
+        state.line_kept_by_pragma = self.stream_state.line_kept_by_pragma;
+        state.line_is_comment_only = self.stream_state.line_is_comment_only;
+        state.line_touched_block_comment = self.stream_state.line_touched_block_comment;
+        aligned.stream_state == self.stream_state
+    }
+}
+
+pub struct Scrubber {
+    settings: ScrubSettings,
+    report: ScrubReport,
+    sink: OutputSink,
+    stream_state: StreamState,
+    pending_block: CommentBlock,
+    pending_attributes: Vec<PendingLine>,
+    leading: Option<Vec<String>>,
+    ready: Vec<ScrubbedLine>,
+    lines_seen: usize,
+    lines_written: usize,
+    token_error: Option<String>,
+}
+
+impl Scrubber {
+    pub fn new(settings: ScrubSettings) -> Self {
+        let filter_reads_changes = settings.filter.remove_redundant || !settings.filter.plugins.is_empty();
+        Scrubber {
+            stream_state: StreamState {
+                change_detail: if filter_reads_changes { ChangeDetail::Full } else { settings.change_detail },
+                ..StreamState::default()
+            },
+            sink: OutputSink {
+                blank_line_filter: settings.blank_line_limit.map(BlankLineFilter::new),
+                open_line: false,
+            },
+            token_error: settings.token_model.and_then(|_| tokens::check_available().err()),
+            settings,
+            report: ScrubReport::default(),
+            pending_block: CommentBlock::default(),
+            pending_attributes: Vec::new(),
+            leading: Some(Vec::new()),
+            ready: Vec::new(),
+            lines_seen: 0,
+            lines_written: 0,
+        }
+    }
+
+    pub fn resume(settings: ScrubSettings, checkpoint: &Checkpoint) -> Self {
+        Scrubber {
+            stream_state: checkpoint.stream_state.clone(),
+            leading: None,
+            lines_seen: checkpoint.line,
+            ..Scrubber::new(settings)
+        }
+    }
+
+    pub fn report(&self) -> &ScrubReport {
+        &self.report
+    }
+
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        let sink_idle = self.sink.blank_line_filter.as_ref().is_none_or(BlankLineFilter::is_idle);
+        (self.leading.is_none() && self.pending_block.lines.is_empty() && sink_idle).then(|| Checkpoint {
+            line: self.lines_seen,
+            stream_state: self.stream_state.clone(),
+        })
+    }
+
+    pub fn scrub_line(&mut self, line: &str) -> Vec<ScrubbedLine> {
+        self.advance(line);
+        std::mem::take(&mut self.ready)
+    }
+
+    pub fn finish_lines(mut self) -> Vec<ScrubbedLine> {
+        self.advance_to_end();
+        self.ready
+    }
+
+    fn advance(&mut self, line: &str) {
+        let Some(leading) = self.leading.as_mut() else {
+            self.advance_line(line);
+            return;
+        };
+        leading.push(line.to_string());
+        if leading.len() == PRAGMA_SCAN_LINES {
+            self.replay_leading();
+        }
+    }
+
+    fn replay_leading(&mut self) {
+        let leading = self.leading.take().unwrap_or_default();
+        self.stream_state.ignore_file = leading.iter().any(|line| is_ignore_file_pragma(line));
+        for line in &leading {
+            self.advance_line(line);
+        }
+    }
+
+    fn advance_line(&mut self, line: &str) {
+        self.lines_seen += 1;
+        self.report.bytes.before += line.len();
+        self.report.lines.before += 1;
+        let line_tokens = self.settings.token_model.map(|model| count_tokens(line, model).unwrap_or(0));
+        self.report.tokens.before += line_tokens.unwrap_or(0);
+
+        if self.lines_seen <= self.settings.header_lines || self.stream_state.ignore_file {
+            self.report.bytes.after += line.len();
+            self.report.lines.after += 1;
+            self.report.tokens.after += line_tokens.unwrap_or(0);
+            self.ready.push(ScrubbedLine {
+                line_number: self.lines_seen,
+                text: line.to_string(),
+                removed: false,
+                changes: Vec::new(),
+            });
+            return;
+        }
+
+        let (processed_segment, line_specific_changes) = process_line_streaming(
+            line,
+            self.lines_seen,
+            &mut self.stream_state,
+            &self.settings.filter,
+        );
+        let suppressed = self.settings.strip_tests && self.stream_state.line_in_test_region && !self.stream_state.pragma_off;
+        let filter = &self.settings.filter;
+        let pending = PendingLine {
+            line_number: self.lines_seen,
+            original: if filter.needs_blocks() || filter.remove_redundant { line.to_string() } else { String::new() },
+            processed: processed_segment,
+            changes: line_specific_changes,
+            suppressed,
+        };
+
+        let comment_only = self.settings.filter.needs_blocks() && self.stream_state.line_is_comment_only;
+        if comment_only && self.pending_attributes.is_empty() {
+            self.pending_block.lines.push(pending);
+            return;
+        }
+        if self.settings.filter.keep_above_pub
+            && !comment_only
+            && !self.pending_block.lines.is_empty()
+            && is_attribute_line(&pending.original)
+        {
+            self.pending_attributes.push(pending);
+            return;
+        }
+        self.flush_pending(&pending.original);
+        if comment_only {
+            self.pending_block.lines.push(pending);
+        } else {
+            self.emit_pending(pending);
+        }
+    }
+
+    fn flush_pending(&mut self, next_line: &str) {
+        self.pending_block.flush(&mut self.sink, &mut self.ready, &mut self.report, &self.settings, next_line);
+        for attribute in std::mem::take(&mut self.pending_attributes) {
+            self.emit_pending(attribute);
+        }
+    }
+
+    fn emit_pending(&mut self, mut pending: PendingLine) {
+        if self.settings.filter.keeps_trailing(&pending.changes, &pending.processed) {
+            pending.processed = restore_comments(&pending.original, &pending.changes, &self.settings.filter);
+            pending.changes.clear();
+        }
+        let text = if pending.suppressed {
+            String::new()
+        } else {
+            self.sink.emit(&mut self.report, std::mem::take(&mut pending.processed), &self.settings)
+        };
+        self.report.changes.extend(pending.changes.iter().cloned());
+        self.ready.push(ScrubbedLine {
+            line_number: pending.line_number,
+            removed: text.is_empty(),
+            text,
+            changes: pending.changes,
+        });
+    }
+
+    fn advance_to_end(&mut self) {
+        if self.leading.is_some() {
+            self.replay_leading();
+        }
+        self.report.unterminated = match self.stream_state.current_parse_state {
+            State::BlockComment => Some("block comment"),
+            State::StringLiteral | State::StringEscape => Some("string literal"),
+            State::InRawString => Some("raw string literal"),
+            _ => None,
+        };
+        self.flush_pending("");
+        let rest = self.sink.finish(&mut self.report, &self.settings);
+        if !rest.is_empty() {
+            self.ready.push(ScrubbedLine {
+                line_number: self.lines_seen,
+                text: rest,
+                removed: false,
+                changes: Vec::new(),
+            });
+        }
+    }
+
+    fn write_ready(&mut self, writer: &mut dyn Write) -> Result<(), String> {
+        for line in self.ready.drain(..) {
+            if self.settings.line_map && !line.text.is_empty() {
+                let mapped = self.lines_written + 1 + line.text.trim_end_matches('\n').matches('\n').count();
+                if self.report.line_map.last().is_none_or(|(original, _)| *original < line.line_number) {
+                    self.report.line_map.push((line.line_number, mapped));
+                }
+                self.lines_written += line.text.matches('\n').count();
+            }
+            let context = if line.line_number <= self.settings.header_lines { "header" } else { "processed" };
+            writer.write_all(line.text.as_bytes())
+                .map_err(|e| format!("Failed to write {} line: {}", context, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn push_line(&mut self, line: &str, writer: &mut dyn Write) -> Result<(), String> {
+        if let Some(e) = &self.token_error {
+            return Err(e.clone());
+        }
+        self.advance(line);
+        self.write_ready(writer)
+    }
+
+    pub fn finish(mut self, writer: &mut dyn Write) -> Result<ScrubReport, String> {
+        if let Some(e) = self.token_error.take() {
+            return Err(e);
+        }
+        self.advance_to_end();
+        self.write_ready(writer)?;
+        Ok(self.report)
+    }
+}
+
+pub fn scrub_stream<R: BufRead>(
+    reader: &mut R,
+    writer: Option<&mut dyn Write>,
+    settings: &ScrubSettings,
+) -> Result<ScrubReport, String> {
+    let mut discard = io::sink();
+    let writer: &mut dyn Write = match writer {
+        Some(writer) => writer,
+        None => &mut discard,
+    };
+    let mut scrubber = Scrubber::new(settings.clone());
+    let mut line_buffer = String::new();
+
+    loop {
+        line_buffer.clear();
+        match reader.read_line(&mut line_buffer) {
+            Ok(0) => break,
+            Ok(_) => scrubber.push_line(&line_buffer, writer)?,
+            Err(e) if scrubber.lines_seen < settings.header_lines => {
+                return Err(format!("Failed to read header line: {}", e))
+            }
+            Err(e) => return Err(format!("Failed to read line for processing: {}", e)),
+        }
+    }
+
+    scrubber.finish(writer)
+}
+
+pub fn scrub_lines<'a, I>(lines: I, settings: &ScrubSettings) -> impl Iterator<Item = ScrubbedLine>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut lines = lines.into_iter();
+    let mut scrubber = Some(Scrubber::new(settings.clone()));
+    let mut ready: VecDeque<ScrubbedLine> = VecDeque::new();
+    std::iter::from_fn(move || loop {
+        if let Some(mut line) = ready.pop_front() {
+            let text = &mut line.text;
+            if text.ends_with('\n') {
+                text.pop();
+                if text.ends_with('\r') {
+                    text.pop();
+                }
+            }
+            return Some(line);
+        }
+        let active = scrubber.as_mut()?;
+        match lines.next() {
+            Some(line) if line.ends_with('\n') => active.advance(line),
+            Some(line) => active.advance(&format!("{}\n", line)),
+            None => {
+                active.advance_to_end();
+                ready.extend(active.ready.drain(..));
+                scrubber = None;
+                continue;
+            }
+        }
+        ready.extend(active.ready.drain(..));
+    })
+}
+
+pub fn scrub_text(text: &str, settings: &ScrubSettings) -> Result<(String, ScrubReport), String> {
+    let mut output = Vec::with_capacity(text.len());
+    let report = scrub_stream(&mut text.as_bytes(), Some(&mut output), settings)?;
+    let output = String::from_utf8(output).map_err(|e| format!("Failed to decode scrubbed output: {}", e))?;
+    Ok((output, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrub_with(input: &str, settings: &ScrubSettings) -> String {
+        scrub_text(input, settings).unwrap().0
+    }
+
+    #[test]
+    fn test_default_removes_doc_comments() {
+        let input = "/// doc\nfn f() {} // trailing\n";
+        assert_eq!(scrub_with(input, &ScrubSettings::default()), "fn f() {} \n");
+    }
+
+    #[test]
+    fn test_keep_doc_preserves_doc_comments() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_doc = true;
+        let input = "    /// doc\n    //! inner\n    // plain\n    /** block */ fn f() {}\n";
+        assert_eq!(scrub_with(input, &settings), "    /// doc\n    //! inner\n    /** block */ fn f() {}\n");
+    }
+
+    #[test]
+    fn test_keep_outer_and_inner_doc_separately() {
+        let input = "//! inner\n/*! inner block */ mod a {}\n/// outer\n/** outer block */ fn f() {}\n";
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_outer_doc = true;
+        assert_eq!(scrub_with(input, &settings), " mod a {}\n/// outer\n/** outer block */ fn f() {}\n");
+        settings.filter.keep_outer_doc = false;
+        settings.filter.keep_inner_doc = true;
+        assert_eq!(scrub_with(input, &settings), "//! inner\n/*! inner block */ mod a {}\n fn f() {}\n");
+    }
+
+    #[test]
+    fn test_keep_safety_preserves_continuation_lines() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_safety = true;
+        let input = "// SAFETY: ptr is valid\n// for the whole call\nunsafe { f() }\n// other\n";
+        assert_eq!(scrub_with(input, &settings), "// SAFETY: ptr is valid\n// for the whole call\nunsafe { f() }\n");
+    }
+
+    #[test]
+    fn test_length_filter_measures_line_comment_blocks() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.max_length = Some(20);
+        let input = "// a long explanation that\n// continues here\nfn f() {}\n// short\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "// a long explanation that\n// continues here\nfn f() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_redundant_keeps_other_comments() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.remove_redundant = true;
+        let input = "/// Counts retries.\nfn f(mut i: u32) {\n    // increment i\n    i += 1;\n    // the caller expects at least one retry\n    i += 1; // increment i\n    let total = i; // in milliseconds\n}\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "/// Counts retries.\nfn f(mut i: u32) {\n    i += 1;\n    // the caller expects at least one retry\n    i += 1; \n    let total = i; // in milliseconds\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_redact_masks_banned_words_in_kept_comments() {
+        let mut banned = crate::banned::BannedWords::default();
+        banned.add("Falcon");
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_doc = true;
+        settings.filter.redact = Some(banned);
+        let input = "/// Falcon entry point.\nfn falcon() {} // Falcon\n";
+        assert_eq!(scrub_with(input, &settings), "/// ****** entry point.\nfn falcon() {} \n");
+    }
+
+    #[test]
+    fn test_transform_rewrites_comment_style() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.transform = Some(Transform::BlockToLine);
+        let input = "    /* first\n       second */\n    let x = 1; /* trailing */\n    f(/* arg */ 2);\n// line\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "    // first\n    // second\n    let x = 1; // trailing\n    f(/* arg */ 2);\n// line\n"
+        );
+        settings.filter.transform = Some(Transform::LineToBlock);
+        assert_eq!(scrub_with("/// doc\nfn f() {} // note\n", &settings), "/** doc */\nfn f() {} /* note */\n");
+    }
+
+    #[test]
+    fn test_rewrap_reflows_kept_doc_comments() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_doc = true;
+        settings.filter.rewrap = Some(30);
+        let input = "/// Returns the sum of all values\n/// in the list.\n// removed\nfn sum() {}\n";
+        assert_eq!(scrub_with(input, &settings), "/// Returns the sum of all\n/// values in the list.\nfn sum() {}\n");
+    }
+
+    #[test]
+    fn test_mark_removals_leaves_one_marker_per_comment() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.mark_removals = true;
+        let input = "    // first\n    // second\nfn f(/* a */ x: u8) {} // note\n/* block\n   comment */\n// last";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "    // …\nfn f(/* … */ x: u8) {} // …\n/* … */\n// …"
+        );
+    }
+
+    #[test]
+    fn test_scrubber_push_line_streams_like_scrub_text() {
+        let input = "use a;\nuse b;\n\nfn a() {} /* spans\n two */ fn b() {}\nfn c() {}\n// tail";
+        let settings = ScrubSettings::default();
+        let mut scrubber = Scrubber::new(settings.clone());
+        let mut output = Vec::new();
+        let mut lines = input.split_inclusive('\n');
+        for line in lines.by_ref().take(PRAGMA_SCAN_LINES) {
+            scrubber.push_line(line, &mut output).unwrap();
+        }
+        assert_eq!(String::from_utf8_lossy(&output), "use a;\nuse b;\n\nfn a() {}  fn b() {}\n");
+        for line in lines {
+            scrubber.push_line(line, &mut output).unwrap();
+        }
+        assert_eq!(scrubber.report().lines.before, 7);
+        let report = scrubber.finish(&mut output).unwrap();
+        let (expected, expected_report) = scrub_text(input, &settings).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+        let spans: Vec<_> = report.changes.iter().map(|change| (change.start_line, change.end_line)).collect();
+        assert_eq!(spans, vec![(4, 5), (7, 7)]);
+        assert_eq!(report.bytes.after, expected_report.bytes.after);
+    }
+
+    #[test]
+    fn test_line_detail_skips_comment_text_and_columns() {
+        let settings = ScrubSettings {
+            change_detail: ChangeDetail::Lines,
+            ..ScrubSettings::default()
+        };
+        let input = "fn a() {} // one\n/* two\n */\n";
+        let (output, report) = scrub_text(input, &settings).unwrap();
+        assert_eq!(output, scrub_text(input, &ScrubSettings::default()).unwrap().0);
+        let lines: Vec<_> = report.changes.iter().map(|change| (change.start_line, change.end_line, change.comment_type)).collect();
+        assert_eq!(lines, vec![(1, 1, VerboseCommentType::Line), (2, 3, VerboseCommentType::Block)]);
+        assert!(report.changes.iter().all(|change| change.text.is_empty() && change.start_column == 0 && change.end_column == 0));
+    }
+
+    #[test]
+    fn test_line_detail_still_captures_text_for_redundant_removal() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.remove_redundant = true;
+        let input = "fn main() {\n    let total = 1; // set total\n    let x = 2; // why two\n}\n";
+        let (full, _) = scrub_text(input, &settings).unwrap();
+        settings.change_detail = ChangeDetail::Lines;
+        let (lines, report) = scrub_text(input, &settings).unwrap();
+        assert_eq!(lines, full);
+        assert!(!report.changes.is_empty() && report.changes.iter().all(|change| !change.text.is_empty()));
+    }
+
+    #[test]
+    fn test_line_map_pairs_original_and_scrubbed_lines() {
+        let settings = ScrubSettings {
+            line_map: true,
+            blank_line_limit: Some(BlankLineLimit(0)),
+            ..ScrubSettings::default()
+        };
+        let input = "// gone\nfn a() {}\n\n/* gone\n   too */\nfn b() {} // kept code\n\n\nfn c() {}\n";
+        let (output, report) = scrub_text(input, &settings).unwrap();
+        assert_eq!(output, "fn a() {}\nfn b() {} \nfn c() {}\n");
+        assert_eq!(report.line_map, vec![(2, 1), (6, 2), (9, 3)]);
+        assert!(scrub_text(input, &ScrubSettings::default()).unwrap().1.line_map.is_empty());
+    }
+
+    #[test]
+    fn test_strip_tests_drops_test_modules() {
+        let settings = ScrubSettings {
+            strip_tests: true,
+            ..ScrubSettings::default()
+        };
+        let input = "fn f() {}\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() { let s = \"}\"; }\n}\nfn g() {}\n";
+        assert_eq!(scrub_with(input, &settings), "fn f() {}\nfn g() {}\n");
+    }
+
+    #[test]
+    fn test_keep_in_tests_preserves_test_comments() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_in_tests = true;
+        settings.filter.transform = Some(Transform::LineToBlock);
+        let input = "fn f() {} // gone
+#[cfg(test)]
+mod tests {
+    // why this matters
+    #[test]
+    fn t() { /* edge */ }
+}
+// gone too
+#[test]
+fn u() {} // kept
+";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "fn f() {} /* gone */
+#[cfg(test)]
+mod tests {
+    // why this matters
+    #[test]
+    fn t() { /* edge */ }
+}
+/* gone too */
+#[test]
+fn u() {} // kept
+"
+        );
+        settings.filter.transform = None;
+        assert_eq!(
+            scrub_with(input, &settings),
+            "fn f() {} 
+#[cfg(test)]
+mod tests {
+    // why this matters
+    #[test]
+    fn t() { /* edge */ }
+}
+#[test]
+fn u() {} // kept
+"
+        );
+    }
+
+    #[test]
+    fn test_keep_in_macros_preserves_macro_rules_bodies() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_in_macros = true;
+        let input = "// gone
+macro_rules! square {
+    // ($x:expr) squares any expression
+    ($x:expr) => {{ $x * $x }}; /* note */
+}
+fn f() {} // gone
+";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "macro_rules! square {
+    // ($x:expr) squares any expression
+    ($x:expr) => {{ $x * $x }}; /* note */
+}
+fn f() {} 
+"
+        );
+    }
+
+    #[test]
+    fn test_keep_above_unsafe_preserves_justifications() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_above_unsafe = true;
+        let input = "// the pointer comes from Box::into_raw\n// and is never freed twice\nlet v = unsafe { *ptr };\n// gone\nlet w = 1;\n// gone too\n\nunsafe impl Send for H {}\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "// the pointer comes from Box::into_raw\n// and is never freed twice\nlet v = unsafe { *ptr };\nlet w = 1;\n\nunsafe impl Send for H {}\n"
+        );
+    }
+
+    #[test]
+    fn test_keep_above_pub_looks_past_attributes() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_above_pub = true;
+        let input = "// Callers must hold the registry lock.\n#[derive(Debug)]\n#[non_exhaustive]\npub struct Entry;\n// gone\n#[derive(Debug)]\nstruct Private;\n// kept\npub(crate) fn f() {}\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "// Callers must hold the registry lock.\n#[derive(Debug)]\n#[non_exhaustive]\npub struct Entry;\n#[derive(Debug)]\nstruct Private;\n// kept\npub(crate) fn f() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_keep_blocks_over_keeps_long_blocks_only() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.keep_blocks_over = Some(3);
+        let input = "// one\nfn f() {} // trailing\n// a\n// b\n// c\n/* two\n   lines */\n/* three\n   whole\n   lines */\n// d\n// e\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "fn f() {} \n// a\n// b\n// c\n\n/* three\n   whole\n   lines */\n"
+        );
+    }
+
+    #[test]
+    fn test_line_ranges_limit_removal() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.line_ranges = vec!["2:3".parse().unwrap(), "6:".parse().unwrap()];
+        settings.filter.transform = Some(Transform::LineToBlock);
+        let input = "// one\nfn a() {} // two\n/* three\n   four */\nfn b() {} // five\n// six\n";
+        assert_eq!(scrub_with(input, &settings), "// one\nfn a() {} /* two */\n/* three\n   four */\nfn b() {} // five\n/* six */\n");
+        settings.filter.transform = None;
+        assert_eq!(scrub_with(input, &settings), "// one\nfn a() {} \n\nfn b() {} // five\n");
+    }
+
+    #[test]
+    fn test_pragmas_exempt_regions_and_files() {
+        let settings = ScrubSettings {
+            strip_tests: true,
+            ..ScrubSettings::default()
+        };
+        let input = "// gone\n// rustscrub:off\n// kept\n#[cfg(test)]\nmod tests {} // kept\n// rustscrub:on\nfn f() {} // gone\n";
+        assert_eq!(
+            scrub_with(input, &settings),
+            "// rustscrub:off\n// kept\n#[cfg(test)]\nmod tests {} // kept\n// rustscrub:on\nfn f() {} \n"
+        );
+        let ignored = "//! Crate docs.\n// rustscrub:ignore-file\n#[cfg(test)]\nmod tests {} // kept\n";
+        assert_eq!(scrub_with(ignored, &settings), ignored);
+        let late = format!("{}// rustscrub:ignore-file\nfn f() {{}} // gone\n", "\n".repeat(PRAGMA_SCAN_LINES));
+        assert!(!scrub_with(&late, &settings).contains("// gone"));
+    }
+
+    #[test]
+    fn test_keep_next_and_keep_line_pragmas() {
+        let input = "// rustscrub:keep-next\n// kept\n// gone\nlet x = 1; /* kept */ // rustscrub:keep-line\nlet y = 2; // gone\n";
+        assert_eq!(
+            scrub_with(input, &ScrubSettings::default()),
+            "// rustscrub:keep-next\n// kept\nlet x = 1; /* kept */ // rustscrub:keep-line\nlet y = 2; \n"
+        );
+    }
+
+    #[derive(Debug)]
+    struct SecretPlugin;
+
+    impl crate::plugin::CommentPlugin for SecretPlugin {
+        fn decide(&self, comment: &crate::plugin::PluginComment) -> Decision {
+            if comment.text.contains("secret") {
+                Decision::Replace { text: "/* redacted */".to_string() }
+            } else if comment.text.contains("SAFETY") {
+                Decision::Keep
+            } else {
+                Decision::Default
+            }
+        }
+    }
+
+    #[test]
+    fn test_plugins_decide_before_filter() {
+        let mut settings = ScrubSettings::default();
+        settings.filter.plugins.push(std::sync::Arc::new(SecretPlugin));
+        let input = "// SAFETY: checked
+let x = 1; // secret key
+/* secret
+   plan */
+// gone
+";
+        assert_eq!(scrub_with(input, &settings), "// SAFETY: checked
+let x = 1; /* redacted */
+/* redacted */
+");
+    }
+
+    #[test]
+    fn test_report_flags_unterminated_literals() {
+        let settings = ScrubSettings::default();
+        let unterminated = |input: &str| scrub_text(input, &settings).unwrap().1.unterminated;
+        assert_eq!(unterminated("fn f<'a>(x: &'a str) -> char { 'x' }
+let s = \"a\nb\";\n"), None);
+        assert_eq!(unterminated("fn f() {}\n/* open\n"), Some("block comment"));
+        assert_eq!(unterminated("let s = \"open\n"), Some("string literal"));
+        assert_eq!(unterminated("let s = r#\"open\"\n"), Some("raw string literal"));
+    }
+
+    #[test]
+    fn test_scrub_lines_maps_output_to_input_lines() {
+        let input = "fn f() {} // note\n// gone\n\nlet s = \"// kept\";";
+        let lines: Vec<ScrubbedLine> = scrub_lines(input.lines(), &ScrubSettings::default()).collect();
+        let texts: Vec<&str> = lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, vec!["fn f() {} ", "", "", "let s = \"// kept\";"]);
+        assert_eq!(lines.iter().map(|line| line.removed).collect::<Vec<_>>(), vec![false, true, false, false]);
+        assert_eq!(lines[0].changes.len(), 1);
+        assert_eq!(lines[1].changes[0].start_line, 2);
+    }
+
+    #[cfg(not(feature = "tokenizer"))]
+    #[test]
+    fn test_token_model_needs_tokenizer_feature() {
+        let settings = ScrubSettings { token_model: Some(TokenModel::default()), ..ScrubSettings::default() };
+        assert!(scrub_text("fn f() {}\n", &settings).unwrap_err().contains("'tokenizer' feature"));
+    }
+
+    #[test]
+    fn test_escaped_non_ascii_characters_in_literals() {
+        let input = "fn a() {}\nlet p = \"C:\\Über\"; // x\nlet c = '\\ü'; /* y */\n";
+        assert_eq!(scrub_with(input, &ScrubSettings::default()), "fn a() {}\nlet p = \"C:\\Über\"; \nlet c = '\\ü'; \n");
+    }
+
+    #[cfg(feature = "legacy-lexer")]
+    #[test]
+    fn test_table_lexer_matches_legacy_lexer() {
+        let mut sources = vec![
+            "let s = \"a \\\" // not\"; // note\n".to_string(),
+            "let r = r##\"/* \"# */\"##; /* multi\n   line */ x /* y */\n".to_string(),
+            "let c = '\\''; let d = '/'; r#type; br\"raw\" // é ü\n".to_string(),
+            "    /// doc ✓\n/**/ /*/ still */ a / b * c\n//! inner".to_string(),
+            "#[cfg(test)]\nmod tests { fn t() { let s = \"}\"; } }\nr".to_string(),
+            "fn a() {}\nlet p = \"C:\\Über\"; // x\nlet c = '\\ü';\n".to_string(),
+        ];
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for entry in std::fs::read_dir(src).unwrap() {
+            sources.push(std::fs::read_to_string(entry.unwrap().path()).unwrap_or_default());
+        }
+        let filter = CommentFilter::default();
+        for source in &sources {
+            let mut table_state = StreamState::default();
+            let mut legacy_state = StreamState::default();
+            for (index, line) in source.split_inclusive('\n').enumerate() {
+                let (table_output, table_changes) = process_line_streaming(line, index + 1, &mut table_state, &filter);
+                let (legacy_output, legacy_changes) =
+                    process_line_streaming_legacy(line, index + 1, &mut legacy_state, &filter);
+                assert_eq!(table_output, legacy_output, "line {}: {:?}", index + 1, line);
+                assert_eq!(format!("{:?}", table_changes), format!("{:?}", legacy_changes), "line {}: {:?}", index + 1, line);
+                assert_eq!(format!("{:?}", table_state), format!("{:?}", legacy_state), "line {}: {:?}", index + 1, line);
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/self_update.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::env;
+use std::fs;
+
+#[cfg(feature = "http")]
+use minisign_verify::{PublicKey, Signature};
+use serde::Deserialize;
+
+use crate::remote::{fetch, DEFAULT_TIMEOUT_SECS};
+use crate::transaction::Transaction;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/vschwaberow/rustscrub/releases/latest";
+const DOWNLOAD_TIMEOUT_SECS: u64 = 300;
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("RUSTSCRUB_RELEASE_PUBLIC_KEY");
+
+#[derive(clap::Args, Debug)]
+pub struct SelfUpdateArgs {
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    check: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> Result<&Asset, String> {
+        self.assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| format!("Release {} has no asset '{}' for this platform.", self.tag_name, name))
+    }
+}
+
+fn version_parts(version: &str) -> [u64; 3] {
+    let mut parts = [0; 3];
+    let numbers = version.trim_start_matches('v').split(['-', '+']).next().unwrap_or_default();
+    for (part, number) in parts.iter_mut().zip(numbers.split('.')) {
+        *part = number.parse().unwrap_or(0);
+    }
+    parts
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    version_parts(latest) > version_parts(current)
+}
+
+fn asset_name() -> String {
+    format!("rustscrub-{}-{}{}", env::consts::ARCH, env::consts::OS, env::consts::EXE_SUFFIX)
+}
+
+#[cfg(feature = "http")]
+fn verify_signature(binary: &[u8], signature: &[u8], public_key: &str, name: &str) -> Result<(), String> {
+    let public_key = PublicKey::from_base64(public_key.trim()).map_err(|e| format!("Invalid release public key: {}", e))?;
+    let signature = Signature::decode(&String::from_utf8_lossy(signature))
+        .map_err(|e| format!("Failed to parse the signature for '{}': {}", name, e))?;
+    public_key
+        .verify(binary, &signature, false)
+        .map_err(|e| format!("Signature verification failed for '{}': {}", name, e))
+}
+
+#[cfg(not(feature = "http"))]
+fn verify_signature(_binary: &[u8], _signature: &[u8], _public_key: &str, _name: &str) -> Result<(), String> {
+    Err("Verifying release signatures requires building rustscrub with the 'http' feature.".to_string())
+}
+
+fn replace_executable(binary: &[u8]) -> Result<(), String> {
+    let executable = env::current_exe().map_err(|e| format!("Failed to locate the running executable: {}", e))?;
+    let permissions = fs::metadata(&executable)
+        .map_err(|e| format!("Failed to read '{}': {}", executable.display(), e))?
+        .permissions();
+    let mut transaction = Transaction::default();
+    let staged = transaction.stage(&executable, false);
+    fs::write(&staged, binary).map_err(|e| format!("Failed to write '{}': {}", staged.display(), e))?;
+    fs::set_permissions(&staged, permissions)
+        .map_err(|e| format!("Failed to set permissions on '{}': {}", staged.display(), e))?;
+    transaction.commit()
+}
+
+pub fn run(args: &SelfUpdateArgs) -> Result<(), String> {
+    let current = env!("CARGO_PKG_VERSION");
+    let body = fetch(LATEST_RELEASE_URL, DEFAULT_TIMEOUT_SECS)?;
+    let release: Release =
+        serde_json::from_slice(&body).map_err(|e| format!("Failed to parse the latest release: {}", e))?;
+    if !is_newer(&release.tag_name, current) {
+        println!("RustScrub: {} is up to date (latest release {}).", current, release.tag_name);
+        return Ok(());
+    }
+    if args.check {
+        println!("RustScrub: {} is available (installed {}). Run `rustscrub self-update` to install it.", release.tag_name, current);
+        return Ok(());
+    }
+    let public_key = RELEASE_PUBLIC_KEY.ok_or_else(|| {
+        "This build has no release signing key; reinstall from a release binary or update with `cargo install`.".to_string()
+    })?;
+    let name = asset_name();
+    let binary = fetch(&release.asset(&name)?.browser_download_url, DOWNLOAD_TIMEOUT_SECS)?;
+    let signature = fetch(&release.asset(&format!("{}.minisig", name))?.browser_download_url, DEFAULT_TIMEOUT_SECS)?;
+    verify_signature(&binary, &signature, public_key, &name)?;
+    replace_executable(&binary)?;
+    println!("RustScrub: Updated from {} to {}.", current, release.tag_name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "http")]
+    const TEST_PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    #[cfg(feature = "http")]
+    const TEST_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==
+";
+
+    #[test]
+    fn test_release_versions() {
+        assert!(is_newer("v0.2.0", "0.1.9"));
+        assert!(is_newer("v1.0.0", "0.9"));
+        assert!(!is_newer("v0.1.0", "0.1.0"));
+        assert!(!is_newer("v0.1.0-rc.1", "0.1.0"));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_verify_signature() {
+        let name = "rustscrub-x86_64-linux";
+        assert!(verify_signature(b"test", TEST_SIGNATURE.as_bytes(), TEST_PUBLIC_KEY, name).is_ok());
+        assert!(verify_signature(b"tampered", TEST_SIGNATURE.as_bytes(), TEST_PUBLIC_KEY, name).is_err());
+        assert!(verify_signature(b"test", b"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08", TEST_PUBLIC_KEY, name).is_err());
+        let other_key = "RWTAPRW2qy9FjsBiMVZfPcQr0cnnnR1MNVBlsgaXbBOkFLcrqbuuJEN0";
+        assert!(verify_signature(b"test", TEST_SIGNATURE.as_bytes(), other_key, name).is_err());
+    }
+}
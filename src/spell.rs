@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/spell.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use rustscrub::filter::CommentFilter;
+use rustscrub::scrub::{scrub_text, ChangeInfo, ScrubSettings};
+
+use crate::status::Failure;
+use crate::walk::{collect_source_files, WalkArgs};
+
+const DEFAULT_DICTIONARIES: [&str; 3] = [
+    "/usr/share/dict/words",
+    "/usr/share/hunspell/en_US.dic",
+    "/usr/share/myspell/en_US.dic",
+];
+
+const CODE_WORDS: [&str; 64] = [
+    "api", "apis", "args", "arg", "async", "bool", "boolean", "buf", "bytecode", "cargo", "clippy", "config",
+    "const", "crate", "crates", "deserialize", "dyn", "enum", "enums", "eof", "fn", "fns", "func", "github",
+    "http", "https", "impl", "impls", "init", "io", "iter", "iterator", "json", "len", "lifetime", "lifetimes",
+    "macro", "macros", "mut", "namespace", "nul", "println", "ptr", "refactor", "regex", "repo", "rustc",
+    "rustdoc", "rustfmt", "serde", "serialize", "stderr", "stdin", "stdout", "struct", "structs", "toml", "trait",
+    "traits", "tuple", "unicode", "unsafe", "usize", "utf",
+];
+
+const MIN_WORD_LENGTH: usize = 3;
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(clap::Args, Debug)]
+pub struct SpellArgs {
+    #[clap(value_parser, required = true)]
+    paths: Vec<String>,
+
+    #[clap(long = "dictionary", value_name = "FILE")]
+    dictionaries: Vec<String>,
+
+    #[clap(long = "words", value_name = "FILE")]
+    word_lists: Vec<String>,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+}
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    pub fn extend<'a>(&mut self, words: impl IntoIterator<Item = &'a str>) {
+        for word in words {
+            let word = word.split('/').next().unwrap_or_default().trim();
+            if !word.is_empty() && !word.starts_with('#') {
+                self.words.insert(word.to_lowercase());
+            }
+        }
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read dictionary '{}': {}", path, e))?;
+        self.extend(text.lines());
+        Ok(())
+    }
+
+    fn has(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        let word = word.strip_suffix("'s").unwrap_or(&word);
+        if self.has(word) {
+            return true;
+        }
+        let stems = [
+            ("ies", "y"),
+            ("ied", "y"),
+            ("es", ""),
+            ("s", ""),
+            ("ed", ""),
+            ("ed", "e"),
+            ("ing", ""),
+            ("ing", "e"),
+            ("ly", ""),
+            ("er", ""),
+            ("ers", ""),
+        ];
+        stems.iter().any(|(suffix, replacement)| {
+            word.strip_suffix(suffix).is_some_and(|stem| {
+                let doubled = stem.len() > 2
+                    && stem.as_bytes()[stem.len() - 1] == stem.as_bytes()[stem.len() - 2]
+                    && self.has(&stem[..stem.len() - 1]);
+                stem.len() >= MIN_WORD_LENGTH - 1 && (self.has(&format!("{}{}", stem, replacement)) || doubled)
+            })
+        })
+    }
+
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let word: Vec<char> = word.to_lowercase().chars().collect();
+        let mut candidates: Vec<(usize, &String)> = self
+            .words
+            .iter()
+            .filter(|candidate| candidate.chars().count().abs_diff(word.len()) <= 2)
+            .filter_map(|candidate| {
+                let distance = edit_distance(&word, &candidate.chars().collect::<Vec<_>>());
+                (distance <= 2).then_some((distance, candidate))
+            })
+            .collect();
+        candidates.sort();
+        candidates.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate.clone()).collect()
+    }
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut before_previous = previous.clone();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+pub fn split_identifier(identifier: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = identifier.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = None;
+    for (index, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphabetic() {
+            if let Some(begin) = start.take() {
+                parts.push(&identifier[begin..offset]);
+            }
+            continue;
+        }
+        let boundary = index > 0 && c.is_uppercase() && {
+            let previous = chars[index - 1].1;
+            let next_is_lower = chars.get(index + 1).is_some_and(|&(_, next)| next.is_lowercase());
+            previous.is_lowercase() || (previous.is_uppercase() && next_is_lower)
+        };
+        match start {
+            Some(begin) if boundary => {
+                parts.push(&identifier[begin..offset]);
+                start = Some(offset);
+            }
+            None => start = Some(offset),
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        parts.push(&identifier[begin..]);
+    }
+    parts
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub line: usize,
+    pub column: usize,
+    pub word: String,
+}
+
+fn comment_words(change: &ChangeInfo) -> Vec<(usize, usize, &str)> {
+    let mut words = Vec::new();
+    let mut line = change.start_line;
+    let mut column = change.start_column;
+    let mut in_code_span = false;
+    let mut token_start: Option<(usize, usize, usize)> = None;
+    let text = &change.text;
+    for (offset, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        let is_token = !c.is_whitespace() && c != '`';
+        match (token_start, is_token) {
+            (None, true) => token_start = Some((offset, line, column)),
+            (Some((start, token_line, token_column)), false) => {
+                if !in_code_span {
+                    words.push((token_line, token_column, &text[start..offset]));
+                }
+                token_start = None;
+            }
+            _ => {}
+        }
+        if c == '`' {
+            in_code_span = !in_code_span;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    words
+}
+
+fn is_checkable(token: &str) -> bool {
+    !token.contains("://")
+        && !token.starts_with("www.")
+        && !token.contains(['_', '@', '/', '\\', '=', '<', '>', '(', ':', '#', '{', '['])
+        && !token.chars().any(|c| c.is_ascii_digit())
+}
+
+pub fn check_source(content: &str, dictionary: &Dictionary) -> Result<Vec<Misspelling>, String> {
+    let settings = ScrubSettings {
+        filter: CommentFilter {
+            keep_directives: false,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    };
+    let (code, report) = scrub_text(content, &settings)?;
+    let identifiers: HashSet<String> = code
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .flat_map(|identifier| std::iter::once(identifier).chain(split_identifier(identifier)))
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut misspellings = Vec::new();
+    for change in &report.changes {
+        for (line, column, raw) in comment_words(change) {
+            let token = raw.trim_matches(|c: char| !c.is_alphanumeric());
+            if !is_checkable(token) || identifiers.contains(&token.to_lowercase()) {
+                continue;
+            }
+            for part in split_identifier(token) {
+                let offset = part.as_ptr() as usize - raw.as_ptr() as usize;
+                let part_column = column + raw[..offset].chars().count();
+                let is_acronym = part.chars().all(|c| c.is_uppercase());
+                if part.chars().count() < MIN_WORD_LENGTH
+                    || is_acronym
+                    || identifiers.contains(&part.to_lowercase())
+                    || dictionary.contains(part)
+                {
+                    continue;
+                }
+                misspellings.push(Misspelling {
+                    line,
+                    column: part_column,
+                    word: part.to_string(),
+                });
+            }
+        }
+    }
+    Ok(misspellings)
+}
+
+pub fn run(args: &SpellArgs) -> Result<(), Failure> {
+    let mut dictionary = Dictionary::default();
+    dictionary.extend(CODE_WORDS);
+    if args.dictionaries.is_empty() {
+        let default = DEFAULT_DICTIONARIES
+            .iter()
+            .find(|path| Path::new(path).is_file())
+            .ok_or("No dictionary found. Pass --dictionary <FILE> (a word list or hunspell .dic file).")?;
+        dictionary.load(default)?;
+    }
+    for path in args.dictionaries.iter().chain(&args.word_lists) {
+        dictionary.load(path)?;
+    }
+
+    let mut total = 0;
+    let paths = collect_source_files(&args.paths, &args.walk)?;
+    for path in &paths {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read input file '{}': {}", path.display(), e))?;
+        for misspelling in check_source(&content, &dictionary)? {
+            let suggestions = dictionary.suggest(&misspelling.word);
+            let hint = if suggestions.is_empty() {
+                String::new()
+            } else {
+                format!(" (did you mean: {})", suggestions.join(", "))
+            };
+            println!(
+                "{}:{}:{}: unknown word '{}'{}",
+                path.display(),
+                misspelling.line,
+                misspelling.column,
+                misspelling.word,
+                hint
+            );
+            total += 1;
+        }
+    }
+    eprintln!("RustScrub Spell: {} unknown words in {} files", total, paths.len());
+    if total > 0 {
+        Err(Failure::changes(format!("Spell check found {} unknown words", total)))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary() -> Dictionary {
+        let mut dictionary = Dictionary::default();
+        dictionary.extend(["the", "parse", "header", "line", "return", "value", "stop", "copy/S"]);
+        dictionary
+    }
+
+    #[test]
+    fn test_split_identifier() {
+        assert_eq!(split_identifier("parseHeaderLines"), vec!["parse", "Header", "Lines"]);
+        assert_eq!(split_identifier("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(split_identifier("max_line_len"), vec!["max", "line", "len"]);
+    }
+
+    #[test]
+    fn test_dictionary_stems_and_suggestions() {
+        let dictionary = dictionary();
+        assert!(dictionary.contains("Lines"));
+        assert!(dictionary.contains("stopped"));
+        assert!(dictionary.contains("copies"));
+        assert!(!dictionary.contains("teh"));
+        assert_eq!(dictionary.suggest("teh"), vec!["the".to_string()]);
+    }
+
+    #[test]
+    fn test_check_source_reports_spans() {
+        let source = "fn frobnicate() {}\n// Parse teh header with `xyzzy`\n    // frobnicate the (valeu)\n";
+        let found = check_source(source, &dictionary()).unwrap();
+        assert_eq!(
+            found,
+            vec![
+                Misspelling { line: 2, column: 10, word: "teh".to_string() },
+                Misspelling { line: 2, column: 21, word: "with".to_string() },
+                Misspelling { line: 3, column: 24, word: "valeu".to_string() },
+            ]
+        );
+    }
+}
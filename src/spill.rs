@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/spill.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::borrow::Cow;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::cleanup;
+
+static SPILL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub fn parse_size(value: &str) -> Result<usize, String> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((index, 'K' | 'k')) => (&value[..index], 1 << 10),
+        Some((index, 'M' | 'm')) => (&value[..index], 1 << 20),
+        Some((index, 'G' | 'g')) => (&value[..index], 1 << 30),
+        _ => (value, 1),
+    };
+    digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .ok_or_else(|| format!("Invalid size '{}': expected a byte count with an optional K, M or G suffix.", value))
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryBudget {
+    limit: Option<usize>,
+    used: AtomicUsize,
+    exceeded: AtomicBool,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: Option<usize>) -> Self {
+        MemoryBudget { limit, ..MemoryBudget::default() }
+    }
+
+    pub fn reserve(&self, bytes: usize) -> bool {
+        let reserved = self
+            .used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                let used = used + bytes;
+                self.limit.is_none_or(|limit| used <= limit).then_some(used)
+            })
+            .is_ok();
+        if !reserved {
+            self.exceeded.store(true, Ordering::SeqCst);
+        }
+        reserved
+    }
+
+    pub fn release(&self, bytes: usize) {
+        let _ = self.used.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| Some(used.saturating_sub(bytes)));
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::SeqCst)
+    }
+}
+
+struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+pub struct SpillBuffer<'a> {
+    budget: &'a MemoryBudget,
+    memory: Vec<u8>,
+    spilled: Option<SpillFile>,
+    len: usize,
+    last_byte: Option<u8>,
+}
+
+impl<'a> SpillBuffer<'a> {
+    pub fn new(budget: &'a MemoryBudget) -> Self {
+        SpillBuffer { budget, memory: Vec::new(), spilled: None, len: 0, last_byte: None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn last_byte(&self) -> Option<u8> {
+        self.last_byte
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        self.spilled.is_some()
+    }
+
+    fn spill(&mut self) -> io::Result<&mut SpillFile> {
+        let path = env::temp_dir().join(format!(
+            "rustscrub-spill-{}-{}",
+            process::id(),
+            SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+        cleanup::remove_on_abort(&path);
+        let mut spilled = SpillFile { path, writer: BufWriter::new(file) };
+        spilled.writer.write_all(&self.memory)?;
+        self.budget.release(self.memory.len());
+        self.memory = Vec::new();
+        Ok(self.spilled.insert(spilled))
+    }
+
+    pub fn reader(&mut self) -> io::Result<Box<dyn Read + '_>> {
+        let len = self.len as u64;
+        let Some(spilled) = self.spilled.as_mut() else {
+            return Ok(Box::new(self.memory.as_slice()));
+        };
+        spilled.writer.flush()?;
+        let file = spilled.writer.get_mut();
+        file.seek(SeekFrom::Start(0))?;
+        Ok(Box::new(file.take(len)))
+    }
+
+    pub fn copy_to(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        io::copy(&mut self.reader()?, writer).map(drop)
+    }
+
+    pub fn text(&mut self) -> Result<Cow<'_, str>, String> {
+        let utf8_error = |e: std::str::Utf8Error| format!("Buffered output is not valid UTF-8: {}", e);
+        if self.spilled.is_none() {
+            return std::str::from_utf8(&self.memory).map(Cow::Borrowed).map_err(utf8_error);
+        }
+        let mut bytes = Vec::with_capacity(self.len);
+        self.reader()
+            .and_then(|mut reader| reader.read_to_end(&mut bytes))
+            .map_err(|e| format!("Failed to read spill file: {}", e))?;
+        String::from_utf8(bytes).map(Cow::Owned).map_err(|e| utf8_error(e.utf8_error()))
+    }
+}
+
+impl Write for SpillBuffer<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(spilled) = self.spilled.as_mut() {
+            spilled.writer.get_mut().seek(SeekFrom::End(0))?;
+        }
+        if self.spilled.is_none() && self.budget.reserve(buf.len()) {
+            self.memory.extend_from_slice(buf);
+        } else {
+            let spilled = match self.spilled.as_mut() {
+                Some(spilled) => spilled,
+                None => self.spill()?,
+            };
+            spilled.writer.write_all(buf)?;
+        }
+        self.len += buf.len();
+        self.last_byte = buf.last().copied().or(self.last_byte);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.spilled.as_mut() {
+            Some(spilled) => spilled.writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for SpillBuffer<'_> {
+    fn drop(&mut self) {
+        self.budget.release(self.memory.len());
+        if let Some(spilled) = self.spilled.take() {
+            drop(spilled.writer);
+            let _ = fs::remove_file(&spilled.path);
+            cleanup::forget(&spilled.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("4K").unwrap(), 4096);
+        assert_eq!(parse_size("2m").unwrap(), 2 * 1024 * 1024);
+        assert!(parse_size("lots").is_err());
+    }
+
+    #[test]
+    fn test_spill_buffer_moves_to_disk_when_budget_is_exceeded() {
+        let budget = MemoryBudget::new(Some(8));
+        let mut small = SpillBuffer::new(&budget);
+        small.write_all(b"fn a(){}").unwrap();
+        assert!(!small.is_spilled());
+
+        let mut large = SpillBuffer::new(&budget);
+        large.write_all(b"fn b() {}\n").unwrap();
+        large.write_all(b"fn c() {}\n").unwrap();
+        assert!(large.is_spilled());
+        assert!(budget.exceeded());
+        assert_eq!(large.len(), 20);
+        assert_eq!(large.last_byte(), Some(b'\n'));
+        assert_eq!(large.text().unwrap(), "fn b() {}\nfn c() {}\n");
+
+        let mut copied = Vec::new();
+        large.copy_to(&mut copied).unwrap();
+        assert_eq!(copied, b"fn b() {}\nfn c() {}\n");
+
+        drop(small);
+        let mut reused = SpillBuffer::new(&budget);
+        reused.write_all(b"fn d(){}").unwrap();
+        assert!(!reused.is_spilled());
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/stale.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::scrub::ChangeInfo;
+
+const MIN_NAME_LENGTH: usize = 3;
+
+const KNOWN_NAMES: [&str; 96] = [
+    "Arc", "AsRef", "BTreeMap", "BTreeSet", "Box", "Clone", "Copy", "Cow", "Debug", "Default", "Display",
+    "Drop", "Err", "File", "From", "GitHub", "HashMap", "HashSet", "Into", "IntoIterator", "Iterator",
+    "JavaScript", "None", "Ok", "Option", "Path", "PathBuf", "PyPI", "Rc", "RefCell", "Result", "Self",
+    "Send", "Sized", "Some", "String", "Sync", "TypeScript", "Vec", "VecDeque", "WebAssembly", "alloc",
+    "assert", "assert_eq", "async", "await", "bool", "break", "char", "const", "continue", "core", "crate",
+    "dyn", "else", "enum", "eprintln", "false", "fmt", "format", "i128", "i16", "i8", "i32", "i64", "impl",
+    "isize", "let", "loop", "macOS", "match", "mod", "move", "mut", "panic", "println", "pub", "return",
+    "self", "static", "std", "str", "struct", "super", "todo", "trait", "true", "type", "u128", "u16",
+    "u32", "u64", "u8", "unsafe", "usize", "vec",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct StaleReference {
+    pub line: usize,
+    pub name: String,
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split("::").flat_map(|segment| segment.split('.'))
+}
+
+fn code_span_name(span: &str) -> Option<&str> {
+    let span = span.trim().trim_end_matches("()").trim_end_matches('!');
+    path_segments(span).all(is_identifier).then_some(span)
+}
+
+fn is_camel_case(word: &str) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    is_identifier(word)
+        && !word.contains('_')
+        && chars.windows(2).any(|pair| pair[0].is_lowercase() && pair[1].is_uppercase())
+}
+
+fn referenced_names(text: &str) -> Vec<(usize, &str)> {
+    let mut names = Vec::new();
+    for (offset, line) in text.split('\n').enumerate() {
+        for (index, part) in line.split('`').enumerate() {
+            if index % 2 == 1 {
+                names.extend(code_span_name(part).map(|name| (offset, name)));
+            } else {
+                names.extend(
+                    part.split_whitespace()
+                        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_'))
+                        .filter(|word| is_camel_case(word))
+                        .map(|word| (offset, word)),
+                );
+            }
+        }
+    }
+    names
+}
+
+pub fn code_identifiers(code: &str) -> HashSet<&str> {
+    code.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+pub fn detect_stale(changes: &[ChangeInfo], identifiers: &HashSet<&str>) -> Vec<StaleReference> {
+    let mut stale = Vec::new();
+    for change in changes {
+        for (offset, name) in referenced_names(&change.text) {
+            let known = path_segments(name).any(|segment| {
+                segment.chars().count() < MIN_NAME_LENGTH
+                    || identifiers.contains(segment)
+                    || KNOWN_NAMES.contains(&segment)
+            });
+            let reference = StaleReference {
+                line: change.start_line + offset,
+                name: name.to_string(),
+            };
+            if !known && !stale.contains(&reference) {
+                stale.push(reference);
+            }
+        }
+    }
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustscrub::filter::CommentFilter;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    #[test]
+    fn test_flags_references_missing_from_code() {
+        let source = "/// Wraps `parse_header` and returns a `HeaderInfo`.\n/// See `Vec::new()`, `old_helper()` and the LegacyParser.\nfn parse_header() -> HeaderInfo { todo!() }\n// run `cargo test` with `--release`\n";
+        let settings = ScrubSettings {
+            filter: CommentFilter {
+                keep_directives: false,
+                ..CommentFilter::default()
+            },
+            ..ScrubSettings::default()
+        };
+        let (code, report) = scrub_text(source, &settings).unwrap();
+        let stale = detect_stale(&report.changes, &code_identifiers(&code));
+        assert_eq!(
+            stale,
+            vec![
+                StaleReference { line: 2, name: "old_helper".to_string() },
+                StaleReference { line: 2, name: "LegacyParser".to_string() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/stats.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::classify::{count_categories, CategoryCounts};
+use rustscrub::filter::CommentFilter;
+use rustscrub::schema;
+use rustscrub::scrub::{scrub_text, ChangeInfo, ScrubSettings};
+
+use crate::classify_report::ClassifyFormat;
+use crate::comment_age;
+use crate::comment_owners;
+use crate::clone::{self, git};
+use crate::walk::{collect_source_files, is_rust_source, WalkArgs};
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    #[clap(value_parser, default_value = ".")]
+    paths: Vec<String>,
+
+    #[clap(long, value_name = "REV1..REV2")]
+    compare: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with = "compare")]
+    age: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["compare", "age"])]
+    owners: bool,
+
+    #[clap(long, value_name = "YEARS", default_value_t = 2, requires = "age")]
+    older_than: u64,
+
+    #[clap(long, value_name = "N", default_value_t = 10, requires = "age")]
+    oldest: usize,
+
+    #[clap(long, value_enum, default_value_t = ClassifyFormat::Table)]
+    format: ClassifyFormat,
+
+    #[clap(flatten)]
+    walk: WalkArgs,
+}
+
+impl StatsArgs {
+    pub fn check_features(&self) -> Result<(), String> {
+        if self.compare.is_some() || self.age || self.owners {
+            clone::check_available()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, JsonSchema)]
+pub struct CommentStats {
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: usize,
+    pub comment_bytes: usize,
+    pub comments: usize,
+    pub counts: CategoryCounts,
+    pub density: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct Revision {
+    rev: String,
+    stats: CommentStats,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Comparison {
+    from: Revision,
+    to: Revision,
+}
+
+fn comment_settings() -> ScrubSettings {
+    ScrubSettings {
+        filter: CommentFilter {
+            keep_directives: false,
+            ..CommentFilter::default()
+        },
+        ..ScrubSettings::default()
+    }
+}
+
+pub fn all_comments(source: &str) -> Result<Vec<ChangeInfo>, String> {
+    Ok(scrub_text(source, &comment_settings())?.1.changes)
+}
+
+impl CommentStats {
+    pub fn add_source(&mut self, source: &str) -> Result<(), String> {
+        let (_, report) = scrub_text(source, &comment_settings())?;
+        self.files += 1;
+        self.lines += report.lines.before;
+        self.bytes += report.bytes.before;
+        self.comment_bytes += report.changes.iter().map(|change| change.text.len()).sum::<usize>();
+        self.comments += report.changes.len();
+        self.counts.add(&count_categories(&report.changes));
+        self.density = if self.bytes == 0 {
+            0.0
+        } else {
+            self.comment_bytes as f64 * 100.0 / self.bytes as f64
+        };
+        Ok(())
+    }
+
+    fn rows(&self) -> [(&'static str, f64); 10] {
+        let c = &self.counts;
+        [
+            ("Files", self.files as f64),
+            ("Lines", self.lines as f64),
+            ("Comments", self.comments as f64),
+            ("Doc", c.doc as f64),
+            ("License", c.license as f64),
+            ("TODO", c.todo as f64),
+            ("Code", c.code as f64),
+            ("Directive", c.directive as f64),
+            ("Prose", c.prose as f64),
+            ("Density %", self.density),
+        ]
+    }
+}
+
+fn format_value(name: &str, value: f64) -> String {
+    if name.ends_with('%') {
+        format!("{:.1}", value)
+    } else {
+        format!("{}", value as usize)
+    }
+}
+
+fn format_change(name: &str, from: f64, to: f64) -> String {
+    let change = to - from;
+    if name.ends_with('%') {
+        format!("{:+.1}", change)
+    } else {
+        format!("{:+}", change as i64)
+    }
+}
+
+pub fn parse_range(range: &str) -> Result<(&str, &str), String> {
+    match range.split_once("..") {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() && !to.starts_with('.') => Ok((from, to)),
+        _ => Err(format!("Invalid revision range '{}'. Expected REV1..REV2.", range)),
+    }
+}
+
+fn revision_stats(rev: &str, paths: &[String]) -> Result<CommentStats, String> {
+    let dir = Path::new(".");
+    let mut args = vec!["ls-tree", "-r", "--name-only", "-z", rev, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let listing = git(&args, dir)?;
+    let mut stats = CommentStats::default();
+    for name in String::from_utf8_lossy(&listing).split('\0').filter(|name| !name.is_empty()) {
+        if !is_rust_source(Path::new(name)) {
+            continue;
+        }
+        let blob = git(&["show", &format!("{}:./{}", rev, name)], dir)?;
+        stats.add_source(&String::from_utf8_lossy(&blob))?;
+    }
+    Ok(stats)
+}
+
+fn print_stats(stats: &CommentStats) {
+    for (name, value) in stats.rows() {
+        println!("{:<10}  {:>10}", name, format_value(name, value));
+    }
+}
+
+fn print_comparison(comparison: &Comparison) {
+    let width = comparison.from.rev.len().max(comparison.to.rev.len()).max(10);
+    println!(
+        "{:<10}  {:>width$}  {:>width$}  {:>8}",
+        "Metric", comparison.from.rev, comparison.to.rev, "Change",
+        width = width
+    );
+    for ((name, from), (_, to)) in comparison.from.stats.rows().into_iter().zip(comparison.to.stats.rows()) {
+        println!(
+            "{:<10}  {:>width$}  {:>width$}  {:>8}",
+            name,
+            format_value(name, from),
+            format_value(name, to),
+            format_change(name, from, to),
+            width = width
+        );
+    }
+}
+
+fn print_json<T: Serialize>(report: &'static str, value: &T) -> Result<(), String> {
+    let json = schema::to_json(report, value).map_err(|e| format!("Failed to serialize statistics: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+pub fn run(args: &StatsArgs) -> Result<(), String> {
+    if let Some(range) = &args.compare {
+        let (from, to) = parse_range(range)?;
+        let comparison = Comparison {
+            from: Revision {
+                rev: from.to_string(),
+                stats: revision_stats(from, &args.paths)?,
+            },
+            to: Revision {
+                rev: to.to_string(),
+                stats: revision_stats(to, &args.paths)?,
+            },
+        };
+        return match args.format {
+            ClassifyFormat::Table => {
+                print_comparison(&comparison);
+                Ok(())
+            }
+            ClassifyFormat::Json => print_json("stats-comparison", &comparison),
+        };
+    }
+
+    if args.owners {
+        let owners = comment_owners::collect(&collect_source_files(&args.paths, &args.walk)?)?;
+        return match args.format {
+            ClassifyFormat::Table => {
+                owners.print();
+                Ok(())
+            }
+            ClassifyFormat::Json => print_json("stats-owners", &owners),
+        };
+    }
+
+    if args.age {
+        let ages = comment_age::collect(&collect_source_files(&args.paths, &args.walk)?, args.older_than, args.oldest)?;
+        return match args.format {
+            ClassifyFormat::Table => {
+                ages.print();
+                Ok(())
+            }
+            ClassifyFormat::Json => print_json("stats-age", &ages),
+        };
+    }
+
+    let mut stats = CommentStats::default();
+    for path in collect_source_files(&args.paths, &args.walk)? {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read input file '{}': {}", path.display(), e))?;
+        stats.add_source(&source)?;
+    }
+    match args.format {
+        ClassifyFormat::Table => {
+            print_stats(&stats);
+            Ok(())
+        }
+        ClassifyFormat::Json => print_json("stats", &stats),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_count_comments_and_density() {
+        let mut stats = CommentStats::default();
+        stats.add_source("/// Adds.\nfn add() {} // TODO: overflow\n").unwrap();
+        stats.add_source("fn sub() {}\n").unwrap();
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.lines, 3);
+        assert_eq!(stats.comments, 2);
+        assert_eq!((stats.counts.doc, stats.counts.todo), (1, 1));
+        assert_eq!(format_value("Density %", stats.density), "50.0");
+        assert_eq!(format_change("TODO", 3.0, 1.0), "-2");
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("v1.0..HEAD").unwrap(), ("v1.0", "HEAD"));
+        assert!(parse_range("HEAD").is_err());
+        assert!(parse_range("a...b").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/status.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::process::ExitCode;
+
+use crate::events::{Event, EventStream};
+use crate::log_file::LogFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Clean = 0,
+    Changes = 1,
+    Usage = 2,
+    Io = 3,
+    Parse = 4,
+    Interrupted = 130,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub status: Status,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn changes(message: String) -> Self {
+        Failure { status: Status::Changes, message }
+    }
+
+    pub fn usage(message: String) -> Self {
+        Failure { status: Status::Usage, message }
+    }
+
+    pub fn parse(message: String) -> Self {
+        Failure { status: Status::Parse, message }
+    }
+
+    pub fn interrupted(message: String) -> Self {
+        Failure { status: Status::Interrupted, message }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Warnings<'a> {
+    pub deny: bool,
+    pub log: Option<&'a LogFile>,
+    pub events: Option<&'a EventStream>,
+}
+
+impl Warnings<'_> {
+    pub fn warn(self, status: Status, message: String) -> Result<(), Failure> {
+        if self.deny {
+            return Err(Failure { status, message });
+        }
+        eprintln!("Warning: {}", message);
+        if let Some(log) = self.log {
+            log.record(&format!("Warning: {}", message))?;
+        }
+        if let Some(events) = self.events {
+            events.emit(&Event::Warning { message: &message })?;
+        }
+        Ok(())
+    }
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Failure { status: Status::Io, message }
+    }
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Failure::from(message.to_string())
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+pub fn exit_code(result: Result<Status, Failure>) -> ExitCode {
+    match result {
+        Ok(status) => status.into(),
+        Err(failure) => {
+            match failure.status {
+                Status::Changes | Status::Interrupted => eprintln!("RustScrub: {}", failure.message),
+                _ => eprintln!("Error: {}", failure.message),
+            }
+            failure.status.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_errors_are_io_failures() {
+        let failure: Failure = "Failed to read input file 'a.rs'".into();
+        assert_eq!(failure.status, Status::Io);
+        assert_eq!(Failure::usage("bad".to_string()).status as u8, 2);
+        assert_eq!(Failure::parse("bad".to_string()).status as u8, 4);
+        assert_eq!(Failure::interrupted("stop".to_string()).status as u8, 130);
+        assert_eq!(exit_code(Ok(Status::Changes)), ExitCode::from(1));
+    }
+
+    #[test]
+    fn test_deny_turns_warnings_into_failures() {
+        assert_eq!(Warnings::default().warn(Status::Parse, "soft".to_string()), Ok(()));
+        let failure = Warnings { deny: true, ..Warnings::default() }.warn(Status::Parse, "soft".to_string()).unwrap_err();
+        assert_eq!(failure, Failure::parse("soft".to_string()));
+    }
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/suffix.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::path::{Path, PathBuf};
+
+pub fn output_path(input: &Path, template: &str) -> Result<PathBuf, String> {
+    let stem = input.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let ext = input.extension().map(|ext| ext.to_string_lossy()).unwrap_or_default();
+    let file_name = if template.contains('{') {
+        template.replace("{stem}", &stem).replace("{ext}", &ext)
+    } else {
+        format!("{}{}", stem, template)
+    };
+    let output = input.with_file_name(file_name);
+    if output == input {
+        return Err(format!(
+            "Suffix template '{}' maps '{}' onto itself.",
+            template,
+            input.display()
+        ));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path_templates() {
+        let input = Path::new("src/lib.rs");
+        assert_eq!(output_path(input, ".scrubbed.rs").unwrap(), Path::new("src/lib.scrubbed.rs"));
+        assert_eq!(output_path(input, "{stem}.clean.{ext}").unwrap(), Path::new("src/lib.clean.rs"));
+        assert!(output_path(input, ".rs").is_err());
+    }
+}
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/summary.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::schema;
+use crate::scrub::{ScrubReport, VerboseCommentType};
+use crate::tokens::TokenModel;
+
+const TOP_FILES: usize = 10;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tally {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl Tally {
+    pub fn saved(&self) -> usize {
+        self.before.saturating_sub(self.after)
+    }
+
+    pub fn saved_percent(&self) -> f64 {
+        if self.before == 0 {
+            0.0
+        } else {
+            self.saved() as f64 * 100.0 / self.before as f64
+        }
+    }
+
+    pub fn add(&mut self, other: &Tally) {
+        self.before += other.before;
+        self.after += other.after;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FileSummary {
+    pub path: String,
+    pub bytes: Tally,
+    pub lines: Tally,
+    pub tokens: Tally,
+}
+
+fn format_tally(label: &str, tally: &Tally) -> String {
+    format!(
+        "- {:<7} {} -> {} ({:.1}% saved)",
+        label, tally.before, tally.after, tally.saved_percent()
+    )
+}
+
+pub fn print_summary(files: &[FileSummary], model: Option<TokenModel>) {
+    let mut total = FileSummary::default();
+    for file in files {
+        total.bytes.add(&file.bytes);
+        total.lines.add(&file.lines);
+        total.tokens.add(&file.tokens);
+    }
+
+    eprintln!("RustScrub Summary ({} files):", files.len());
+    eprintln!("{}", format_tally("Bytes:", &total.bytes));
+    eprintln!("{}", format_tally("Lines:", &total.lines));
+    if let Some(model) = model {
+        eprintln!("{} [{}]", format_tally("Tokens:", &total.tokens), model);
+    }
+
+    let mut ranked: Vec<&FileSummary> = files.iter().collect();
+    ranked.sort_by(|a, b| b.bytes.saved().cmp(&a.bytes.saved()).then(a.path.cmp(&b.path)));
+    ranked.truncate(TOP_FILES);
+
+    let width = ranked.iter().map(|f| f.path.len()).max().unwrap_or(0).max(4);
+    eprintln!("Top {} files by bytes saved:", ranked.len());
+    eprintln!(
+        "  {:<width$}  {:>12}  {:>12}  {:>8}",
+        "File", "Bytes before", "Bytes after", "Saved",
+        width = width
+    );
+    for file in ranked {
+        eprintln!(
+            "  {:<width$}  {:>12}  {:>12}  {:>7.1}%",
+            file.path,
+            file.bytes.before,
+            file.bytes.after,
+            file.bytes.saved_percent(),
+            width = width
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SummaryFormat {
+    Table,
+    Json,
+    #[default]
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileStatus {
+    Scrubbed,
+    WouldScrub,
+    Unchanged,
+}
+
+impl FileStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            FileStatus::Scrubbed => "scrubbed",
+            FileStatus::WouldScrub => "would-scrub",
+            FileStatus::Unchanged => "unchanged",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileRow {
+    pub path: String,
+    pub line_comments: usize,
+    pub block_comments: usize,
+    pub bytes_saved: usize,
+    pub status: FileStatus,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FileSummaryReport<'a> {
+    pub files: &'a [FileRow],
+}
+
+impl FileRow {
+    pub fn new(path: &str, report: &ScrubReport, written: bool) -> Self {
+        let line_comments = report
+            .changes
+            .iter()
+            .filter(|change| change.comment_type == VerboseCommentType::Line)
+            .count();
+        let status = match (report.changes.is_empty(), written) {
+            (true, _) => FileStatus::Unchanged,
+            (false, true) => FileStatus::Scrubbed,
+            (false, false) => FileStatus::WouldScrub,
+        };
+        FileRow {
+            path: path.to_string(),
+            line_comments,
+            block_comments: report.changes.len() - line_comments,
+            bytes_saved: report.bytes.saved(),
+            status,
+        }
+    }
+}
+
+pub fn format_file_table(rows: &[FileRow]) -> String {
+    let width = rows.iter().map(|row| row.path.len()).max().unwrap_or(0).max("Total".len());
+    let mut table = format!(
+        "{:<width$}  {:>13}  {:>14}  {:>11}  {}\n",
+        "File", "Line comments", "Block comments", "Bytes saved", "Status",
+        width = width
+    );
+    for row in rows {
+        table.push_str(&format!(
+            "{:<width$}  {:>13}  {:>14}  {:>11}  {}\n",
+            row.path, row.line_comments, row.block_comments, row.bytes_saved, row.status.as_str(),
+            width = width
+        ));
+    }
+    table.push_str(&format!(
+        "{:<width$}  {:>13}  {:>14}  {:>11}\n",
+        "Total",
+        rows.iter().map(|row| row.line_comments).sum::<usize>(),
+        rows.iter().map(|row| row.block_comments).sum::<usize>(),
+        rows.iter().map(|row| row.bytes_saved).sum::<usize>(),
+        width = width
+    ));
+    table
+}
+
+pub fn print_file_summary(rows: &[FileRow], format: SummaryFormat) -> Result<(), String> {
+    match format {
+        SummaryFormat::Table => eprint!("{}", format_file_table(rows)),
+        SummaryFormat::Json => {
+            let json = schema::to_json("file-summary", &FileSummaryReport { files: rows })
+                .map_err(|e| format!("Failed to serialize file summary: {}", e))?;
+            eprintln!("{}", json);
+        }
+        SummaryFormat::None => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrub::{scrub_text, ScrubSettings};
+
+    #[test]
+    fn test_summary_tallies_bytes_lines_and_tokens() {
+        let settings = ScrubSettings {
+            token_model: cfg!(feature = "tokenizer").then(TokenModel::default),
+            ..ScrubSettings::default()
+        };
+        let (output, report) = scrub_text("// header comment\nfn f() {} // trailing\n", &settings).unwrap();
+        assert_eq!(output, "fn f() {} \n");
+        assert_eq!((report.bytes.before, report.bytes.after, report.bytes.saved()), (40, 11, 29));
+        assert_eq!((report.lines.before, report.lines.after), (2, 1));
+        if cfg!(feature = "tokenizer") {
+            assert!(report.tokens.after > 0 && report.tokens.after < report.tokens.before);
+        }
+        assert_eq!(format_tally("Bytes:", &report.bytes), "- Bytes:  40 -> 11 (72.5% saved)");
+        assert_eq!(Tally::default().saved_percent(), 0.0);
+
+        let mut total = Tally::default();
+        total.add(&report.bytes);
+        total.add(&Tally { before: 60, after: 60 });
+        assert_eq!((total.before, total.after, total.saved()), (100, 71, 29));
+    }
+
+    #[test]
+    fn test_file_table_is_aligned() {
+        let (_, report) = scrub_text("fn f() {} // one\n/* two */\n", &ScrubSettings::default()).unwrap();
+        let (_, clean) = scrub_text("fn g() {}\n", &ScrubSettings::default()).unwrap();
+        let rows = vec![FileRow::new("src/long_name.rs", &report, true), FileRow::new("b.rs", &clean, true)];
+        assert_eq!(rows[0].line_comments, 1);
+        assert_eq!(rows[0].block_comments, 1);
+        assert_eq!(rows[0].status, FileStatus::Scrubbed);
+        assert_eq!(rows[1].status, FileStatus::Unchanged);
+
+        let table = format_file_table(&rows);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("b.rs              "));
+        assert!(lines[3].starts_with("Total"));
+        let status_column = lines[0].find("Status").unwrap();
+        assert_eq!(&lines[1][status_column..], "scrubbed");
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/suppressions.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use rustscrub::fingerprint::normalize;
+
+use crate::glob::Glob;
+
+pub const DEFAULT_SUPPRESSIONS_FILE: &str = "rustscrub-suppressions.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suppression {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl Suppression {
+    fn matches(&self, path: &str, rule: &str, text: &str, fingerprint: &str) -> bool {
+        self.path.as_deref().is_none_or(|pattern| Glob::new(pattern).is_match(path))
+            && self.rule.as_deref().is_none_or(|suppressed| suppressed == rule)
+            && self.fingerprint.as_deref().is_none_or(|suppressed| suppressed == fingerprint)
+            && self.pattern.as_deref().is_none_or(|pattern| normalize(text).contains(pattern))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SuppressionsFile {
+    suppress: Vec<Suppression>,
+}
+
+#[derive(Debug)]
+pub struct Suppressions {
+    path: String,
+    entries: Vec<Suppression>,
+    recorded: Option<Vec<Suppression>>,
+}
+
+impl Suppressions {
+    pub fn open(path: Option<&str>, update: bool) -> Result<Self, String> {
+        let path = path.unwrap_or(DEFAULT_SUPPRESSIONS_FILE);
+        let mut entries = if Path::new(path).is_file() {
+            let text = fs::read_to_string(path).map_err(|e| format!("Failed to read suppressions '{}': {}", path, e))?;
+            let file: SuppressionsFile = toml::from_str(&text).map_err(|e| format!("Failed to parse suppressions '{}': {}", path, e))?;
+            file.suppress
+        } else {
+            Vec::new()
+        };
+        if update {
+            entries.retain(|entry| entry.fingerprint.is_none());
+        }
+        Ok(Suppressions {
+            path: path.to_string(),
+            entries,
+            recorded: update.then(Vec::new),
+        })
+    }
+
+    pub fn is_suppressed(&mut self, path: &str, rule: &str, text: &str, fingerprint: &str) -> bool {
+        if self.entries.iter().any(|entry| entry.matches(path, rule, text, fingerprint)) {
+            return true;
+        }
+        match self.recorded.as_mut() {
+            Some(recorded) => {
+                recorded.push(Suppression {
+                    path: Some(path.to_string()),
+                    rule: Some(rule.to_string()),
+                    fingerprint: Some(fingerprint.to_string()),
+                    pattern: None,
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        let Some(mut recorded) = self.recorded else {
+            return Ok(());
+        };
+        recorded.sort();
+        recorded.dedup();
+        let count = recorded.len();
+        let mut suppress = self.entries;
+        suppress.extend(recorded);
+        let text = toml::to_string(&SuppressionsFile { suppress }).map_err(|e| format!("Failed to serialize suppressions: {}", e))?;
+        fs::write(&self.path, text).map_err(|e| format!("Failed to write suppressions '{}': {}", self.path, e))?;
+        eprintln!("RustScrub: Recorded {} suppressed findings in '{}'", count, self.path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suppressions_match_fingerprints_and_patterns() {
+        let path = std::env::temp_dir().join(format!("rustscrub-suppressions-{}.toml", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        fs::write(
+            &path,
+            "[[suppress]]\npath = \"src/legacy/**\"\npattern = \"HACK\"\n\n[[suppress]]\npath = \"src/a.rs\"\nrule = \"comment\"\nfingerprint = \"0123456789abcdef\"\n",
+        )
+        .unwrap();
+
+        let mut suppressions = Suppressions::open(Some(&path), false).unwrap();
+        assert!(suppressions.is_suppressed("src/legacy/old.rs", "comment", "//  HACK: retry", "ffffffffffffffff"));
+        assert!(!suppressions.is_suppressed("src/new.rs", "comment", "// HACK: retry", "ffffffffffffffff"));
+        assert!(suppressions.is_suppressed("src/a.rs", "comment", "// anything", "0123456789abcdef"));
+        assert!(!suppressions.is_suppressed("src/a.rs", "todo-max-age", "// anything", "0123456789abcdef"));
+        suppressions.finish().unwrap();
+
+        let mut updating = Suppressions::open(Some(&path), true).unwrap();
+        assert!(updating.is_suppressed("src/b.rs", "comment", "// new", "1111111111111111"));
+        updating.finish().unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("pattern = \"HACK\""));
+        assert!(text.contains("1111111111111111"));
+        assert!(!text.contains("0123456789abcdef"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/timings.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+fn count_allocation() {
+    if COUNTING.load(Ordering::Relaxed) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+pub fn count_allocations() {
+    COUNTING.store(true, Ordering::Relaxed);
+}
+
+pub fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone)]
+pub struct FileTiming {
+    pub path: String,
+    pub elapsed: Duration,
+    pub bytes: usize,
+    pub allocations: usize,
+}
+
+fn throughput(bytes: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs == 0.0 {
+        0.0
+    } else {
+        bytes as f64 / secs / (1024.0 * 1024.0)
+    }
+}
+
+pub fn format_timings(timings: &[FileTiming], slowest: usize) -> String {
+    let mut sorted: Vec<&FileTiming> = timings.iter().collect();
+    sorted.sort_by_key(|timing| Reverse(timing.elapsed));
+    let shown = slowest.min(sorted.len());
+    let mut text = format!("RustScrub Timings (slowest {} of {} files):\n", shown, timings.len());
+    for timing in &sorted[..shown] {
+        text.push_str(&format!(
+            "- {}: {:.3} ms, {:.1} MiB/s, {} allocations\n",
+            timing.path,
+            timing.elapsed.as_secs_f64() * 1000.0,
+            throughput(timing.bytes, timing.elapsed),
+            timing.allocations
+        ));
+    }
+    let elapsed: Duration = timings.iter().map(|timing| timing.elapsed).sum();
+    let bytes: usize = timings.iter().map(|timing| timing.bytes).sum();
+    text.push_str(&format!(
+        "- Total: {:.3} ms, {:.1} MiB/s, {} allocations\n",
+        elapsed.as_secs_f64() * 1000.0,
+        throughput(bytes, elapsed),
+        timings.iter().map(|timing| timing.allocations).sum::<usize>()
+    ));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings_list_slowest_files_first() {
+        let timing = |path: &str, millis: u64| FileTiming {
+            path: path.to_string(),
+            elapsed: Duration::from_millis(millis),
+            bytes: 1024 * 1024,
+            allocations: 10,
+        };
+        let text = format_timings(&[timing("a.rs", 1), timing("b.rs", 500), timing("c.rs", 250)], 2);
+        assert_eq!(
+            text,
+            "RustScrub Timings (slowest 2 of 3 files):\n\
+             - b.rs: 500.000 ms, 2.0 MiB/s, 10 allocations\n\
+             - c.rs: 250.000 ms, 4.0 MiB/s, 10 allocations\n\
+             - Total: 751.000 ms, 4.0 MiB/s, 30 allocations\n"
+        );
+    }
+
+    #[test]
+    fn test_allocations_are_counted() {
+        count_allocations();
+        let before = allocations();
+        let buffer: Vec<u64> = Vec::with_capacity(16);
+        assert!(allocations() > before);
+        drop(buffer);
+    }
+}
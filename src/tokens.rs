@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/tokens.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenModel {
+    #[default]
+    Cl100k,
+    O200k,
+    P50k,
+}
+
+impl TokenModel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenModel::Cl100k => "cl100k",
+            TokenModel::O200k => "o200k",
+            TokenModel::P50k => "p50k",
+        }
+    }
+
+    #[cfg(feature = "tokenizer")]
+    fn encoding(&self) -> &'static tiktoken_rs::CoreBPE {
+        match self {
+            TokenModel::Cl100k => tiktoken_rs::cl100k_base_singleton(),
+            TokenModel::O200k => tiktoken_rs::o200k_base_singleton(),
+            TokenModel::P50k => tiktoken_rs::p50k_base_singleton(),
+        }
+    }
+}
+
+impl fmt::Display for TokenModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TokenModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cl100k" | "cl100k_base" | "gpt-4" | "gpt-3.5-turbo" => Ok(TokenModel::Cl100k),
+            "o200k" | "o200k_base" | "gpt-4o" => Ok(TokenModel::O200k),
+            "p50k" | "p50k_base" | "codex" => Ok(TokenModel::P50k),
+            _ => Err(format!(
+                "Unknown token model '{}'. Expected one of: cl100k, o200k, p50k.",
+                s
+            )),
+        }
+    }
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "tokenizer") {
+        Ok(())
+    } else {
+        Err("Token counting requires building rustscrub with the 'tokenizer' feature.".to_string())
+    }
+}
+
+#[cfg(not(feature = "tokenizer"))]
+pub fn count_tokens(_text: &str, _model: TokenModel) -> Result<usize, String> {
+    check_available().map(|_| 0)
+}
+
+#[cfg(feature = "tokenizer")]
+pub fn count_tokens(text: &str, model: TokenModel) -> Result<usize, String> {
+    Ok(model.encoding().encode_ordinary(text).len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "tokenizer")]
+    #[test]
+    fn test_counts_match_the_bpe_encodings() {
+        assert_eq!(count_tokens("", TokenModel::Cl100k), Ok(0));
+        assert_eq!(count_tokens("let x", TokenModel::Cl100k), Ok(2));
+        assert_eq!(count_tokens("don't", TokenModel::Cl100k), Ok(2));
+        assert_eq!(count_tokens("hello world", TokenModel::O200k), Ok(2));
+        assert_eq!(count_tokens("    ", TokenModel::P50k), Ok(1));
+        assert_eq!(count_tokens("fn process_line_streaming() {}", TokenModel::Cl100k), Ok(7));
+    }
+
+    #[test]
+    fn test_model_parsing() {
+        assert_eq!("o200k".parse::<TokenModel>(), Ok(TokenModel::O200k));
+        assert!("unknown".parse::<TokenModel>().is_err());
+    }
+
+    #[cfg(not(feature = "tokenizer"))]
+    #[test]
+    fn test_token_counting_needs_tokenizer_feature() {
+        assert!(check_available().unwrap_err().contains("'tokenizer' feature"));
+        assert_eq!(count_tokens("let x", TokenModel::Cl100k), check_available().map(|_| 0));
+    }
+}
@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/transaction.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustscrub::cleanup;
+
+struct Staged {
+    path: PathBuf,
+    target: PathBuf,
+    #[cfg_attr(not(feature = "verify"), allow(dead_code))]
+    compressed: bool,
+}
+
+#[derive(Default)]
+pub struct Transaction {
+    staged: Vec<Staged>,
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}", name, suffix))
+}
+
+#[cfg(feature = "verify")]
+fn verify_file(path: &Path, target: &Path) -> Result<(), String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read staged output for '{}': {}", target.display(), e))?;
+    syn::parse_file(&text)
+        .map(drop)
+        .map_err(|e| format!("Failed to parse scrubbed output for '{}': {}", target.display(), e))
+}
+
+pub fn check_available() -> Result<(), String> {
+    if cfg!(feature = "verify") {
+        Ok(())
+    } else {
+        Err("--verify requires building rustscrub with the 'verify' feature.".to_string())
+    }
+}
+
+impl Transaction {
+    pub fn stage(&mut self, target: &Path, compressed: bool) -> PathBuf {
+        let path = sibling(target, "rustscrub-tmp");
+        cleanup::remove_on_abort(&path);
+        self.staged.push(Staged {
+            path: path.clone(),
+            target: target.to_path_buf(),
+            compressed,
+        });
+        path
+    }
+
+    #[cfg(feature = "verify")]
+    pub fn verify(&self) -> Result<(), String> {
+        self.staged
+            .iter()
+            .filter(|staged| !staged.compressed)
+            .try_for_each(|staged| verify_file(&staged.path, &staged.target))
+    }
+
+    #[cfg(not(feature = "verify"))]
+    pub fn verify(&self) -> Result<(), String> {
+        check_available()
+    }
+
+    pub fn commit(mut self) -> Result<(), String> {
+        let staged = std::mem::take(&mut self.staged);
+        let mut backups: Vec<(PathBuf, PathBuf)> = Vec::new();
+        let mut replaced: Vec<PathBuf> = Vec::new();
+        let result = staged.iter().try_for_each(|Staged { path, target, .. }| {
+            if target.exists() {
+                let backup = sibling(target, "rustscrub-bak");
+                fs::rename(target, &backup)
+                    .map_err(|e| format!("Failed to back up '{}': {}", target.display(), e))?;
+                cleanup::restore_on_abort(&backup, target);
+                backups.push((backup, target.clone()));
+            }
+            fs::rename(path, target).map_err(|e| format!("Failed to replace '{}': {}", target.display(), e))?;
+            cleanup::forget(path);
+            replaced.push(target.clone());
+            Ok(())
+        });
+        match result {
+            Ok(()) => {
+                for (backup, _) in &backups {
+                    cleanup::forget(backup);
+                    let _ = fs::remove_file(backup);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                for target in &replaced {
+                    let _ = fs::remove_file(target);
+                }
+                for (backup, target) in &backups {
+                    let _ = fs::rename(backup, target);
+                    cleanup::forget(backup);
+                }
+                for staged in &staged {
+                    let _ = fs::remove_file(&staged.path);
+                    cleanup::forget(&staged.path);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        for staged in &self.staged {
+            let _ = fs::remove_file(&staged.path);
+            cleanup::forget(&staged.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_commits_or_rolls_back() {
+        let dir = std::env::temp_dir().join(format!("rustscrub-transaction-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.clean.rs"), dir.join("b.clean.rs"));
+        fs::write(&a, "old a").unwrap();
+
+        let mut transaction = Transaction::default();
+        fs::write(transaction.stage(&a, false), "new a").unwrap();
+        fs::write(transaction.stage(&b, false), "new b").unwrap();
+        drop(transaction);
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old a");
+        assert!(!b.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let mut transaction = Transaction::default();
+        fs::write(transaction.stage(&a, false), "new a").unwrap();
+        fs::write(transaction.stage(&b, false), "new b").unwrap();
+        transaction.commit().unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "new a");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "new b");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/transform.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transform {
+    BlockToLine,
+    LineToBlock,
+}
+
+fn split_block(text: &str) -> (&str, &str) {
+    let (marker, inner) = if text.starts_with("/**") && !text.starts_with("/***") && text != "/**/" {
+        ("///", &text[3..])
+    } else if let Some(inner) = text.strip_prefix("/*!") {
+        ("//!", inner)
+    } else {
+        ("//", text.strip_prefix("/*").unwrap_or(text))
+    };
+    (marker, inner.strip_suffix("*/").unwrap_or(inner))
+}
+
+fn indent_width(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+pub fn block_to_line(text: &str, indent: &str) -> String {
+    let (marker, inner) = split_block(text);
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines: Vec<&str> = inner.split('\n').map(|line| line.trim_end()).collect();
+    let continuation = &lines[1..];
+    let starred = continuation
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| line.trim_start().starts_with('*'));
+    let continuation: Vec<&str> = continuation
+        .iter()
+        .map(|line| if starred { line.trim_start().strip_prefix('*').unwrap_or(line.trim_start()) } else { line })
+        .collect();
+    let dedent = continuation
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_width(line))
+        .min()
+        .unwrap_or(0);
+
+    let mut bodies = vec![lines[0].trim()];
+    bodies.extend(continuation.iter().map(|line| line.get(dedent..).unwrap_or("")));
+    while bodies.len() > 1 && bodies.first().is_some_and(|body| body.is_empty()) {
+        bodies.remove(0);
+    }
+    while bodies.len() > 1 && bodies.last().is_some_and(|body| body.is_empty()) {
+        bodies.pop();
+    }
+    bodies
+        .iter()
+        .map(|body| if body.is_empty() { marker.to_string() } else { format!("{} {}", marker, body) })
+        .collect::<Vec<_>>()
+        .join(&format!("{}{}", newline, indent))
+}
+
+pub fn line_to_block(text: &str) -> Option<String> {
+    let (open, body) = if text.starts_with("///") && !text.starts_with("////") {
+        ("/**", &text[3..])
+    } else if let Some(body) = text.strip_prefix("//!") {
+        ("/*!", body)
+    } else {
+        ("/*", text.strip_prefix("//").unwrap_or(text))
+    };
+    if body.contains("/*") || body.contains("*/") {
+        return None;
+    }
+    let body = body.trim();
+    Some(if body.is_empty() { format!("{} */", open) } else { format!("{} {} */", open, body) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_to_line() {
+        assert_eq!(block_to_line("/* note */", ""), "// note");
+        assert_eq!(block_to_line("/**\n     * Adds one.\n     *\n     *     add(1);\n     */", "    "), "/// Adds one.\n    ///\n    ///     add(1);");
+        assert_eq!(block_to_line("/*! crate docs\n    second line */", ""), "//! crate docs\n//! second line");
+    }
+
+    #[test]
+    fn test_line_to_block() {
+        assert_eq!(line_to_block("// note").as_deref(), Some("/* note */"));
+        assert_eq!(line_to_block("/// Adds one.").as_deref(), Some("/** Adds one. */"));
+        assert_eq!(line_to_block("//!").as_deref(), Some("/*! */"));
+        assert_eq!(line_to_block("// glob: *.rs */"), None);
+    }
+}
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/trash.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::cell::{Cell, RefCell};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{self, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const TRASH_DIR: &str = ".rustscrub-trash";
+
+const MANIFEST: &str = "manifest.txt";
+
+#[derive(clap::Args, Debug)]
+pub struct UndoArgs {
+    #[clap(value_parser)]
+    run_id: Option<String>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with = "run_id")]
+    list: bool,
+}
+
+pub struct Trash {
+    dir: PathBuf,
+    run_id: String,
+    manifest: RefCell<Option<File>>,
+    count: Cell<usize>,
+}
+
+impl Trash {
+    pub fn new(root: &Path) -> Result<Self, String> {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Failed to read the system time: {}", e))?
+            .as_secs();
+        let run_id = format!("{}-{}", secs, std::process::id());
+        Ok(Trash {
+            dir: root.join(&run_id),
+            run_id,
+            manifest: RefCell::new(None),
+            count: Cell::new(0),
+        })
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.get()
+    }
+
+    pub fn preserve(&self, path: &Path) -> Result<(), String> {
+        if !path.is_file() {
+            return Ok(());
+        }
+        let original = path::absolute(path).map_err(|e| format!("Failed to resolve '{}': {}", path.display(), e))?;
+        let mut manifest = self.manifest.borrow_mut();
+        if manifest.is_none() {
+            fs::create_dir_all(&self.dir)
+                .map_err(|e| format!("Failed to create trash directory '{}': {}", self.dir.display(), e))?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(MANIFEST))
+                .map_err(|e| format!("Failed to create trash manifest in '{}': {}", self.dir.display(), e))?;
+            *manifest = Some(file);
+        }
+        let index = self.count.get();
+        fs::copy(path, self.dir.join(index.to_string()))
+            .map_err(|e| format!("Failed to move '{}' to the trash: {}", path.display(), e))?;
+        let manifest = manifest.as_mut().unwrap();
+        writeln!(manifest, "{}\t{}", index, original.display())
+            .and_then(|_| manifest.sync_data())
+            .map_err(|e| format!("Failed to write trash manifest in '{}': {}", self.dir.display(), e))?;
+        self.count.set(index + 1);
+        Ok(())
+    }
+}
+
+fn runs(root: &Path) -> Result<Vec<String>, String> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read trash directory '{}': {}", root.display(), e)),
+    };
+    let mut runs: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join(MANIFEST).is_file())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    runs.sort_by_key(|run| {
+        let (secs, pid) = run.split_once('-').unwrap_or((run, ""));
+        (secs.parse::<u64>().unwrap_or(0), pid.parse::<u32>().unwrap_or(0))
+    });
+    Ok(runs)
+}
+
+pub fn restore(root: &Path, run_id: &str) -> Result<usize, String> {
+    let dir = root.join(run_id);
+    let manifest = fs::read_to_string(dir.join(MANIFEST))
+        .map_err(|e| format!("Failed to read trash run '{}': {}", run_id, e))?;
+    let mut restored = 0;
+    for line in manifest.lines().rev() {
+        let (index, original) = line
+            .split_once('\t')
+            .ok_or_else(|| format!("Failed to read trash run '{}': malformed manifest line '{}'", run_id, line))?;
+        fs::copy(dir.join(index), original).map_err(|e| format!("Failed to restore '{}': {}", original, e))?;
+        restored += 1;
+    }
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove trash run '{}': {}", dir.display(), e))?;
+    Ok(restored)
+}
+
+pub fn run(args: &UndoArgs) -> Result<(), String> {
+    let root = Path::new(TRASH_DIR);
+    let runs = runs(root)?;
+    if args.list {
+        for run in &runs {
+            let count = fs::read_to_string(root.join(run).join(MANIFEST)).map(|m| m.lines().count()).unwrap_or(0);
+            println!("{}\t{} files", run, count);
+        }
+        return Ok(());
+    }
+    let run_id = match &args.run_id {
+        Some(run_id) => run_id.clone(),
+        None => runs.last().cloned().ok_or_else(|| format!("No runs to undo in '{}'.", TRASH_DIR))?,
+    };
+    let restored = restore(root, &run_id)?;
+    eprintln!("RustScrub: Restored {} files from run {}", restored, run_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_preserves_and_restores_outputs() {
+        let dir = std::env::temp_dir().join(format!("rustscrub-trash-{}", std::process::id()));
+        let root = dir.join(TRASH_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("a.clean.rs");
+        fs::write(&output, "old").unwrap();
+
+        let trash = Trash::new(&root).unwrap();
+        trash.preserve(&output).unwrap();
+        trash.preserve(&dir.join("missing.rs")).unwrap();
+        assert_eq!(trash.count(), 1);
+        fs::write(&output, "new").unwrap();
+
+        assert_eq!(runs(&root).unwrap(), vec![trash.run_id().to_string()]);
+        assert_eq!(restore(&root, trash.run_id()).unwrap(), 1);
+        assert_eq!(fs::read_to_string(&output).unwrap(), "old");
+        assert!(runs(&root).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
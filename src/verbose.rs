@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/verbose.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use rustscrub::fingerprint::fingerprints;
+use rustscrub::schema;
+use rustscrub::scrub::{ChangeInfo, ScrubReport, VerboseCommentType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum VerboseFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct VerboseChange {
+    #[schemars(extend("enum" = ["line", "block"]))]
+    pub kind: &'static str,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct VerboseFile {
+    pub path: String,
+    pub line_comments: usize,
+    pub block_comments: usize,
+    pub changes: Vec<VerboseChange>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct VerboseReport {
+    pub files: Vec<VerboseFile>,
+    pub line_comments: usize,
+    pub block_comments: usize,
+}
+
+fn count(changes: &[(&ChangeInfo, String)], comment_type: VerboseCommentType) -> usize {
+    changes.iter().filter(|(change, _)| change.comment_type == comment_type).count()
+}
+
+impl VerboseReport {
+    pub fn new(reports: &[(String, ScrubReport)]) -> Self {
+        let mut files: Vec<VerboseFile> = reports
+            .iter()
+            .map(|(path, report)| {
+                let fingerprints = fingerprints(report.changes.iter().map(|change| change.text.as_str()));
+                let mut changes: Vec<(&ChangeInfo, String)> = report.changes.iter().zip(fingerprints).collect();
+                changes.sort_by_key(|(change, _)| (change.start_line, change.start_column));
+                VerboseFile {
+                    path: path.clone(),
+                    line_comments: count(&changes, VerboseCommentType::Line),
+                    block_comments: count(&changes, VerboseCommentType::Block),
+                    changes: changes
+                        .into_iter()
+                        .map(|(change, fingerprint)| VerboseChange {
+                            kind: match change.comment_type {
+                                VerboseCommentType::Line => "line",
+                                VerboseCommentType::Block => "block",
+                            },
+                            start_line: change.start_line,
+                            end_line: change.end_line,
+                            fingerprint,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        VerboseReport {
+            line_comments: files.iter().map(|file| file.line_comments).sum(),
+            block_comments: files.iter().map(|file| file.block_comments).sum(),
+            files,
+        }
+    }
+
+    pub fn format_text(&self) -> String {
+        if self.line_comments + self.block_comments == 0 {
+            return "RustScrub: No comments found to remove in the processed section (Verbose Mode).\n".to_string();
+        }
+        let grouped = self.files.len() > 1;
+        let mut text = String::from("RustScrub: Comments Removed (Verbose Mode):\n");
+        for file in self.files.iter().filter(|file| !file.changes.is_empty()) {
+            if grouped {
+                text.push_str(&format!("{}:\n", file.path));
+            }
+            for change in &file.changes {
+                let lines = if change.start_line == change.end_line {
+                    format!("Line {}", change.start_line)
+                } else {
+                    format!("Lines {}-{}", change.start_line, change.end_line)
+                };
+                text.push_str(&format!("- {}: Removed {} comment.\n", lines, change.kind));
+            }
+            if grouped {
+                text.push_str(&format!(
+                    "  Subtotal: {} line comments, {} block comments\n",
+                    file.line_comments, file.block_comments
+                ));
+            }
+        }
+        text.push_str("---\nRustScrub Statistics:\n");
+        text.push_str(&format!("- Total line comments removed: {}\n", self.line_comments));
+        text.push_str(&format!("- Total block comments removed: {}\n", self.block_comments));
+        text.push_str("---\n");
+        text
+    }
+}
+
+pub fn print_verbose(reports: &[(String, ScrubReport)], format: VerboseFormat) -> Result<(), String> {
+    let report = VerboseReport::new(reports);
+    match format {
+        VerboseFormat::Text => eprint!("{}", report.format_text()),
+        VerboseFormat::Json => {
+            let json = schema::to_json("verbose", &report).map_err(|e| format!("Failed to serialize verbose report: {}", e))?;
+            eprintln!("{}", json);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustscrub::scrub::{scrub_text, ScrubSettings};
+
+    fn report(path: &str, text: &str) -> (String, ScrubReport) {
+        (path.to_string(), scrub_text(text, &ScrubSettings::default()).unwrap().1)
+    }
+
+    #[test]
+    fn test_verbose_groups_files_in_path_order() {
+        let reports = vec![report("b.rs", "fn f() {} // one\n/* two\n */\n"), report("a.rs", "// three\n"), report("c.rs", "fn g() {}\n")];
+        let verbose = VerboseReport::new(&reports);
+        let paths: Vec<&str> = verbose.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.rs", "b.rs", "c.rs"]);
+        assert_eq!(
+            verbose.format_text(),
+            "RustScrub: Comments Removed (Verbose Mode):\n\
+             a.rs:\n\
+             - Line 1: Removed line comment.\n  Subtotal: 1 line comments, 0 block comments\n\
+             b.rs:\n\
+             - Line 1: Removed line comment.\n\
+             - Lines 2-3: Removed block comment.\n  Subtotal: 1 line comments, 1 block comments\n\
+             ---\nRustScrub Statistics:\n\
+             - Total line comments removed: 2\n\
+             - Total block comments removed: 1\n---\n"
+        );
+        let single = VerboseReport::new(&reports[1..2]).format_text();
+        assert!(single.starts_with("RustScrub: Comments Removed (Verbose Mode):\n- Line 1: Removed line comment.\n---"));
+    }
+}
@@ -0,0 +1,421 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/walk.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::glob::Glob;
+use crate::modgraph::{crate_files, crate_roots, included_files};
+use crate::remote::is_url;
+
+const BUILTIN_TYPES: &[(&str, &[&str])] = &[("rust", &["*.rs"])];
+
+const INCLUDE_LANGUAGES: &[(&str, &[&str])] = &[
+    ("Rust", &["rs"]),
+    ("C", &["c", "h"]),
+    ("C++", &["cc", "cpp", "cxx", "hh", "hpp", "hxx"]),
+    ("C#", &["cs"]),
+    ("Java", &["java"]),
+    ("GLSL", &["glsl", "vert", "frag", "comp", "geom", "tesc", "tese"]),
+    ("HLSL", &["hlsl"]),
+    ("WGSL", &["wgsl"]),
+    ("Protocol Buffers", &["proto"]),
+];
+
+pub fn is_rust_source(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "rs")
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct WalkArgs {
+    #[clap(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub follow_symlinks: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub hidden: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub follow_mods: bool,
+
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub follow_includes: bool,
+
+    #[clap(long = "include", value_name = "GLOB")]
+    pub includes: Vec<String>,
+
+    #[clap(long = "exclude", value_name = "GLOB")]
+    pub excludes: Vec<String>,
+
+    #[clap(long = "type", value_name = "TYPE")]
+    pub types: Vec<String>,
+
+    #[clap(long = "type-not", value_name = "TYPE")]
+    pub types_not: Vec<String>,
+
+    #[clap(long = "type-add", value_name = "NAME:GLOB")]
+    pub type_adds: Vec<String>,
+}
+
+fn file_types(type_adds: &[String]) -> Result<Vec<(String, Vec<String>)>, String> {
+    let mut types: Vec<(String, Vec<String>)> = BUILTIN_TYPES
+        .iter()
+        .map(|(name, globs)| (name.to_string(), globs.iter().map(|glob| glob.to_string()).collect()))
+        .collect();
+    for definition in type_adds {
+        let (name, glob) = definition
+            .split_once(':')
+            .filter(|(name, glob)| !name.is_empty() && !glob.is_empty())
+            .ok_or_else(|| format!("Invalid --type-add '{}'. Expected NAME:GLOB, e.g. 'build:build.rs'.", definition))?;
+        match types.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, globs)) => globs.push(glob.to_string()),
+            None => types.push((name.to_string(), vec![glob.to_string()])),
+        }
+    }
+    Ok(types)
+}
+
+fn type_globs(types: &[(String, Vec<String>)], names: &[String]) -> Result<Vec<Glob>, String> {
+    let mut globs = Vec::new();
+    for name in names {
+        let (_, patterns) = types.iter().find(|(existing, _)| existing == name).ok_or_else(|| {
+            let known: Vec<&str> = types.iter().map(|(name, _)| name.as_str()).collect();
+            format!("Unknown file type '{}'. Known types: {}.", name, known.join(", "))
+        })?;
+        globs.extend(patterns.iter().map(|pattern| Glob::new(pattern)));
+    }
+    Ok(globs)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum FileId {
+    #[cfg(unix)]
+    Inode(u64, u64),
+    #[cfg(not(unix))]
+    Path(PathBuf),
+}
+
+fn file_id(path: &Path) -> Option<FileId> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        fs::metadata(path).ok().map(|metadata| FileId::Inode(metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        fs::canonicalize(path).ok().map(FileId::Path)
+    }
+}
+
+struct Walker<'a> {
+    options: &'a WalkArgs,
+    visit: &'a mut dyn FnMut(PathBuf) -> bool,
+    stopped: bool,
+    visited: HashSet<PathBuf>,
+    seen: HashSet<FileId>,
+    includes: Vec<Glob>,
+    excludes: Vec<Glob>,
+    types: Vec<Glob>,
+    types_not: Vec<Glob>,
+}
+
+impl<'a> Walker<'a> {
+    fn new(options: &'a WalkArgs, visit: &'a mut dyn FnMut(PathBuf) -> bool) -> Result<Self, String> {
+        let file_types = file_types(&options.type_adds)?;
+        Ok(Walker {
+            options,
+            visit,
+            stopped: false,
+            visited: HashSet::new(),
+            seen: HashSet::new(),
+            includes: options.includes.iter().map(|pattern| Glob::new(pattern)).collect(),
+            excludes: options.excludes.iter().map(|pattern| Glob::new(pattern)).collect(),
+            types: type_globs(&file_types, &options.types)?,
+            types_not: type_globs(&file_types, &options.types_not)?,
+        })
+    }
+
+    fn is_selected(&self, relative: &Path) -> bool {
+        let relative = relative.to_string_lossy();
+        let matches_any = |globs: &[Glob]| globs.iter().any(|glob| glob.is_match(&relative));
+        (self.includes.is_empty() || matches_any(&self.includes))
+            && !matches_any(&self.excludes)
+            && (self.types.is_empty() || matches_any(&self.types))
+            && !matches_any(&self.types_not)
+    }
+
+    fn is_candidate(&self, path: &Path) -> bool {
+        is_rust_source(path) || (!self.types.is_empty() && include_language(path).is_some())
+    }
+
+    fn emit(&mut self, path: PathBuf) -> Result<(), String> {
+        if self.stopped || file_id(&path).is_some_and(|id| !self.seen.insert(id)) {
+            return Ok(());
+        }
+        let follow = self.options.follow_includes && is_rust_source(&path) && !is_url(&path.to_string_lossy());
+        if !(self.visit)(path.clone()) {
+            self.stopped = true;
+            return Ok(());
+        }
+        if follow {
+            self.follow_includes(&path)?;
+        }
+        Ok(())
+    }
+
+    fn follow_includes(&mut self, file: &Path) -> Result<(), String> {
+        let source = fs::read_to_string(file).map_err(|e| format!("Failed to read '{}': {}", file.display(), e))?;
+        for included in included_files(file, &source) {
+            if !included.is_file() {
+                eprintln!("RustScrub: Skipping '{}' included from '{}': file not found.", included.display(), file.display());
+                continue;
+            }
+            if include_language(&included).is_none() {
+                if file_id(&included).is_none_or(|id| self.seen.insert(id)) {
+                    eprintln!(
+                        "RustScrub: Skipping '{}' included from '{}': not a language with // and /* */ comments.",
+                        included.display(),
+                        file.display()
+                    );
+                }
+                continue;
+            }
+            self.emit(included)?;
+        }
+        Ok(())
+    }
+
+    fn walk_directory(&mut self, root: &Path, dir: &Path, depth: usize) -> Result<(), String> {
+        if self.options.max_depth.is_some_and(|max_depth| depth > max_depth) {
+            return Ok(());
+        }
+        if self.options.follow_symlinks {
+            let canonical = fs::canonicalize(dir)
+                .map_err(|e| format!("Failed to resolve directory '{}': {}", dir.display(), e))?;
+            if !self.visited.insert(canonical) {
+                eprintln!("RustScrub: Skipping '{}': directory cycle detected.", dir.display());
+                return Ok(());
+            }
+        }
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read directory '{}': {}", dir.display(), e))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read directory entry in '{}': {}", dir.display(), e))?;
+        entries.sort();
+
+        for path in entries {
+            if self.stopped {
+                break;
+            }
+            let is_symlink = path.symlink_metadata().is_ok_and(|metadata| metadata.file_type().is_symlink());
+            if (is_symlink && !self.options.follow_symlinks) || (!self.options.hidden && is_hidden(&path)) {
+                continue;
+            }
+            if path.is_dir() {
+                self.walk_directory(root, &path, depth + 1)?;
+            } else if path.is_file() && self.is_candidate(&path) && self.is_selected(path.strip_prefix(root).unwrap_or(&path)) {
+                self.emit(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn include_language(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    INCLUDE_LANGUAGES
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map(|(language, _)| *language)
+}
+
+pub fn walk_source_files(inputs: &[String], options: &WalkArgs, visit: &mut dyn FnMut(PathBuf) -> bool) -> Result<(), String> {
+    if let Some(missing) = inputs.iter().find(|input| !is_url(input) && !Path::new(input).exists()) {
+        return Err(format!("Input path '{}' does not exist.", missing));
+    }
+    let mut walker = Walker::new(options, visit)?;
+    for input in inputs {
+        if walker.stopped {
+            break;
+        }
+        if is_url(input) {
+            walker.emit(PathBuf::from(input))?;
+            continue;
+        }
+        let path = Path::new(input);
+        if options.follow_mods && path.is_dir() {
+            let roots = crate_roots(path);
+            if roots.is_empty() {
+                return Err(format!("No crate root (src/lib.rs, src/main.rs or src/bin/*.rs) found in '{}'.", input));
+            }
+            for file in crate_files(&roots)? {
+                if walker.is_selected(file.strip_prefix(path).unwrap_or(&file)) {
+                    walker.emit(file)?;
+                }
+            }
+        } else if options.follow_mods && is_rust_source(path) {
+            let dir = path.parent().unwrap_or(Path::new(""));
+            for (index, file) in crate_files(&[path.to_path_buf()])?.into_iter().enumerate() {
+                if index == 0 || walker.is_selected(file.strip_prefix(dir).unwrap_or(&file)) {
+                    walker.emit(file)?;
+                }
+            }
+        } else if path.is_dir() {
+            walker.walk_directory(path, path, 1)?;
+        } else {
+            walker.emit(path.to_path_buf())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn collect_source_files(inputs: &[String], options: &WalkArgs) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    walk_source_files(inputs, options, &mut |path| {
+        files.push(path);
+        true
+    })?;
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_inputs_are_deduplicated() {
+        let inputs = vec!["src".to_string(), "./src/walk.rs".to_string(), "src/../src/walk.rs".to_string()];
+        let files = collect_source_files(&inputs, &WalkArgs::default()).unwrap();
+        let walk_files: Vec<&PathBuf> = files.iter().filter(|path| path.ends_with("walk.rs")).collect();
+        assert_eq!(walk_files, vec![&PathBuf::from("src/walk.rs")]);
+    }
+
+    #[test]
+    fn test_walk_streams_until_the_visitor_stops() {
+        let mut visited = Vec::new();
+        walk_source_files(&["src".to_string()], &WalkArgs::default(), &mut |path| {
+            visited.push(path);
+            visited.len() < 2
+        })
+        .unwrap();
+        assert_eq!(visited.len(), 2);
+
+        let inputs = vec!["src".to_string(), "missing.rs".to_string()];
+        let error = walk_source_files(&inputs, &WalkArgs::default(), &mut |_| panic!("walked before validating inputs")).unwrap_err();
+        assert_eq!(error, "Input path 'missing.rs' does not exist.");
+    }
+
+    #[test]
+    fn test_follow_includes_adds_files_with_known_comment_syntax() {
+        let root = std::env::temp_dir().join(format!("rustscrub-walk-includes-{}", std::process::id()));
+        fs::create_dir_all(root.join("shaders")).unwrap();
+        fs::write(root.join("lib.rs"), "include!(\"table.rs\");\nconst S: &str = include_str!(\"shaders/blur.wgsl\");\nconst T: &str = include_str!(\"notes.txt\");\n").unwrap();
+        fs::write(root.join("table.rs"), "const U: &str = include_str!(\"shaders/blur.wgsl\");\n").unwrap();
+        fs::write(root.join("shaders/blur.wgsl"), "// blur\n").unwrap();
+        fs::write(root.join("notes.txt"), "// not code\n").unwrap();
+        let inputs = vec![root.join("lib.rs").to_string_lossy().into_owned()];
+
+        let files = collect_source_files(&inputs, &WalkArgs { follow_includes: true, ..WalkArgs::default() }).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(files, vec![root.join("lib.rs"), root.join("table.rs"), root.join("shaders/blur.wgsl")]);
+        assert_eq!(include_language(Path::new("a.FRAG")), Some("GLSL"));
+    }
+
+    #[test]
+    fn test_hidden_entries_need_opt_in() {
+        let root = std::env::temp_dir().join(format!("rustscrub-walk-hidden-{}", std::process::id()));
+        fs::create_dir_all(root.join(".cargo")).unwrap();
+        fs::write(root.join(".cargo/build.rs"), "").unwrap();
+        fs::write(root.join(".hidden.rs"), "").unwrap();
+        fs::write(root.join("lib.rs"), "").unwrap();
+        let inputs = vec![root.to_string_lossy().into_owned()];
+
+        let visible = collect_source_files(&inputs, &WalkArgs::default()).unwrap();
+        let all = collect_source_files(&inputs, &WalkArgs { hidden: true, ..WalkArgs::default() }).unwrap();
+        let explicit = collect_source_files(&[root.join(".hidden.rs").to_string_lossy().into_owned()], &WalkArgs::default()).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(visible, vec![root.join("lib.rs")]);
+        assert_eq!(all.len(), 3);
+        assert_eq!(explicit, vec![root.join(".hidden.rs")]);
+    }
+
+    #[test]
+    fn test_include_and_exclude_globs() {
+        let options = WalkArgs {
+            includes: vec!["src/**".to_string()],
+            excludes: vec!["*_generated.rs".to_string(), "src/bin/**".to_string()],
+            ..WalkArgs::default()
+        };
+        let files = collect_source_files(&[".".to_string()], &options).unwrap();
+        assert!(files.contains(&PathBuf::from("./src/walk.rs")));
+        assert!(files.iter().all(|path| path.starts_with("./src")));
+
+        let mut visit = |_| true;
+        let walker = Walker::new(&options, &mut visit).unwrap();
+        assert!(walker.is_selected(Path::new("src/lib.rs")));
+        assert!(!walker.is_selected(Path::new("src/schema_generated.rs")));
+        assert!(!walker.is_selected(Path::new("src/bin/tool.rs")));
+        assert!(!walker.is_selected(Path::new("benches/speed.rs")));
+    }
+
+    #[test]
+    fn test_types_select_files_of_other_languages() {
+        let root = std::env::temp_dir().join(format!("rustscrub-walk-types-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        for name in ["a.rs", "b.c", "c.h", "notes.txt"] {
+            fs::write(root.join(name), "").unwrap();
+        }
+        let inputs = vec![root.to_string_lossy().into_owned()];
+        let walk = |types: &[&str], types_not: &[&str]| {
+            let options = WalkArgs {
+                types: types.iter().map(|name| name.to_string()).collect(),
+                types_not: types_not.iter().map(|name| name.to_string()).collect(),
+                type_adds: vec!["c:*.c".to_string(), "c:*.h".to_string(), "text:*.txt".to_string()],
+                ..WalkArgs::default()
+            };
+            collect_source_files(&inputs, &options).unwrap()
+        };
+
+        let c_only = walk(&["c"], &[]);
+        let c_and_rust = walk(&["rust", "c"], &[]);
+        let not_c = walk(&[], &["c"]);
+        let text = walk(&["text"], &[]);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(c_only, vec![root.join("b.c"), root.join("c.h")]);
+        assert_eq!(c_and_rust, vec![root.join("a.rs"), root.join("b.c"), root.join("c.h")]);
+        assert_eq!(not_c, vec![root.join("a.rs")]);
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn test_type_filters() {
+        let options = WalkArgs {
+            types: vec!["rust".to_string()],
+            types_not: vec!["build".to_string()],
+            type_adds: vec!["build:build.rs".to_string(), "build:build/**".to_string()],
+            ..WalkArgs::default()
+        };
+        let mut visit = |_| true;
+        let walker = Walker::new(&options, &mut visit).unwrap();
+        assert!(walker.is_selected(Path::new("src/lib.rs")));
+        assert!(!walker.is_selected(Path::new("build.rs")));
+        assert!(!walker.is_selected(Path::new("build/gen.rs")));
+
+        let unknown = WalkArgs { types: vec!["go".to_string()], ..WalkArgs::default() };
+        assert_eq!(Walker::new(&unknown, &mut visit).err().unwrap(), "Unknown file type 'go'. Known types: rust.");
+        let invalid = WalkArgs { type_adds: vec!["build".to_string()], ..WalkArgs::default() };
+        assert!(Walker::new(&invalid, &mut visit).err().unwrap().starts_with("Invalid --type-add 'build'"));
+    }
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MIT
+// Project: rustscrub
+// Description: A program to remove comments from source files.
+// File: src/wasm.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2025 Volker Schwaberow
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::inventory::InventoryEntry;
+use crate::options::ScrubOptions;
+use crate::scrub::scrub_text;
+
+#[derive(Debug, Serialize)]
+struct ScrubOutput {
+    text: String,
+    changes: Vec<InventoryEntry>,
+}
+
+#[wasm_bindgen]
+pub fn scrub(text: &str, options_json: &str) -> Result<JsValue, JsError> {
+    let settings = ScrubOptions::from_json(options_json)
+        .map_err(|e| JsError::new(&e))?
+        .settings();
+    let (output, report) = scrub_text(text, &settings).map_err(|e| JsError::new(&e))?;
+    let result = ScrubOutput {
+        text: output,
+        changes: InventoryEntry::entries(&report.changes),
+    };
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsError::new(&e.to_string()))
+}